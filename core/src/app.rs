@@ -1,9 +1,17 @@
-use gtk4::{Application, prelude::*};
+use gtk4::{Application, glib, prelude::*};
 use libadwaita as adw;
 use adw::prelude::*;
 
-use crate::ui::MainWindow;
-use crate::module_loader::ModuleManager;
+use crate::ui::{show_toast, MainWindow, SystemView, TaskManagerView, DiskAnalyzerView, NetworkView,
+                PackageManagerView, ServiceManagerView, StartupManagerView, SystemCleanerView};
+use crate::module_loader::{Module, ModuleManager};
+use crate::auto_clean;
+use crate::config::Settings;
+use crate::system_info::SystemInfo;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::Duration;
 
 pub struct HealthCenterApp {
     window: adw::ApplicationWindow,
@@ -19,19 +27,94 @@ impl HealthCenterApp {
             .title("SysMate")
             .build();
 
-        // Create module manager
-        let module_manager = ModuleManager::new();
+        // Load preferences and apply the color scheme
+        let settings = Rc::new(RefCell::new(Settings::load()));
+        adw::StyleManager::default().set_color_scheme(settings.borrow().color_scheme.to_adw());
+
+        // CPU, memory and process figures, shared by the views that show them
+        let system_info = Rc::new(RefCell::new(SystemInfo::new()));
+
+        // Register modules in sidebar order
+        let mut module_manager = ModuleManager::new();
+        let modules: Vec<Box<dyn Module>> = vec![
+            Box::new(SystemView::new(system_info.clone())),
+            Box::new(TaskManagerView::new(settings.clone(), system_info.clone())),
+            Box::new(DiskAnalyzerView::new()),
+            Box::new(NetworkView::new()),
+            Box::new(PackageManagerView::new(settings.clone())),
+            Box::new(ServiceManagerView::new()),
+            Box::new(StartupManagerView::new(system_info)),
+            Box::new(SystemCleanerView::new(settings.clone())),
+        ];
+        for module in modules {
+            let id = module.id().to_string();
+            if let Err(e) = module_manager.register_module(module) {
+                tracing::error!("Failed to load module '{}': {}", id, e);
+            }
+        }
 
         // Build the UI
-        let main_window = MainWindow::new();
+        let main_window = MainWindow::new(&module_manager, &settings);
         window.set_content(Some(&main_window.build()));
 
+        // Clean in the background if the auto-clean policy is due
+        Self::start_auto_clean(&settings, &main_window.toast_overlay());
+
+        // Save preferences on shutdown
+        window.connect_close_request(move |_| {
+            settings.borrow().save_or_warn();
+            glib::Propagation::Proceed
+        });
+
         Self {
             window,
             _module_manager: module_manager,
         }
     }
 
+    /// Run the auto-clean policy on a worker thread and report the result as a toast
+    /// 
+    /// Nothing runs in safe mode, since auto-clean has nowhere to show a simulation.
+    fn start_auto_clean(settings: &Rc<RefCell<Settings>>, toast_overlay: &adw::ToastOverlay) {
+        let (categories, exclusions) = {
+            let mut settings = settings.borrow_mut();
+            let policy = &settings.auto_clean;
+            let now = auto_clean::now();
+            let free_bytes = policy.min_free_gb.and_then(|_| auto_clean::home_free_space());
+            if settings.safe_mode || !policy.is_due(now, free_bytes) {
+                return;
+            }
+
+            let categories = policy.cleanup_categories();
+            if categories.is_empty() {
+                return;
+            }
+
+            // Record the run up front, so a crash or dismissed password prompt doesn't repeat it on every start
+            settings.auto_clean.last_run = Some(now);
+            settings.save_or_warn();
+            (categories, settings.cleanup_exclusions.clone())
+        };
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let results = auto_clean::run(&categories, &exclusions);
+            let _ = tx.send((categories, results));
+        });
+
+        let toast_overlay = toast_overlay.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            match rx.try_recv() {
+                Ok((categories, results)) => {
+                    show_toast(&toast_overlay, &auto_clean::summary(&categories, &results));
+                    glib::ControlFlow::Break
+                }
+                Err(TryRecvError::Empty) => glib::ControlFlow::Continue,
+                Err(TryRecvError::Disconnected) => glib::ControlFlow::Break,
+            }
+        });
+    }
+
     pub fn show(&self) {
         self.window.present();
     }
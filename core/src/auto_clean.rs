@@ -0,0 +1,103 @@
+//! Background cleanup on startup
+//! 
+//! Runs the categories of the auto-clean policy (see `config::AutoCleanPolicy`)
+//! and appends what was cleaned to `~/.local/share/sysmate/auto-clean.log`,
+//! one JSON object per line.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use system_cleaner::{clean_categories, format_size, AuthenticationDismissed, CleanupCategory};
+
+/// One cleaned category in the auto-clean log
+#[derive(Debug, Serialize)]
+struct LogEntry<'a> {
+    /// Seconds since the Unix epoch
+    time: u64,
+    category: &'a str,
+    freed: Option<u64>,
+    error: Option<String>,
+}
+
+/// Seconds since the Unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Free space on the filesystem holding the home directory
+pub fn home_free_space() -> Option<u64> {
+    let home = PathBuf::from(std::env::var("HOME").ok()?);
+    disk_analyzer::get_mount_points()
+        .ok()?
+        .into_iter()
+        .filter(|mount| home.starts_with(&mount.mount_point))
+        .max_by_key(|mount| mount.mount_point.as_os_str().len())
+        .map(|mount| mount.available)
+}
+
+/// Location of the auto-clean log
+pub fn log_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("sysmate").join("auto-clean.log"))
+}
+
+/// Clean the given categories and record the outcome in the log
+/// 
+/// Returns the bytes freed per category, in the order given. Failing to
+/// write the log is only logged, it doesn't fail the cleanup.
+pub fn run(categories: &[CleanupCategory], exclusions: &[String]) -> Vec<Result<u64>> {
+    let results = clean_categories(categories, exclusions);
+    if let Err(e) = record(categories, &results) {
+        tracing::warn!("Failed to record auto-clean: {:#}", e);
+    }
+    results
+}
+
+/// Append one line per category to the auto-clean log
+fn record(categories: &[CleanupCategory], results: &[Result<u64>]) -> Result<()> {
+    let path = log_path().context("Could not determine the data directory")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).context(format!("Failed to create {:?}", dir))?;
+    }
+    
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .context(format!("Failed to open {:?}", path))?;
+    
+    let time = now();
+    for (category, result) in categories.iter().zip(results) {
+        let entry = LogEntry {
+            time,
+            category: category.name(),
+            freed: result.as_ref().ok().copied(),
+            error: result.as_ref().err().map(|e| format!("{:#}", e)),
+        };
+        writeln!(file, "{}", serde_json::to_string(&entry)?)
+            .context(format!("Failed to write {:?}", path))?;
+    }
+    
+    Ok(())
+}
+
+/// One-line summary of an auto-clean run, for a toast
+pub fn summary(categories: &[CleanupCategory], results: &[Result<u64>]) -> String {
+    let freed: u64 = results.iter().flatten().sum();
+    let failed: Vec<&str> = categories.iter()
+        .zip(results)
+        .filter(|(_, result)| result.as_ref().is_err_and(|e| !e.is::<AuthenticationDismissed>()))
+        .map(|(category, _)| category.name())
+        .collect();
+    
+    if failed.is_empty() {
+        format!("Auto-clean freed {}", format_size(freed))
+    } else {
+        format!("Auto-clean freed {}, failed to clean {}", format_size(freed), failed.join(", "))
+    }
+}
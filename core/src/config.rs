@@ -0,0 +1,246 @@
+//! Persistent UI preferences
+//! 
+//! Settings are stored as TOML in `~/.config/sysmate/config.toml`. A missing
+//! or unreadable file falls back to the defaults, so a broken config never
+//! prevents the app from starting.
+
+use anyhow::{Context, Result};
+use libadwaita as adw;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use system_cleaner::CleanupCategory;
+
+/// Auto-clean runs at most this often with the weekly trigger
+const WEEK_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Preferred light/dark appearance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorScheme {
+    /// Follow the desktop's preference
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl ColorScheme {
+    /// The scheme after this one when cycling System → Light → Dark
+    pub fn next(self) -> Self {
+        match self {
+            ColorScheme::System => ColorScheme::Light,
+            ColorScheme::Light => ColorScheme::Dark,
+            ColorScheme::Dark => ColorScheme::System,
+        }
+    }
+    
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorScheme::System => "Follow System",
+            ColorScheme::Light => "Light",
+            ColorScheme::Dark => "Dark",
+        }
+    }
+    
+    pub fn icon_name(self) -> &'static str {
+        match self {
+            ColorScheme::System => "emblem-system-symbolic",
+            ColorScheme::Light => "weather-clear-symbolic",
+            ColorScheme::Dark => "weather-clear-night-symbolic",
+        }
+    }
+    
+    pub fn to_adw(self) -> adw::ColorScheme {
+        match self {
+            ColorScheme::System => adw::ColorScheme::Default,
+            ColorScheme::Light => adw::ColorScheme::ForceLight,
+            ColorScheme::Dark => adw::ColorScheme::ForceDark,
+        }
+    }
+}
+
+/// When auto-clean runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoCleanTrigger {
+    /// Every time the app starts
+    #[default]
+    OnStartup,
+    /// On the first start at least a week after the last run
+    Weekly,
+}
+
+/// A cleanup category auto-clean may run, as named in the config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoCleanCategory {
+    Thumbnails,
+    Trash,
+    BrowserCache,
+    PackageCache,
+    Logs,
+    RotatedLogs,
+    OldKernels,
+    TempFiles,
+    ContainerCache,
+}
+
+impl AutoCleanCategory {
+    pub fn to_cleanup(self) -> CleanupCategory {
+        match self {
+            AutoCleanCategory::Thumbnails => CleanupCategory::Thumbnails,
+            AutoCleanCategory::Trash => CleanupCategory::Trash,
+            AutoCleanCategory::BrowserCache => CleanupCategory::BrowserCache,
+            AutoCleanCategory::PackageCache => CleanupCategory::PackageCache,
+            AutoCleanCategory::Logs => CleanupCategory::Logs,
+            AutoCleanCategory::RotatedLogs => CleanupCategory::RotatedLogs,
+            AutoCleanCategory::OldKernels => CleanupCategory::OldKernels,
+            AutoCleanCategory::TempFiles => CleanupCategory::TempFiles,
+            AutoCleanCategory::ContainerCache => CleanupCategory::ContainerCache,
+        }
+    }
+}
+
+/// Cleanup run in the background when the app starts
+/// 
+/// Off by default. Categories that need root are skipped unless
+/// `allow_privileged` is set, so auto-clean never asks for a password by surprise.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoCleanPolicy {
+    pub enabled: bool,
+    pub categories: Vec<AutoCleanCategory>,
+    pub trigger: AutoCleanTrigger,
+    /// Only clean when the home filesystem has less than this many GB free
+    pub min_free_gb: Option<u64>,
+    /// Also run categories that delete as root
+    pub allow_privileged: bool,
+    /// When auto-clean last ran, in seconds since the Unix epoch
+    pub last_run: Option<u64>,
+}
+
+impl Default for AutoCleanPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            categories: vec![
+                AutoCleanCategory::Thumbnails,
+                AutoCleanCategory::Trash,
+                AutoCleanCategory::BrowserCache,
+            ],
+            trigger: AutoCleanTrigger::default(),
+            min_free_gb: None,
+            allow_privileged: false,
+            last_run: None,
+        }
+    }
+}
+
+impl AutoCleanPolicy {
+    /// The categories to clean, without those needing root unless allowed
+    pub fn cleanup_categories(&self) -> Vec<CleanupCategory> {
+        let mut categories: Vec<CleanupCategory> = Vec::new();
+        for category in self.categories.iter().map(|c| c.to_cleanup()) {
+            if (self.allow_privileged || !category.requires_root()) && !categories.contains(&category) {
+                categories.push(category);
+            }
+        }
+        categories
+    }
+    
+    /// Whether auto-clean should run at `now` (seconds since the Unix epoch)
+    /// 
+    /// `free_bytes` is the free space on the home filesystem. With a
+    /// `min_free_gb` threshold and no known free space, nothing is cleaned.
+    pub fn is_due(&self, now: u64, free_bytes: Option<u64>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        
+        let triggered = match self.trigger {
+            AutoCleanTrigger::OnStartup => true,
+            AutoCleanTrigger::Weekly => self.last_run.is_none_or(|last| now.saturating_sub(last) >= WEEK_SECS),
+        };
+        let low_on_space = match self.min_free_gb {
+            Some(gb) => free_bytes.is_some_and(|free| free < gb.saturating_mul(1 << 30)),
+            None => true,
+        };
+        
+        triggered && low_on_space
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub color_scheme: ColorScheme,
+    /// Id of the module shown when the app was last closed
+    pub last_module: Option<String>,
+    /// Task manager refresh interval in seconds, 0 when paused
+    pub process_refresh_secs: u64,
+    /// Simulate package and cleanup actions instead of running them
+    pub safe_mode: bool,
+    /// Paths or glob patterns the system cleaner keeps, e.g. `~/.cache/mozilla/firefox/*.default`
+    pub cleanup_exclusions: Vec<String>,
+    pub auto_clean: AutoCleanPolicy,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            color_scheme: ColorScheme::default(),
+            last_module: None,
+            process_refresh_secs: 2,
+            safe_mode: false,
+            cleanup_exclusions: Vec::new(),
+            auto_clean: AutoCleanPolicy::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Location of the config file
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("sysmate").join("config.toml"))
+    }
+    
+    /// Load settings, falling back to the defaults if the file is missing or invalid
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+        
+        match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid config {:?}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+    
+    /// Save settings, creating the config directory if needed
+    /// 
+    /// Writes to a temporary file first so a crash can't leave a truncated config.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path().context("Could not determine the config directory")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).context(format!("Failed to create {:?}", dir))?;
+        }
+        
+        let contents = toml::to_string(self).context("Failed to serialize settings")?;
+        let temp_path = path.with_extension("toml.tmp");
+        fs::write(&temp_path, contents).context(format!("Failed to write {:?}", temp_path))?;
+        fs::rename(&temp_path, &path).context(format!("Failed to replace {:?}", path))?;
+        
+        Ok(())
+    }
+    
+    /// Save settings, logging instead of failing
+    pub fn save_or_warn(&self) {
+        if let Err(e) = self.save() {
+            tracing::warn!("Failed to save settings: {:#}", e);
+        }
+    }
+}
@@ -6,6 +6,12 @@ mod app;
 mod module_loader;
 mod ui;
 mod system_info;
+mod process_manager;
+mod power;
+mod search;
+mod report;
+mod config;
+mod auto_clean;
 
 use app::HealthCenterApp;
 
@@ -31,10 +37,6 @@ fn build_ui(app: &Application) {
     // Initialize Libadwaita
     adw::init().expect("Failed to initialize Libadwaita");
 
-    // Enable dark theme
-    let style_manager = adw::StyleManager::default();
-    style_manager.set_color_scheme(adw::ColorScheme::PreferDark);
-
     // Create and show the main application
     let health_app = HealthCenterApp::new(app);
     health_app.show();
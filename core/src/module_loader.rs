@@ -1,46 +1,57 @@
-use std::collections::HashMap;
 use anyhow::Result;
 
-/// Trait that all modules must implement (reserved for future dynamic module loading)
-#[allow(dead_code)]
-pub trait Module: Send + Sync {
+/// Trait that all modules must implement
+///
+/// Each module contributes one sidebar row and one stack page.
+pub trait Module {
+    /// Stable identifier, used as the stack page name
+    fn id(&self) -> &str;
     fn name(&self) -> &str;
     fn description(&self) -> &str;
     fn icon_name(&self) -> &str;
-    fn initialize(&mut self) -> Result<()>;
+
+    /// Prepare the module before its page is first shown
+    fn initialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// The module's page
+    fn widget(&self) -> gtk4::Widget;
 }
 
-/// Manages all loaded modules (reserved for future dynamic module loading)
+/// Manages all loaded modules, in registration order
 pub struct ModuleManager {
-    #[allow(dead_code)]
-    modules: HashMap<String, Box<dyn Module>>,
+    modules: Vec<Box<dyn Module>>,
 }
 
 impl ModuleManager {
     pub fn new() -> Self {
         Self {
-            modules: HashMap::new(),
+            modules: Vec::new(),
         }
     }
 
-    /// Register a new module
-    #[allow(dead_code)]
-    pub fn register_module(&mut self, module: Box<dyn Module>) -> Result<()> {
-        let name = module.name().to_string();
-        self.modules.insert(name, module);
+    /// Register a new module and initialize it
+    ///
+    /// Fails if a module with the same id is already registered.
+    pub fn register_module(&mut self, mut module: Box<dyn Module>) -> Result<()> {
+        if self.get_module(module.id()).is_some() {
+            anyhow::bail!("Module '{}' is already registered", module.id());
+        }
+
+        module.initialize()?;
+        self.modules.push(module);
         Ok(())
     }
 
-    /// Get all registered modules
-    #[allow(dead_code)]
-    pub fn get_modules(&self) -> Vec<&str> {
-        self.modules.keys().map(|s| s.as_str()).collect()
+    /// Get all registered modules, in registration order
+    pub fn get_modules(&self) -> impl Iterator<Item = &dyn Module> {
+        self.modules.iter().map(|module| module.as_ref())
     }
 
-    /// Get a specific module by name
-    #[allow(dead_code)]
-    pub fn get_module(&self, name: &str) -> Option<&Box<dyn Module>> {
-        self.modules.get(name)
+    /// Get a specific module by id
+    pub fn get_module(&self, id: &str) -> Option<&dyn Module> {
+        self.get_modules().find(|module| module.id() == id)
     }
 }
 
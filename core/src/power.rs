@@ -0,0 +1,139 @@
+//! Screen brightness and power profiles
+//! 
+//! Brightness is read from the kernel's backlight devices and written through
+//! the privileged helper. Power profiles come from power-profiles-daemon via
+//! `powerprofilesctl`, which needs no privileges for the active session.
+
+use anyhow::{Context, Result};
+use privileged::{HelperCommand, BACKLIGHT_DIR};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// A display backlight
+#[derive(Debug, Clone)]
+pub struct Backlight {
+    /// Device name in /sys/class/backlight, e.g. `intel_backlight`
+    pub device: String,
+    pub brightness: u32,
+    pub max_brightness: u32,
+}
+
+impl Backlight {
+    /// Current brightness in percent of the maximum
+    pub fn percent(&self) -> f64 {
+        self.brightness as f64 / self.max_brightness as f64 * 100.0
+    }
+}
+
+/// Get the backlight of the built-in display
+/// 
+/// With several devices, firmware interfaces are preferred over platform and
+/// raw ones, as the kernel documentation recommends. Returns `None` on
+/// machines without a backlight, e.g. desktops.
+pub fn get_backlight() -> Option<Backlight> {
+    let entries = fs::read_dir(BACKLIGHT_DIR).ok()?;
+    
+    let read_value = |path: &Path, name: &str| -> Option<u32> {
+        fs::read_to_string(path.join(name)).ok()?.trim().parse().ok()
+    };
+    
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let max_brightness = read_value(&path, "max_brightness").filter(|max| *max > 0)?;
+            let priority = match fs::read_to_string(path.join("type")).unwrap_or_default().trim() {
+                "firmware" => 0,
+                "platform" => 1,
+                _ => 2,
+            };
+            let backlight = Backlight {
+                device: entry.file_name().to_string_lossy().to_string(),
+                brightness: read_value(&path, "brightness")?,
+                max_brightness,
+            };
+            Some((priority, backlight))
+        })
+        .min_by(|(a, a_light), (b, b_light)| a.cmp(b).then_with(|| a_light.device.cmp(&b_light.device)))
+        .map(|(_, backlight)| backlight)
+}
+
+/// Set the brightness of the built-in display in percent
+/// 
+/// Never goes fully dark, so the screen stays readable.
+pub fn set_backlight(percent: f64) -> Result<()> {
+    let backlight = get_backlight().context("No backlight found")?;
+    let brightness = (backlight.max_brightness as f64 * percent.clamp(1.0, 100.0) / 100.0).round() as u32;
+    
+    privileged::run_commands(&[HelperCommand::SetBacklight {
+        device: backlight.device,
+        brightness: brightness.max(1),
+    }])
+}
+
+/// The active power profile and the ones to choose from
+#[derive(Debug, Clone)]
+pub struct PowerProfiles {
+    /// e.g. `balanced`
+    pub active: String,
+    /// In the order `powerprofilesctl` lists them
+    pub available: Vec<String>,
+}
+
+/// Get the power profiles from power-profiles-daemon
+/// 
+/// Returns `None` when `powerprofilesctl` is missing or the daemon isn't running.
+pub fn get_power_profile() -> Option<PowerProfiles> {
+    let output = Command::new("powerprofilesctl").arg("list").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_power_profiles(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse `powerprofilesctl list`
+/// 
+/// Each profile is a `name:` line, marked with `*` when active, followed by
+/// indented details.
+fn parse_power_profiles(output: &str) -> Option<PowerProfiles> {
+    let mut active = None;
+    let mut available = Vec::new();
+    
+    for line in output.lines() {
+        let (is_active, rest) = if let Some(rest) = line.strip_prefix("* ") {
+            (true, rest)
+        } else if let Some(rest) = line.strip_prefix("  ") {
+            (false, rest)
+        } else {
+            continue;
+        };
+        let Some(name) = rest.strip_suffix(':') else {
+            continue;
+        };
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            continue;
+        }
+        
+        if is_active {
+            active = Some(name.to_string());
+        }
+        available.push(name.to_string());
+    }
+    
+    Some(PowerProfiles { active: active?, available })
+}
+
+/// Switch to another power profile
+pub fn set_power_profile(profile: &str) -> Result<()> {
+    let output = Command::new("powerprofilesctl")
+        .args(["set", profile])
+        .output()
+        .context("Failed to run powerprofilesctl")?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("powerprofilesctl set {} failed: {}", profile, stderr.trim());
+    }
+    Ok(())
+}
@@ -0,0 +1,292 @@
+//! Process control for the task manager
+//! 
+//! Sends signals to processes, escalating through pkexec when the process
+//! belongs to another user.
+
+use anyhow::{Context, Result};
+use nix::errno::Errno;
+use nix::sys::signal::{self, Signal};
+use nix::unistd::{Pid, Uid, User};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::process::Command;
+use sysinfo::{Process, ProcessStatus};
+
+#[derive(Debug, thiserror::Error)]
+#[error("Authentication was cancelled or not authorized")]
+pub struct AuthenticationDismissed;
+
+/// Details shown for a single process
+#[derive(Debug, Clone)]
+pub struct ProcessDetails {
+    /// Full command line, or the bracketed name for kernel threads
+    pub command_line: String,
+    /// Owning user name, or the numeric uid if it can't be resolved
+    pub user: String,
+    /// Start time in seconds since the Unix epoch
+    pub start_time: u64,
+    /// Number of open file descriptors, `None` if /proc/<pid>/fd is not readable
+    pub open_files: Option<usize>,
+}
+
+/// Collect the details of a process
+pub fn get_process_details(process: &Process) -> ProcessDetails {
+    let command_line = if process.cmd().is_empty() {
+        format!("[{}]", process.name())
+    } else {
+        process.cmd().join(" ")
+    };
+    
+    let user = match process.user_id() {
+        Some(uid) => {
+            let uid = Uid::from_raw(**uid);
+            User::from_uid(uid)
+                .ok()
+                .flatten()
+                .map(|user| user.name)
+                .unwrap_or_else(|| uid.to_string())
+        }
+        None => "Unknown".to_string(),
+    };
+    
+    // Other users' descriptors are only readable as root
+    let open_files = fs::read_dir(format!("/proc/{}/fd", process.pid()))
+        .ok()
+        .map(|entries| entries.count());
+    
+    ProcessDetails {
+        command_line,
+        user,
+        start_time: process.start_time(),
+        open_files,
+    }
+}
+
+/// Column the process list is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProcessSortKey {
+    #[default]
+    Cpu,
+    Memory,
+    Name,
+    Pid,
+}
+
+impl ProcessSortKey {
+    /// All keys, in the order they are offered in the UI
+    pub const ALL: [ProcessSortKey; 4] = [
+        ProcessSortKey::Cpu,
+        ProcessSortKey::Memory,
+        ProcessSortKey::Name,
+        ProcessSortKey::Pid,
+    ];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProcessSortKey::Cpu => "CPU",
+            ProcessSortKey::Memory => "Memory",
+            ProcessSortKey::Name => "Name",
+            ProcessSortKey::Pid => "PID",
+        }
+    }
+    
+    /// Compare two processes, busiest first for CPU and memory
+    /// 
+    /// Ties are broken by PID so the order doesn't shuffle between refreshes.
+    pub fn compare(&self, a: &Process, b: &Process) -> Ordering {
+        let ordering = match self {
+            ProcessSortKey::Cpu => b.cpu_usage().partial_cmp(&a.cpu_usage()).unwrap_or(Ordering::Equal),
+            ProcessSortKey::Memory => b.memory().cmp(&a.memory()),
+            ProcessSortKey::Name => a.name().to_lowercase().cmp(&b.name().to_lowercase()),
+            ProcessSortKey::Pid => Ordering::Equal,
+        };
+        ordering.then_with(|| a.pid().cmp(&b.pid()))
+    }
+    
+    /// Compare two aggregated applications, ordering by their lowest PID for `Pid`
+    pub fn compare_aggregated(&self, a: &AggregatedProcess, b: &AggregatedProcess) -> Ordering {
+        let ordering = match self {
+            ProcessSortKey::Cpu => b.cpu_usage.partial_cmp(&a.cpu_usage).unwrap_or(Ordering::Equal),
+            ProcessSortKey::Memory => b.memory.cmp(&a.memory),
+            ProcessSortKey::Name | ProcessSortKey::Pid => Ordering::Equal,
+        };
+        ordering
+            .then_with(|| match self {
+                ProcessSortKey::Pid => a.pids.first().cmp(&b.pids.first()),
+                _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            })
+            .then_with(|| a.name.cmp(&b.name))
+    }
+}
+
+/// Processes sharing a name, added up
+#[derive(Debug, Clone)]
+pub struct AggregatedProcess {
+    pub name: String,
+    /// Sum of the CPU usage of all instances, can exceed 100% on multi-core systems
+    pub cpu_usage: f32,
+    /// Sum of the resident memory of all instances in bytes
+    pub memory: u64,
+    /// Sum of the swapped out memory of all instances in bytes
+    pub swap: u64,
+    /// PIDs of all instances, lowest first
+    pub pids: Vec<sysinfo::Pid>,
+}
+
+impl AggregatedProcess {
+    pub fn count(&self) -> usize {
+        self.pids.len()
+    }
+}
+
+/// Sum CPU and memory usage of processes sharing a name
+/// 
+/// Threads are left out so they aren't counted twice. The result is sorted
+/// by memory usage, largest first.
+pub fn aggregate_by_name(processes: &HashMap<sysinfo::Pid, Process>) -> Vec<AggregatedProcess> {
+    let mut groups: HashMap<&str, AggregatedProcess> = HashMap::new();
+    
+    for process in processes.values().filter(|p| p.thread_kind().is_none()) {
+        let group = groups.entry(process.name()).or_insert_with(|| AggregatedProcess {
+            name: process.name().to_string(),
+            cpu_usage: 0.0,
+            memory: 0,
+            swap: 0,
+            pids: Vec::new(),
+        });
+        group.cpu_usage += process.cpu_usage();
+        group.memory += process.memory();
+        group.swap += get_process_swap(process.pid()).unwrap_or(0);
+        group.pids.push(process.pid());
+    }
+    
+    let mut aggregated: Vec<AggregatedProcess> = groups.into_values()
+        .map(|mut group| {
+            group.pids.sort();
+            group
+        })
+        .collect();
+    aggregated.sort_by(|a, b| ProcessSortKey::Memory.compare_aggregated(a, b));
+    aggregated
+}
+
+/// Swapped out memory of a process in bytes
+/// 
+/// Reads `VmSwap` from /proc/<pid>/status. Returns `None` for kernel threads
+/// and processes that have already exited.
+pub fn get_process_swap(pid: sysinfo::Pid) -> Option<u64> {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    parse_vm_swap(&status)
+}
+
+fn parse_vm_swap(status: &str) -> Option<u64> {
+    let value = status.lines().find_map(|line| line.strip_prefix("VmSwap:"))?;
+    let kib: u64 = value.trim().trim_end_matches("kB").trim().parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// A process and the processes it started
+#[derive(Debug, Clone)]
+pub struct ProcessNode {
+    pub pid: sysinfo::Pid,
+    pub children: Vec<ProcessNode>,
+}
+
+/// Arrange processes into trees by parent PID
+/// 
+/// Processes without a listed parent become roots. Threads are left out, and
+/// siblings are ordered by `sort_key`.
+pub fn build_process_tree(
+    processes: &HashMap<sysinfo::Pid, Process>,
+    sort_key: ProcessSortKey,
+) -> Vec<ProcessNode> {
+    let mut children: HashMap<sysinfo::Pid, Vec<&Process>> = HashMap::new();
+    let mut roots = Vec::new();
+    
+    for process in processes.values().filter(|p| p.thread_kind().is_none()) {
+        match process.parent().filter(|parent| processes.contains_key(parent)) {
+            Some(parent) => children.entry(parent).or_default().push(process),
+            None => roots.push(process),
+        }
+    }
+    
+    fn build_node(
+        process: &Process,
+        children: &HashMap<sysinfo::Pid, Vec<&Process>>,
+        sort_key: ProcessSortKey,
+    ) -> ProcessNode {
+        let mut kids = children.get(&process.pid()).cloned().unwrap_or_default();
+        kids.sort_by(|a, b| sort_key.compare(a, b));
+        
+        ProcessNode {
+            pid: process.pid(),
+            children: kids.into_iter()
+                .map(|child| build_node(child, children, sort_key))
+                .collect(),
+        }
+    }
+    
+    roots.sort_by(|a, b| sort_key.compare(a, b));
+    roots.into_iter()
+        .map(|root| build_node(root, &children, sort_key))
+        .collect()
+}
+
+/// Short description of a state that points at a stuck or leaked process
+/// 
+/// Only zombies and processes in uninterruptible sleep (D state) are
+/// flagged; a process stuck in D state usually waits on a hung disk or
+/// network mount.
+pub fn unusual_state(process: &Process) -> Option<&'static str> {
+    match process.status() {
+        ProcessStatus::Zombie => Some("Zombie"),
+        ProcessStatus::UninterruptibleDiskSleep => Some("Uninterruptible sleep"),
+        _ => None,
+    }
+}
+
+/// Processes that have exited but were not reaped by their parent, lowest PID first
+/// 
+/// Zombies can't be killed; they go away once their parent collects them or
+/// exits itself.
+pub fn get_zombie_processes(processes: &HashMap<sysinfo::Pid, Process>) -> Vec<&Process> {
+    let mut zombies: Vec<&Process> = processes.values()
+        .filter(|p| p.thread_kind().is_none() && p.status() == ProcessStatus::Zombie)
+        .collect();
+    zombies.sort_by_key(|p| p.pid());
+    zombies
+}
+
+/// Send a signal to a process
+/// 
+/// Signals the process directly first. If that is not permitted, e.g. for a
+/// root-owned process, the signal is sent with `pkexec kill` instead.
+pub fn kill_process(pid: Pid, signal: Signal) -> Result<()> {
+    match signal::kill(pid, signal) {
+        Ok(()) => Ok(()),
+        Err(Errno::ESRCH) => anyhow::bail!("No process with PID {} exists", pid),
+        Err(Errno::EPERM) => kill_privileged(pid, signal),
+        Err(e) => Err(e).context(format!("Failed to send {} to PID {}", signal, pid)),
+    }
+}
+
+fn kill_privileged(pid: Pid, signal: Signal) -> Result<()> {
+    let signal_name = signal.as_str().trim_start_matches("SIG");
+    let output = Command::new("pkexec")
+        .args(["kill", "-s", signal_name, &pid.to_string()])
+        .output()
+        .context("Failed to run pkexec")?;
+    
+    if output.status.success() {
+        return Ok(());
+    }
+    
+    match output.status.code() {
+        Some(126) | Some(127) => Err(AuthenticationDismissed.into()),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("kill -s {} {} failed ({}): {}", signal_name, pid, output.status, stderr.trim())
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! System report export
+//! 
+//! Gathers a snapshot of the system for support requests and renders it as
+//! Markdown or JSON. Each source is gathered independently; a source that
+//! fails is listed under `errors` instead of aborting the report.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::system_info::SystemInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Json,
+}
+
+impl ReportFormat {
+    /// Pick the format from a file's extension, defaulting to Markdown
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => ReportFormat::Json,
+            _ => ReportFormat::Markdown,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SystemReport {
+    system: SystemSection,
+    memory: MemorySection,
+    temperatures: Vec<TemperatureEntry>,
+    mounts: Vec<MountEntry>,
+    packages: Option<PackageSection>,
+    failed_services: Option<Vec<String>>,
+    /// Sources that could not be gathered, with the reason
+    errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SystemSection {
+    os: String,
+    hostname: String,
+    kernel: String,
+    uptime_seconds: u64,
+    cpu: String,
+    cpu_cores: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct MemorySection {
+    total_bytes: u64,
+    used_bytes: u64,
+    available_bytes: u64,
+    swap_total_bytes: u64,
+    swap_used_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct TemperatureEntry {
+    sensor: String,
+    celsius: f32,
+    critical_celsius: Option<f32>,
+}
+
+#[derive(Debug, Serialize)]
+struct MountEntry {
+    device: String,
+    mount_point: String,
+    fs_type: String,
+    total_bytes: u64,
+    used_bytes: u64,
+    available_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct PackageSection {
+    backend: String,
+    installed: usize,
+    upgradeable: usize,
+    auto_removable: usize,
+    held: usize,
+}
+
+/// Generate a system report in the given format
+pub fn generate_report(format: ReportFormat) -> Result<String> {
+    let report = gather();
+    
+    match format {
+        ReportFormat::Json => Ok(serde_json::to_string_pretty(&report)?),
+        ReportFormat::Markdown => Ok(render_markdown(&report)),
+    }
+}
+
+fn gather() -> SystemReport {
+    let sys_info = SystemInfo::new();
+    let mut errors = Vec::new();
+    
+    let system = SystemSection {
+        os: sys_info.os_version(),
+        hostname: sys_info.hostname(),
+        kernel: sys_info.kernel_version(),
+        uptime_seconds: sys_info.uptime().as_secs(),
+        cpu: sys_info.cpu_brand(),
+        cpu_cores: sys_info.cpu_count(),
+    };
+    
+    let memory = MemorySection {
+        total_bytes: sys_info.total_memory(),
+        used_bytes: sys_info.used_memory(),
+        available_bytes: sys_info.available_memory(),
+        swap_total_bytes: sys_info.total_swap(),
+        swap_used_bytes: sys_info.used_swap(),
+    };
+    
+    let temperatures = SystemInfo::get_temperatures()
+        .into_iter()
+        .map(|sensor| TemperatureEntry {
+            sensor: sensor.name,
+            celsius: sensor.temperature,
+            critical_celsius: sensor.critical,
+        })
+        .collect();
+    
+    let mounts = match disk_analyzer::get_mount_points() {
+        Ok(mounts) => mounts.into_iter()
+            .map(|mount| MountEntry {
+                device: mount.device,
+                mount_point: mount.mount_point.display().to_string(),
+                fs_type: mount.fs_type,
+                total_bytes: mount.total,
+                used_bytes: mount.used,
+                available_bytes: mount.available,
+            })
+            .collect(),
+        Err(e) => {
+            errors.push(format!("Mount points: {}", e));
+            Vec::new()
+        }
+    };
+    
+    let packages = match package_manager::get_package_stats() {
+        Ok(stats) => Some(PackageSection {
+            backend: package_manager::detect_backend().name().to_string(),
+            installed: stats.total_installed,
+            upgradeable: stats.upgradeable,
+            auto_removable: stats.auto_removable,
+            held: stats.held,
+        }),
+        Err(e) => {
+            errors.push(format!("Packages: {}", e));
+            None
+        }
+    };
+    
+    let failed_services = match service_manager::list_services() {
+        Ok(services) => Some(services.into_iter()
+            .filter(|service| service.state == service_manager::ServiceState::Failed)
+            .map(|service| service.name)
+            .collect()),
+        Err(e) => {
+            errors.push(format!("Services: {}", e));
+            None
+        }
+    };
+    
+    SystemReport {
+        system,
+        memory,
+        temperatures,
+        mounts,
+        packages,
+        failed_services,
+        errors,
+    }
+}
+
+fn render_markdown(report: &SystemReport) -> String {
+    let mut out = String::from("# SysMate System Report\n\n");
+    
+    let system = &report.system;
+    out.push_str("## System\n\n");
+    out.push_str(&format!("- **Operating System:** {}\n", system.os));
+    out.push_str(&format!("- **Hostname:** {}\n", system.hostname));
+    out.push_str(&format!("- **Kernel:** {}\n", system.kernel));
+    out.push_str(&format!("- **Uptime:** {} seconds\n", system.uptime_seconds));
+    out.push_str(&format!("- **CPU:** {} ({} cores)\n\n", system.cpu, system.cpu_cores));
+    
+    let memory = &report.memory;
+    out.push_str("## Memory\n\n");
+    out.push_str(&format!("- **Used:** {} / {}\n",
+        SystemInfo::format_memory(memory.used_bytes),
+        SystemInfo::format_memory(memory.total_bytes)));
+    out.push_str(&format!("- **Available:** {}\n", SystemInfo::format_memory(memory.available_bytes)));
+    out.push_str(&format!("- **Swap:** {} / {}\n\n",
+        SystemInfo::format_memory(memory.swap_used_bytes),
+        SystemInfo::format_memory(memory.swap_total_bytes)));
+    
+    if !report.temperatures.is_empty() {
+        out.push_str("## Temperatures\n\n| Sensor | Temperature | Critical |\n|---|---|---|\n");
+        for temp in &report.temperatures {
+            let critical = temp.critical_celsius
+                .map(SystemInfo::format_temperature)
+                .unwrap_or_else(|| "-".to_string());
+            out.push_str(&format!("| {} | {} | {} |\n",
+                temp.sensor, SystemInfo::format_temperature(temp.celsius), critical));
+        }
+        out.push('\n');
+    }
+    
+    if !report.mounts.is_empty() {
+        out.push_str("## Disks\n\n| Mount | Device | Type | Used | Total |\n|---|---|---|---|---|\n");
+        for mount in &report.mounts {
+            out.push_str(&format!("| {} | {} | {} | {} | {} |\n",
+                mount.mount_point,
+                mount.device,
+                mount.fs_type,
+                disk_analyzer::MountPoint::format_size(mount.used_bytes),
+                disk_analyzer::MountPoint::format_size(mount.total_bytes)));
+        }
+        out.push('\n');
+    }
+    
+    if let Some(packages) = &report.packages {
+        out.push_str("## Packages\n\n");
+        out.push_str(&format!("- **Backend:** {}\n", packages.backend));
+        out.push_str(&format!("- **Installed:** {}\n", packages.installed));
+        out.push_str(&format!("- **Upgradeable:** {}\n", packages.upgradeable));
+        out.push_str(&format!("- **Auto-removable:** {}\n", packages.auto_removable));
+        out.push_str(&format!("- **Held:** {}\n\n", packages.held));
+    }
+    
+    if let Some(failed) = &report.failed_services {
+        out.push_str("## Failed Services\n\n");
+        if failed.is_empty() {
+            out.push_str("None\n");
+        }
+        for service in failed {
+            out.push_str(&format!("- {}\n", service));
+        }
+        out.push('\n');
+    }
+    
+    if !report.errors.is_empty() {
+        out.push_str("## Errors\n\nThese sources could not be gathered:\n\n");
+        for error in &report.errors {
+            out.push_str(&format!("- {}\n", error));
+        }
+    }
+    
+    out
+}
@@ -0,0 +1,109 @@
+//! Global search
+//! 
+//! Queries packages, services and autostart entries at once so the sidebar
+//! can offer a single search box that jumps to the matching view.
+
+/// Maximum number of hits returned per kind
+pub const MAX_HITS_PER_KIND: usize = 5;
+
+/// Where a search hit comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchKind {
+    Package,
+    Service,
+    Autostart,
+}
+
+impl SearchKind {
+    /// Name of the stack page showing this kind
+    pub fn page_name(&self) -> &'static str {
+        match self {
+            SearchKind::Package => "packages",
+            SearchKind::Service => "services",
+            SearchKind::Autostart => "startup",
+        }
+    }
+    
+    pub fn icon_name(&self) -> &'static str {
+        match self {
+            SearchKind::Package => "package-x-generic-symbolic",
+            SearchKind::Service => "preferences-system-symbolic",
+            SearchKind::Autostart => "system-run-symbolic",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub kind: SearchKind,
+    pub title: String,
+    pub subtitle: String,
+}
+
+/// Search all modules for `query`
+/// 
+/// The modules are queried in parallel. A module that fails to search
+/// simply contributes no hits.
+pub fn search_all(query: &str) -> Vec<SearchHit> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    
+    let (packages, services, autostart) = std::thread::scope(|scope| {
+        let packages = scope.spawn(|| search_packages(query));
+        let services = scope.spawn(|| search_services(query));
+        let autostart = scope.spawn(|| search_autostart(query));
+        (
+            packages.join().unwrap_or_default(),
+            services.join().unwrap_or_default(),
+            autostart.join().unwrap_or_default(),
+        )
+    });
+    
+    let mut hits = packages;
+    hits.extend(services);
+    hits.extend(autostart);
+    hits
+}
+
+fn search_packages(query: &str) -> Vec<SearchHit> {
+    package_manager::search_packages(query)
+        .unwrap_or_default()
+        .into_iter()
+        .take(MAX_HITS_PER_KIND)
+        .map(|package| SearchHit {
+            kind: SearchKind::Package,
+            title: package.name,
+            subtitle: package.description,
+        })
+        .collect()
+}
+
+fn search_services(query: &str) -> Vec<SearchHit> {
+    service_manager::search_services(query)
+        .unwrap_or_default()
+        .into_iter()
+        .take(MAX_HITS_PER_KIND)
+        .map(|service| SearchHit {
+            kind: SearchKind::Service,
+            title: service.name,
+            subtitle: service.description,
+        })
+        .collect()
+}
+
+fn search_autostart(query: &str) -> Vec<SearchHit> {
+    let query = query.to_lowercase();
+    startup_manager::list_autostart_apps()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|app| app.name.to_lowercase().contains(&query))
+        .take(MAX_HITS_PER_KIND)
+        .map(|app| SearchHit {
+            kind: SearchKind::Autostart,
+            title: app.name,
+            subtitle: app.comment,
+        })
+        .collect()
+}
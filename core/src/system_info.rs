@@ -1,29 +1,188 @@
-use sysinfo::System;
-use std::time::Duration;
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, Pid, Process, ProcessRefreshKind, RefreshKind, System, MINIMUM_CPU_UPDATE_INTERVAL};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use std::fs;
+use std::net::{Ipv4Addr, Ipv6Addr};
 
 #[derive(Debug, Clone)]
 pub struct TemperatureSensor {
     pub name: String,
     pub temperature: f32,
     pub label: String,
+    /// Hardware-reported critical temperature, if any
+    pub critical: Option<f32>,
 }
 
+/// Critical temperature assumed for sensors that don't report one
+const DEFAULT_CRITICAL_TEMPERATURE: f32 = 85.0;
+
+#[derive(Debug, Clone)]
+pub struct CpuDetails {
+    pub model: String,
+    pub vendor: String,
+    /// Feature flags of all cores, deduplicated and sorted
+    pub flags: Vec<String>,
+    pub virtualization: Virtualization,
+}
+
+#[derive(Debug, Clone)]
+pub struct Virtualization {
+    /// "VT-x" or "AMD-V" when the CPU supports hardware virtualization
+    pub technology: Option<&'static str>,
+    /// Whether /dev/kvm exists and can be opened by the current user
+    pub kvm_usable: bool,
+}
+
+/// Clock frequency of one logical CPU, from cpufreq
+#[derive(Debug, Clone)]
+pub struct CpuFreq {
+    /// Logical CPU number, as in `cpu3`
+    pub core: usize,
+    pub current_mhz: u32,
+    /// Lowest and highest frequency the hardware supports
+    pub min_mhz: u32,
+    pub max_mhz: u32,
+    /// Scaling governor such as `powersave` or `schedutil`
+    pub governor: Option<String>,
+}
+
+/// Pressure stall averages over 10, 60 and 300 seconds, in percent of wall time
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PressureAverages {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+}
+
+/// Pressure stall information for one resource
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourcePressure {
+    /// Time at least one task was stalled waiting for the resource
+    pub some: PressureAverages,
+    /// Time all non-idle tasks were stalled at once; absent for CPU on older kernels
+    pub full: Option<PressureAverages>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PressureStats {
+    pub cpu: ResourcePressure,
+    pub memory: ResourcePressure,
+    pub io: ResourcePressure,
+}
+
+#[derive(Debug, Clone)]
+pub struct BatteryInfo {
+    /// Charge level in percent
+    pub percentage: f32,
+    pub charging: bool,
+    /// Time until empty when discharging, or until full when charging
+    pub time_remaining: Option<Duration>,
+    /// Full capacity as a percentage of the design capacity
+    pub health: Option<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiskIoStat {
+    pub device: String,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+}
+
+/// Time between the two /proc/diskstats samples of `get_disk_io()`
+const DISK_IO_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// /proc/diskstats counts in 512-byte sectors regardless of the device's sector size
+const DISKSTATS_SECTOR_SIZE: u64 = 512;
+
+#[derive(Debug, Clone)]
+pub struct NetInterfaceInfo {
+    pub name: String,
+    pub mac: Option<String>,
+    pub ipv4: Vec<Ipv4Addr>,
+    pub ipv6: Vec<Ipv6Addr>,
+    /// Total bytes received since boot
+    pub rx_bytes: u64,
+    /// Total bytes sent since boot
+    pub tx_bytes: u64,
+    pub is_up: bool,
+}
+
+impl NetInterfaceInfo {
+    /// Receive rate in bytes per second since an earlier sample of this interface
+    /// (reserved for the network monitor)
+    #[allow(dead_code)]
+    pub fn rx_rate(&self, previous: &NetInterfaceInfo, elapsed: Duration) -> f64 {
+        Self::rate(previous.rx_bytes, self.rx_bytes, elapsed)
+    }
+    
+    /// Transmit rate in bytes per second since an earlier sample of this interface
+    /// (reserved for the network monitor)
+    #[allow(dead_code)]
+    pub fn tx_rate(&self, previous: &NetInterfaceInfo, elapsed: Duration) -> f64 {
+        Self::rate(previous.tx_bytes, self.tx_bytes, elapsed)
+    }
+    
+    #[allow(dead_code)]
+    fn rate(before: u64, after: u64, elapsed: Duration) -> f64 {
+        if elapsed.is_zero() {
+            return 0.0;
+        }
+        // Counters reset when the interface is re-created
+        after.saturating_sub(before) as f64 / elapsed.as_secs_f64()
+    }
+}
+
+/// CPU, memory and process figures, shared by the views that show them
+/// 
+/// Only CPUs, memory and processes are loaded; disks, networks and sensors are
+/// read by the associated functions below when needed. Refreshing is explicit
+/// and partial, so each view refreshes just what it shows.
 pub struct SystemInfo {
     system: System,
+    last_cpu_refresh: Instant,
 }
 
 impl SystemInfo {
     pub fn new() -> Self {
-        let mut system = System::new_all();
-        system.refresh_all();
-        Self { system }
+        let system = System::new_with_specifics(
+            RefreshKind::new()
+                .with_cpu(CpuRefreshKind::everything())
+                .with_memory(MemoryRefreshKind::everything())
+                .with_processes(ProcessRefreshKind::everything())
+        );
+        Self { system, last_cpu_refresh: Instant::now() }
     }
 
-    /// Refresh system information (reserved for future live updates)
-    #[allow(dead_code)]
-    pub fn refresh(&mut self) {
-        self.system.refresh_all();
+    /// Take a new CPU usage sample
+    /// 
+    /// Usage is computed from the difference between two samples, so if the
+    /// last sample is more recent than sysinfo's minimum update interval the
+    /// current figures are kept. Several views share this, and they refresh
+    /// from the main thread, which must never wait.
+    pub fn refresh_cpu(&mut self) {
+        if self.last_cpu_refresh.elapsed() < MINIMUM_CPU_UPDATE_INTERVAL {
+            return;
+        }
+        self.system.refresh_cpu();
+        self.last_cpu_refresh = Instant::now();
+    }
+
+    pub fn refresh_memory(&mut self) {
+        self.system.refresh_memory();
+    }
+
+    pub fn refresh_processes(&mut self) {
+        self.system.refresh_processes();
+    }
+
+    /// Overall CPU usage in percent, as of the last CPU refresh
+    pub fn global_cpu_usage(&self) -> f32 {
+        self.system.global_cpu_info().cpu_usage()
+    }
+
+    /// All processes, as of the last process refresh
+    pub fn processes(&self) -> &HashMap<Pid, Process> {
+        self.system.processes()
     }
 
     pub fn os_version(&self) -> String {
@@ -70,6 +229,149 @@ impl SystemInfo {
         self.system.cpus().len()
     }
 
+    /// System load averaged over the last 1, 5 and 15 minutes
+    /// 
+    /// Load counts tasks that are running or waiting for the CPU or for disk
+    /// I/O, so a value equal to `cpu_count()` means the machine is fully busy.
+    pub fn load_average() -> (f64, f64, f64) {
+        let load = System::load_average();
+        (load.one, load.five, load.fifteen)
+    }
+
+    /// Number of processes, not counting their threads
+    pub fn process_count(&self) -> usize {
+        self.system.processes().values()
+            .filter(|process| process.thread_kind().is_none())
+            .count()
+    }
+
+    /// Number of threads across all processes
+    /// 
+    /// Read from the total of kernel scheduling entities in /proc/loadavg.
+    pub fn thread_count(&self) -> usize {
+        fs::read_to_string("/proc/loadavg")
+            .ok()
+            .and_then(|contents| {
+                let entities = contents.split_whitespace().nth(3)?;
+                entities.split_once('/')?.1.parse().ok()
+            })
+            .unwrap_or(0)
+    }
+
+    /// Pressure stall information for CPU, memory and I/O
+    /// 
+    /// Read from /proc/pressure. Unlike CPU usage this says how much work is
+    /// being held up waiting for a resource, which is what makes a system feel
+    /// slow. Returns `None` when PSI is unavailable (kernels before 4.20, or
+    /// booted with `psi=0`).
+    pub fn get_pressure() -> Option<PressureStats> {
+        let read = |resource: &str| {
+            fs::read_to_string(format!("/proc/pressure/{}", resource))
+                .ok()
+                .and_then(|contents| Self::parse_pressure(&contents))
+        };
+        
+        Some(PressureStats {
+            cpu: read("cpu")?,
+            memory: read("memory")?,
+            io: read("io")?,
+        })
+    }
+
+    /// Parse a /proc/pressure file
+    /// 
+    /// Each line looks like `some avg10=0.12 avg60=0.05 avg300=0.01 total=12345`.
+    fn parse_pressure(contents: &str) -> Option<ResourcePressure> {
+        let mut some = None;
+        let mut full = None;
+        
+        for line in contents.lines() {
+            let mut fields = line.split_whitespace();
+            let kind = fields.next();
+            let mut averages = PressureAverages::default();
+            for field in fields {
+                let Some((key, value)) = field.split_once('=') else {
+                    continue;
+                };
+                let Ok(value) = value.parse() else {
+                    continue;
+                };
+                match key {
+                    "avg10" => averages.avg10 = value,
+                    "avg60" => averages.avg60 = value,
+                    "avg300" => averages.avg300 = value,
+                    _ => {}
+                }
+            }
+            
+            match kind {
+                Some("some") => some = Some(averages),
+                Some("full") => full = Some(averages),
+                _ => {}
+            }
+        }
+        
+        Some(ResourcePressure { some: some?, full })
+    }
+
+    /// CPU model name as reported by the first core
+    pub fn cpu_brand(&self) -> String {
+        self.system.cpus().first()
+            .map(|cpu| cpu.brand().trim().to_string())
+            .filter(|brand| !brand.is_empty())
+            .unwrap_or_else(|| "Unknown".to_string())
+    }
+
+    /// CPU model, vendor and feature flags from /proc/cpuinfo
+    /// 
+    /// x86 lists `flags` per core, ARM lists `Features`; either is accepted.
+    /// Returns `None` if /proc/cpuinfo can't be read.
+    pub fn cpu_details() -> Option<CpuDetails> {
+        let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+        
+        let mut model = None;
+        let mut vendor = None;
+        let mut flags = std::collections::BTreeSet::new();
+        for line in cpuinfo.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "model name" | "Model" if model.is_none() => model = Some(value.to_string()),
+                "vendor_id" | "CPU implementer" if vendor.is_none() => vendor = Some(value.to_string()),
+                "flags" | "Features" => flags.extend(value.split_whitespace().map(|flag| flag.to_string())),
+                _ => {}
+            }
+        }
+        
+        let technology = if flags.contains("vmx") {
+            Some("VT-x")
+        } else if flags.contains("svm") {
+            Some("AMD-V")
+        } else {
+            None
+        };
+        let kvm_usable = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/kvm")
+            .is_ok();
+        
+        Some(CpuDetails {
+            model: model.unwrap_or_else(|| "Unknown".to_string()),
+            vendor: vendor.unwrap_or_else(|| "Unknown".to_string()),
+            flags: flags.into_iter().collect(),
+            virtualization: Virtualization { technology, kvm_usable },
+        })
+    }
+
+    /// Usage of each core in percent, refreshing the CPU sample first (see `refresh_cpu`)
+    pub fn per_core_usage(&mut self) -> Vec<f32> {
+        self.refresh_cpu();
+        self.system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect()
+    }
+
     pub fn format_uptime(&self) -> String {
         let uptime = self.uptime();
         let days = uptime.as_secs() / 86400;
@@ -96,6 +398,50 @@ impl SystemInfo {
         }
     }
     
+    /// Get the clock frequency of each logical CPU, lowest CPU number first
+    /// 
+    /// Empty when cpufreq isn't exposed, e.g. in most virtual machines.
+    pub fn get_cpu_frequencies() -> Vec<CpuFreq> {
+        let Ok(entries) = fs::read_dir("/sys/devices/system/cpu") else {
+            return Vec::new();
+        };
+        
+        // cpufreq reports kHz
+        let read_mhz = |dir: &std::path::Path, name: &str| -> Option<u32> {
+            let khz: u64 = fs::read_to_string(dir.join(name)).ok()?.trim().parse().ok()?;
+            Some((khz / 1000) as u32)
+        };
+        
+        let mut frequencies: Vec<CpuFreq> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let core = entry.file_name().to_str()?.strip_prefix("cpu")?.parse().ok()?;
+                let dir = entry.path().join("cpufreq");
+                Some(CpuFreq {
+                    core,
+                    current_mhz: read_mhz(&dir, "scaling_cur_freq")?,
+                    min_mhz: read_mhz(&dir, "cpuinfo_min_freq").or_else(|| read_mhz(&dir, "scaling_min_freq"))?,
+                    max_mhz: read_mhz(&dir, "cpuinfo_max_freq").or_else(|| read_mhz(&dir, "scaling_max_freq"))?,
+                    governor: fs::read_to_string(dir.join("scaling_governor"))
+                        .ok()
+                        .map(|governor| governor.trim().to_string()),
+                })
+            })
+            .collect();
+        
+        frequencies.sort_by_key(|freq| freq.core);
+        frequencies
+    }
+    
+    /// Format a frequency in MHz, e.g. "2.40 GHz"
+    pub fn format_frequency(mhz: u32) -> String {
+        if mhz >= 1000 {
+            format!("{:.2} GHz", mhz as f64 / 1000.0)
+        } else {
+            format!("{} MHz", mhz)
+        }
+    }
+    
     /// Get CPU and hardware temperatures
     pub fn get_temperatures() -> Vec<TemperatureSensor> {
         let mut sensors = Vec::new();
@@ -115,10 +461,20 @@ impl SystemInfo {
                                 .trim()
                                 .to_string();
                             
+                            // The critical point is one of the zone's trip points
+                            let critical = (0..16)
+                                .take_while(|i| path.join(format!("trip_point_{}_type", i)).exists())
+                                .find(|i| {
+                                    fs::read_to_string(path.join(format!("trip_point_{}_type", i)))
+                                        .is_ok_and(|t| t.trim() == "critical")
+                                })
+                                .and_then(|i| Self::read_millidegrees(&path.join(format!("trip_point_{}_temp", i))));
+                            
                             sensors.push(TemperatureSensor {
                                 name: entry.file_name().to_string_lossy().to_string(),
                                 temperature: temp_celsius,
                                 label,
+                                critical,
                             });
                         }
                     }
@@ -156,10 +512,15 @@ impl SystemInfo {
                                         .trim()
                                         .to_string();
                                     
+                                    // Prefer the critical limit, fall back to the max limit
+                                    let critical = Self::read_millidegrees(&path.join(format!("temp{}_crit", i)))
+                                        .or_else(|| Self::read_millidegrees(&path.join(format!("temp{}_max", i))));
+                                    
                                     sensors.push(TemperatureSensor {
                                         name: format!("{} - {}", name, label),
                                         temperature: temp_celsius,
                                         label,
+                                        critical,
                                     });
                                 }
                             }
@@ -176,18 +537,224 @@ impl SystemInfo {
         sensors
     }
     
+    /// Get the combined status of all system batteries
+    /// 
+    /// Returns `None` when there is no battery, e.g. on desktops. Multiple
+    /// batteries are reported as one by summing their capacities.
+    pub fn get_battery() -> Option<BatteryInfo> {
+        let mut paths: Vec<_> = fs::read_dir("/sys/class/power_supply").ok()?
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("BAT"))
+            .map(|entry| entry.path())
+            .collect();
+        paths.sort();
+        
+        let read_value = |path: &std::path::Path, name: &str| -> Option<f64> {
+            fs::read_to_string(path.join(name)).ok()?.trim().parse().ok()
+        };
+        
+        let mut unit = None;
+        let mut now = 0.0;
+        let mut full = 0.0;
+        let mut full_design = Some(0.0);
+        let mut rate = 0.0;
+        let mut charging = false;
+        
+        for path in paths {
+            // Skip batteries of peripherals such as wireless mice
+            if fs::read_to_string(path.join("scope")).is_ok_and(|s| s.trim() == "Device") {
+                continue;
+            }
+            
+            // Batteries report either energy (µWh) or charge (µAh), which can't be
+            // added up, so only batteries using the same unit as the first are counted
+            let prefix = if path.join("energy_now").exists() { "energy" } else { "charge" };
+            if unit.is_some_and(|unit| unit != prefix) {
+                continue;
+            }
+            let (Some(battery_now), Some(battery_full)) = (
+                read_value(&path, &format!("{}_now", prefix)),
+                read_value(&path, &format!("{}_full", prefix)),
+            ) else {
+                continue;
+            };
+            
+            unit = Some(prefix);
+            now += battery_now;
+            full += battery_full;
+            // Health is only meaningful when every battery has a design capacity
+            full_design = full_design
+                .zip(read_value(&path, &format!("{}_full_design", prefix)))
+                .map(|(total, design)| total + design);
+            rate += read_value(&path, if prefix == "energy" { "power_now" } else { "current_now" })
+                .unwrap_or(0.0)
+                .abs();
+            
+            if fs::read_to_string(path.join("status")).is_ok_and(|s| s.trim() == "Charging") {
+                charging = true;
+            }
+        }
+        
+        if unit.is_none() || full <= 0.0 {
+            return None;
+        }
+        
+        let remaining = if charging { full - now } else { now };
+        let time_remaining = (rate > 0.0 && remaining > 0.0)
+            .then(|| Duration::from_secs_f64(remaining / rate * 3600.0));
+        
+        Some(BatteryInfo {
+            percentage: ((now / full) * 100.0).clamp(0.0, 100.0) as f32,
+            charging,
+            time_remaining,
+            health: full_design
+                .filter(|&design| design > 0.0)
+                .map(|design| ((full / design) * 100.0) as f32),
+        })
+    }
+    
+    /// List network interfaces with their addresses and traffic counters
+    pub fn get_network_interfaces() -> Vec<NetInterfaceInfo> {
+        let mut interfaces = Vec::new();
+        
+        let Ok(entries) = fs::read_dir("/sys/class/net") else {
+            return interfaces;
+        };
+        
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            
+            let read_counter = |counter: &str| -> u64 {
+                fs::read_to_string(path.join("statistics").join(counter))
+                    .ok()
+                    .and_then(|s| s.trim().parse().ok())
+                    .unwrap_or(0)
+            };
+            
+            let mac = fs::read_to_string(path.join("address"))
+                .ok()
+                .map(|s| s.trim().to_string())
+                .filter(|mac| !mac.is_empty() && mac != "00:00:00:00:00:00");
+            
+            // Loopback reports "unknown" rather than "up"
+            let operstate = fs::read_to_string(path.join("operstate")).unwrap_or_default();
+            let is_up = match operstate.trim() {
+                "up" => true,
+                "unknown" => fs::read_to_string(path.join("flags"))
+                    .ok()
+                    .and_then(|s| u32::from_str_radix(s.trim().trim_start_matches("0x"), 16).ok())
+                    .is_some_and(|flags| flags & 0x1 != 0),
+                _ => false,
+            };
+            
+            interfaces.push(NetInterfaceInfo {
+                name,
+                mac,
+                ipv4: Vec::new(),
+                ipv6: Vec::new(),
+                rx_bytes: read_counter("rx_bytes"),
+                tx_bytes: read_counter("tx_bytes"),
+                is_up,
+            });
+        }
+        
+        if let Ok(addresses) = nix::ifaddrs::getifaddrs() {
+            for address in addresses {
+                let Some(interface) = interfaces.iter_mut().find(|i| i.name == address.interface_name) else {
+                    continue;
+                };
+                let Some(storage) = address.address else {
+                    continue;
+                };
+                if let Some(v4) = storage.as_sockaddr_in() {
+                    interface.ipv4.push(v4.ip());
+                } else if let Some(v6) = storage.as_sockaddr_in6() {
+                    interface.ipv6.push(v6.ip());
+                }
+            }
+        }
+        
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        interfaces
+    }
+    
+    /// Get read and write rates of each disk
+    /// 
+    /// Samples /proc/diskstats twice, so this blocks for a short interval.
+    /// Only whole disks are listed; partitions, loop and RAM devices are skipped.
+    pub fn get_disk_io() -> Vec<DiskIoStat> {
+        let before = Self::read_diskstats();
+        std::thread::sleep(DISK_IO_SAMPLE_INTERVAL);
+        let after = Self::read_diskstats();
+        
+        let seconds = DISK_IO_SAMPLE_INTERVAL.as_secs_f64();
+        after.into_iter()
+            .filter_map(|(device, read_after, written_after)| {
+                let (_, read_before, written_before) = before.iter().find(|(d, _, _)| *d == device)?;
+                Some(DiskIoStat {
+                    read_bytes_per_sec: read_after.saturating_sub(*read_before) as f64 / seconds,
+                    write_bytes_per_sec: written_after.saturating_sub(*written_before) as f64 / seconds,
+                    device,
+                })
+            })
+            .collect()
+    }
+    
+    /// Bytes read and written so far per disk, from /proc/diskstats
+    fn read_diskstats() -> Vec<(String, u64, u64)> {
+        let Ok(contents) = fs::read_to_string("/proc/diskstats") else {
+            return Vec::new();
+        };
+        
+        contents.lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let device = *fields.get(2)?;
+                if device.starts_with("loop") || device.starts_with("ram") {
+                    return None;
+                }
+                // Whole disks have an entry in /sys/block, partitions don't
+                if !std::path::Path::new("/sys/block").join(device).exists() {
+                    return None;
+                }
+                
+                let sectors_read: u64 = fields.get(5)?.parse().ok()?;
+                let sectors_written: u64 = fields.get(9)?.parse().ok()?;
+                Some((
+                    device.to_string(),
+                    sectors_read * DISKSTATS_SECTOR_SIZE,
+                    sectors_written * DISKSTATS_SECTOR_SIZE,
+                ))
+            })
+            .collect()
+    }
+    
     /// Format temperature with color coding
     pub fn format_temperature(celsius: f32) -> String {
         format!("{:.1}°C", celsius)
     }
     
-    /// Get temperature status (normal, warm, hot)
-    pub fn temperature_status(celsius: f32) -> &'static str {
-        if celsius < 50.0 {
+    /// Read a sysfs temperature in millidegrees as degrees Celsius
+    /// 
+    /// Zero and negative values mean the limit is not set.
+    fn read_millidegrees(path: &std::path::Path) -> Option<f32> {
+        let millidegrees: i32 = fs::read_to_string(path).ok()?.trim().parse().ok()?;
+        (millidegrees > 0).then(|| millidegrees as f32 / 1000.0)
+    }
+    
+    /// Get temperature status (normal, warm, hot, critical)
+    /// 
+    /// Compares against the sensor's own critical temperature when the
+    /// hardware reports one, otherwise against 85°C.
+    pub fn temperature_status(sensor: &TemperatureSensor) -> &'static str {
+        let critical = sensor.critical.unwrap_or(DEFAULT_CRITICAL_TEMPERATURE);
+        
+        if sensor.temperature < critical - 35.0 {
             "Normal"
-        } else if celsius < 70.0 {
+        } else if sensor.temperature < critical - 15.0 {
             "Warm"
-        } else if celsius < 85.0 {
+        } else if sensor.temperature < critical {
             "Hot"
         } else {
             "Critical"
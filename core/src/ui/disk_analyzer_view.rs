@@ -1,11 +1,35 @@
 //! Disk Analyzer View
 //! 
-//! Shows disk usage for all mounted filesystems with visual progress indicators and folder analysis.
+//! Shows disk usage for all mounted filesystems with visual progress indicators, SMART
+//! health of the physical disks, and folder analysis.
 
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Label, Orientation, ProgressBar};
+use gtk4::{Box as GtkBox, Button, ButtonsType, CheckButton, Entry, Label, ListBox, MessageDialog, MessageType, Orientation, ProgressBar, glib};
 use libadwaita as adw;
 use adw::prelude::*;
+use disk_analyzer::{DiskHealth, FolderInfo, HealthStatus};
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, TryRecvError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::module_loader::Module;
+use crate::ui::show_toast;
+
+/// Messages sent from a scan thread to the UI
+enum ScanMessage<T> {
+    Progress(u64),
+    Done(anyhow::Result<T>),
+}
+
+/// Number of files listed under "Largest Files"
+const LARGEST_FILES_LIMIT: usize = 20;
+
+/// Files smaller than this are not worth listing under "Largest Files"
+const LARGEST_FILES_MIN_SIZE: u64 = 1024 * 1024;
 
 pub struct DiskAnalyzerView {
     root: GtkBox,
@@ -24,13 +48,24 @@ impl DiskAnalyzerView {
 
         // Get disk information
         match disk_analyzer::get_mount_points() {
-            Ok(mounts) => {
+            Ok(mut mounts) => {
                 let is_empty = mounts.is_empty();
                 
+                // Internal disks first, then removable drives, then network shares
+                mounts.sort_by_key(|m| (m.is_network, m.is_removable));
+                
                 for mount in mounts {
                     let group = adw::PreferencesGroup::new();
                     group.set_title(&mount.mount_point.display().to_string());
-                    group.set_description(Some(&format!("{} ({})", mount.device, mount.fs_type)));
+                    let kind = if mount.is_network {
+                        " · Network"
+                    } else if mount.is_removable {
+                        " · Removable"
+                    } else {
+                        ""
+                    };
+                    let readonly = if mount.is_readonly() { " · Read-only" } else { "" };
+                    group.set_description(Some(&format!("{} ({}){}{}", mount.device, mount.fs_type, kind, readonly)));
 
                     // Usage row with progress bar
                     let usage_row = adw::ActionRow::new();
@@ -66,6 +101,21 @@ impl DiskAnalyzerView {
                     usage_row.add_suffix(&usage_box);
                     group.add(&usage_row);
 
+                    // Running out of inodes fills a disk just as well as running out of space
+                    if mount.inode_used_percentage() > 90.0 {
+                        let inode_row = adw::ActionRow::new();
+                        inode_row.set_title("Inode Usage High");
+                        inode_row.set_subtitle(&format!(
+                            "{:.1}% of inodes used ({} of {}); new files may fail even with free space",
+                            mount.inode_used_percentage(),
+                            mount.used_inodes,
+                            mount.total_inodes
+                        ));
+                        let icon = gtk4::Image::from_icon_name("dialog-warning-symbolic");
+                        inode_row.add_prefix(&icon);
+                        group.add(&inode_row);
+                    }
+
                     content.append(&group);
                 }
 
@@ -86,12 +136,500 @@ impl DiskAnalyzerView {
             }
         }
 
+        content.append(&Self::build_disk_health());
+        content.append(&Self::build_folder_analysis());
+        content.append(&Self::build_largest_files());
+
         // Large folders section
         let large_folders_group = adw::PreferencesGroup::new();
         large_folders_group.set_title("Large Folders in Home Directory");
         large_folders_group.set_description(Some("Folders taking up significant disk space"));
 
-        match disk_analyzer::get_common_large_folders() {
+        // Walking the home directory can take a while, so do it off the main thread
+        let scanning_row = adw::ActionRow::new();
+        scanning_row.set_title("Scanning folders...");
+        let spinner = gtk4::Spinner::new();
+        spinner.start();
+        scanning_row.add_prefix(&spinner);
+        large_folders_group.add(&scanning_row);
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(disk_analyzer::get_common_large_folders());
+        });
+
+        let group = large_folders_group.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            match rx.try_recv() {
+                Ok(result) => {
+                    group.remove(&scanning_row);
+                    Self::show_large_folders(&group, result);
+                    glib::ControlFlow::Break
+                }
+                Err(TryRecvError::Empty) => glib::ControlFlow::Continue,
+                Err(TryRecvError::Disconnected) => glib::ControlFlow::Break,
+            }
+        });
+
+        content.append(&large_folders_group);
+
+        // Cleanup suggestions
+        let suggestions_group = adw::PreferencesGroup::new();
+        suggestions_group.set_title("Cleanup Suggestions");
+        suggestions_group.set_description(Some("Common ways to free up disk space"));
+
+        for suggestion in disk_analyzer::get_cleanup_suggestions() {
+            let row = adw::ActionRow::new();
+            row.set_title(&suggestion);
+            
+            let icon = gtk4::Image::from_icon_name("user-trash-symbolic");
+            row.add_prefix(&icon);
+            
+            suggestions_group.add(&row);
+        }
+
+        content.append(&suggestions_group);
+
+        let scrolled = gtk4::ScrolledWindow::new();
+        scrolled.set_vexpand(true);
+        scrolled.set_child(Some(&content));
+
+        root.append(&scrolled);
+
+        Self { root }
+    }
+
+    /// Build the "Disk Health" group showing SMART status per physical disk
+    /// 
+    /// smartctl needs root, so the check only runs when asked for rather than
+    /// prompting for a password whenever the page is built.
+    fn build_disk_health() -> adw::PreferencesGroup {
+        let group = adw::PreferencesGroup::new();
+        group.set_title("Disk Health");
+        group.set_description(Some("SMART status reported by each physical disk"));
+
+        let check_button = Button::with_label("Check");
+        check_button.set_valign(gtk4::Align::Center);
+        group.set_header_suffix(Some(&check_button));
+
+        let results = ListBox::new();
+        results.set_selection_mode(gtk4::SelectionMode::None);
+        results.add_css_class("boxed-list");
+        group.add(&results);
+
+        if !disk_analyzer::smartctl_available() {
+            let row = adw::ActionRow::new();
+            row.set_title("Install smartmontools to check disk health");
+            row.set_subtitle("SysMate reads SMART data with smartctl");
+            let icon = gtk4::Image::from_icon_name("dialog-information-symbolic");
+            row.add_prefix(&icon);
+            results.append(&row);
+            check_button.set_sensitive(false);
+            return group;
+        }
+        results.set_visible(false);
+
+        check_button.connect_clicked(move |btn| {
+            btn.set_sensitive(false);
+            while let Some(child) = results.first_child() {
+                results.remove(&child);
+            }
+            results.set_visible(true);
+
+            let checking_row = adw::ActionRow::new();
+            checking_row.set_title("Reading SMART data...");
+            let spinner = gtk4::Spinner::new();
+            spinner.start();
+            checking_row.add_prefix(&spinner);
+            results.append(&checking_row);
+
+            let (tx, rx) = mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = tx.send(disk_analyzer::get_disk_health());
+            });
+
+            let btn_clone = btn.clone();
+            let results = results.clone();
+            glib::timeout_add_local(Duration::from_millis(100), move || {
+                match rx.try_recv() {
+                    Ok(disks) => {
+                        results.remove(&checking_row);
+                        Self::show_disk_health(&results, &disks);
+                        btn_clone.set_sensitive(true);
+                        glib::ControlFlow::Break
+                    }
+                    Err(TryRecvError::Empty) => glib::ControlFlow::Continue,
+                    Err(TryRecvError::Disconnected) => {
+                        btn_clone.set_sensitive(true);
+                        glib::ControlFlow::Break
+                    }
+                }
+            });
+        });
+
+        group
+    }
+
+    fn show_disk_health(results: &ListBox, disks: &[DiskHealth]) {
+        if disks.is_empty() {
+            let row = adw::ActionRow::new();
+            row.set_title("No physical disks found");
+            results.append(&row);
+            return;
+        }
+
+        for disk in disks {
+            let row = adw::ActionRow::new();
+            row.set_title(&format!("/dev/{}", disk.device));
+
+            let mut details = Vec::new();
+            if !disk.model.is_empty() {
+                details.push(disk.model.clone());
+            }
+            if let Some(temperature) = disk.temperature {
+                details.push(format!("{} °C", temperature));
+            }
+            if let Some(hours) = disk.power_on_hours {
+                details.push(format!("{} hours powered on", hours));
+            }
+            if let Some(sectors) = disk.reallocated_sectors {
+                details.push(format!("{} reallocated sectors", sectors));
+            }
+            if disk.status == HealthStatus::Unknown {
+                details.push("SMART data unavailable".to_string());
+            }
+            row.set_subtitle(&details.join(" · "));
+
+            let icon = gtk4::Image::from_icon_name("drive-harddisk-symbolic");
+            row.add_prefix(&icon);
+
+            let (text, css_class) = match disk.status {
+                HealthStatus::Passed => ("Healthy", "success"),
+                HealthStatus::Failed => ("Failing", "error"),
+                HealthStatus::Unknown => ("Unknown", "dim-label"),
+            };
+            let badge = Label::new(Some(text));
+            badge.add_css_class(css_class);
+            badge.add_css_class("heading");
+            row.add_suffix(&badge);
+
+            results.append(&row);
+        }
+    }
+
+    /// Build the "Analyze Folder" group for scanning a user-chosen directory
+    fn build_folder_analysis() -> adw::PreferencesGroup {
+        let group = adw::PreferencesGroup::new();
+        group.set_title("Analyze Folder");
+        group.set_description(Some("Find the largest subfolders of any directory"));
+
+        let input_box = GtkBox::new(Orientation::Horizontal, 6);
+        input_box.set_margin_bottom(12);
+
+        let path_entry = Entry::new();
+        path_entry.set_placeholder_text(Some("~/Downloads"));
+        path_entry.set_hexpand(true);
+        input_box.append(&path_entry);
+
+        let same_fs_check = CheckButton::with_label("Stay on this filesystem");
+        same_fs_check.set_active(true);
+        same_fs_check.set_tooltip_text(Some("Don't descend into other mounts such as /proc or network shares"));
+        input_box.append(&same_fs_check);
+
+        let analyze_button = Button::with_label("Analyze");
+        analyze_button.add_css_class("suggested-action");
+        input_box.append(&analyze_button);
+
+        group.add(&input_box);
+
+        let results = ListBox::new();
+        results.set_selection_mode(gtk4::SelectionMode::None);
+        results.add_css_class("boxed-list");
+        results.set_visible(false);
+        group.add(&results);
+
+        // Cancels the running scan when a new one starts
+        let current_scan: Rc<RefCell<Option<Arc<AtomicBool>>>> = Rc::new(RefCell::new(None));
+
+        let path_entry_clone = path_entry.clone();
+        let start_scan = move || {
+            let path = path_entry_clone.text().to_string();
+            let stay_on_filesystem = same_fs_check.is_active();
+            Self::run_scan(
+                &results,
+                &current_scan,
+                move |cancel, progress| {
+                    disk_analyzer::analyze_folder_for_path(&path, usize::MAX, stay_on_filesystem, cancel, progress)
+                },
+                Self::show_folder_results,
+            );
+        };
+        let start_scan = Rc::new(start_scan);
+
+        let on_click = start_scan.clone();
+        analyze_button.connect_clicked(move |_| on_click());
+        path_entry.connect_activate(move |_| start_scan());
+
+        group
+    }
+
+    /// Run a cancellable scan on a worker thread and show its result in `results`
+    /// 
+    /// The scan in `current_scan` is cancelled first. A cancelled scan still
+    /// returns what it found so far, so its poll timer stops without touching
+    /// `results`, which by then belong to the new scan.
+    fn run_scan<T: Send + 'static>(
+        results: &ListBox,
+        current_scan: &Rc<RefCell<Option<Arc<AtomicBool>>>>,
+        scan: impl FnOnce(Arc<AtomicBool>, &mut dyn FnMut(u64)) -> anyhow::Result<T> + Send + 'static,
+        show: fn(&ListBox, anyhow::Result<T>),
+    ) {
+        if let Some(cancel) = current_scan.borrow_mut().take() {
+            cancel.store(true, Ordering::Relaxed);
+        }
+        while let Some(child) = results.first_child() {
+            results.remove(&child);
+        }
+        results.set_visible(true);
+
+        let scanning_row = adw::ActionRow::new();
+        scanning_row.set_title("Scanning...");
+        let spinner = gtk4::Spinner::new();
+        spinner.start();
+        scanning_row.add_prefix(&spinner);
+        results.append(&scanning_row);
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        *current_scan.borrow_mut() = Some(cancel.clone());
+
+        let (tx, rx) = mpsc::channel();
+        let cancel_clone = cancel.clone();
+        std::thread::spawn(move || {
+            let progress_tx = tx.clone();
+            let result = scan(cancel_clone, &mut |scanned| {
+                let _ = progress_tx.send(ScanMessage::Progress(scanned));
+            });
+            let _ = tx.send(ScanMessage::Done(result));
+        });
+
+        let results = results.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            if cancel.load(Ordering::Relaxed) {
+                return glib::ControlFlow::Break;
+            }
+            let mut scanned = None;
+            loop {
+                match rx.try_recv() {
+                    Ok(ScanMessage::Progress(bytes)) => scanned = Some(bytes),
+                    Ok(ScanMessage::Done(result)) => {
+                        results.remove(&scanning_row);
+                        show(&results, result);
+                        return glib::ControlFlow::Break;
+                    }
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+                }
+            }
+            if let Some(bytes) = scanned {
+                scanning_row.set_subtitle(&format!("{} scanned", disk_analyzer::MountPoint::format_size(bytes)));
+            }
+            glib::ControlFlow::Continue
+        });
+    }
+
+    /// Build the "Largest Files" group for finding big files in a directory
+    fn build_largest_files() -> adw::PreferencesGroup {
+        let group = adw::PreferencesGroup::new();
+        group.set_title("Largest Files");
+        group.set_description(Some("Find the biggest individual files in a directory"));
+
+        let input_box = GtkBox::new(Orientation::Horizontal, 6);
+        input_box.set_margin_bottom(12);
+
+        let path_entry = Entry::new();
+        path_entry.set_placeholder_text(Some("~"));
+        path_entry.set_hexpand(true);
+        input_box.append(&path_entry);
+
+        let find_button = Button::with_label("Find");
+        find_button.add_css_class("suggested-action");
+        input_box.append(&find_button);
+
+        group.add(&input_box);
+
+        let results = ListBox::new();
+        results.set_selection_mode(gtk4::SelectionMode::None);
+        results.add_css_class("boxed-list");
+        results.set_visible(false);
+        group.add(&results);
+
+        // Cancels the running scan when a new one starts
+        let current_scan: Rc<RefCell<Option<Arc<AtomicBool>>>> = Rc::new(RefCell::new(None));
+
+        let path_entry_clone = path_entry.clone();
+        let start_scan = move || {
+            let text = path_entry_clone.text();
+            let path = disk_analyzer::expand_home(if text.trim().is_empty() { "~" } else { text.trim() });
+            Self::run_scan(
+                &results,
+                &current_scan,
+                move |cancel, progress| {
+                    disk_analyzer::scan_largest_files(&path, LARGEST_FILES_LIMIT, LARGEST_FILES_MIN_SIZE, cancel, progress)
+                },
+                Self::show_largest_files,
+            );
+        };
+        let start_scan = Rc::new(start_scan);
+
+        let on_click = start_scan.clone();
+        find_button.connect_clicked(move |_| on_click());
+        path_entry.connect_activate(move |_| start_scan());
+
+        group
+    }
+
+    fn show_largest_files(results: &ListBox, result: anyhow::Result<Vec<(PathBuf, u64)>>) {
+        match result {
+            Ok(files) if files.is_empty() => {
+                let row = adw::ActionRow::new();
+                row.set_title("No large files found");
+                results.append(&row);
+            }
+            Ok(files) => {
+                for (path, size) in files {
+                    let row = adw::ActionRow::new();
+                    if let Some(name) = path.file_name() {
+                        row.set_title(&name.to_string_lossy());
+                    }
+                    row.set_subtitle(&path.display().to_string());
+
+                    let icon = gtk4::Image::from_icon_name("text-x-generic-symbolic");
+                    row.add_prefix(&icon);
+
+                    let size_label = Label::new(Some(&disk_analyzer::MountPoint::format_size(size)));
+                    row.add_suffix(&size_label);
+
+                    let delete_button = Button::from_icon_name("user-trash-symbolic");
+                    delete_button.set_tooltip_text(Some("Delete file"));
+                    delete_button.set_valign(gtk4::Align::Center);
+                    delete_button.add_css_class("flat");
+                    let row_clone = row.clone();
+                    delete_button.connect_clicked(move |btn| {
+                        Self::confirm_delete(btn, &row_clone, &path, size);
+                    });
+                    row.add_suffix(&delete_button);
+
+                    results.append(&row);
+                }
+            }
+            Err(e) => {
+                let row = adw::ActionRow::new();
+                row.set_title("Error scanning folder");
+                row.set_subtitle(&e.to_string());
+                let icon = gtk4::Image::from_icon_name("dialog-error-symbolic");
+                row.add_prefix(&icon);
+                results.append(&row);
+            }
+        }
+    }
+
+    /// Ask before deleting a file from the "Largest Files" list, then remove its row
+    fn confirm_delete(btn: &Button, row: &adw::ActionRow, path: &std::path::Path, size: u64) {
+        let Some(window) = btn.root().and_downcast::<gtk4::Window>() else {
+            return;
+        };
+        
+        let dialog = MessageDialog::new(
+            Some(&window),
+            gtk4::DialogFlags::MODAL,
+            MessageType::Warning,
+            ButtonsType::None,
+            format!(
+                "Permanently delete {} ({})? It will not be moved to the trash.",
+                path.display(),
+                disk_analyzer::MountPoint::format_size(size)
+            )
+        );
+        dialog.set_title(Some("Delete File"));
+        dialog.add_button("Cancel", gtk4::ResponseType::Cancel);
+        dialog.add_button("Delete", gtk4::ResponseType::Ok);
+        
+        let window_clone = window.clone();
+        let row = row.clone();
+        let path = path.to_path_buf();
+        dialog.connect_response(move |dialog, response| {
+            dialog.close();
+            if response != gtk4::ResponseType::Ok {
+                return;
+            }
+            
+            match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    show_toast(&row, &format!("Deleted {}", path.display()));
+                    if let Some(list) = row.parent().and_downcast::<ListBox>() {
+                        list.remove(&row);
+                    }
+                }
+                Err(e) => {
+                    let error_dialog = MessageDialog::new(
+                        Some(&window_clone),
+                        gtk4::DialogFlags::MODAL,
+                        MessageType::Error,
+                        ButtonsType::Ok,
+                        format!("Could not delete {}: {}", path.display(), e)
+                    );
+                    error_dialog.set_title(Some("Failed to Delete File"));
+                    error_dialog.connect_response(|dialog, _| {
+                        dialog.close();
+                    });
+                    error_dialog.present();
+                }
+            }
+        });
+        
+        dialog.present();
+    }
+
+    fn show_folder_results(results: &ListBox, result: anyhow::Result<Vec<FolderInfo>>) {
+        match result {
+            Ok(folders) if folders.is_empty() => {
+                let row = adw::ActionRow::new();
+                row.set_title("No subfolders found");
+                results.append(&row);
+            }
+            Ok(folders) => {
+                for folder in folders.iter().take(20) {
+                    let row = adw::ActionRow::new();
+                    row.set_title(&folder.path.display().to_string());
+                    row.set_subtitle(&format!(
+                        "{} on disk • {} files, {} folders",
+                        folder.format_size_on_disk(),
+                        folder.file_count,
+                        folder.dir_count
+                    ));
+
+                    let icon = gtk4::Image::from_icon_name("folder-symbolic");
+                    row.add_prefix(&icon);
+
+                    let size_label = Label::new(Some(&folder.format_size()));
+                    row.add_suffix(&size_label);
+
+                    results.append(&row);
+                }
+            }
+            Err(e) => {
+                let row = adw::ActionRow::new();
+                row.set_title("Error scanning folder");
+                row.set_subtitle(&e.to_string());
+                let icon = gtk4::Image::from_icon_name("dialog-error-symbolic");
+                row.add_prefix(&icon);
+                results.append(&row);
+            }
+        }
+    }
+
+    fn show_large_folders(group: &adw::PreferencesGroup, result: anyhow::Result<Vec<FolderInfo>>) {
+        match result {
             Ok(folders) => {
                 for folder in folders.iter().take(10) {
                     let row = adw::ActionRow::new();
@@ -103,8 +641,8 @@ impl DiskAnalyzerView {
                     }
                     
                     row.set_subtitle(&format!(
-                        "{} ({} files, {} folders)",
-                        folder.format_size(),
+                        "{} on disk ({} files, {} folders)",
+                        folder.format_size_on_disk(),
                         folder.file_count,
                         folder.dir_count
                     ));
@@ -116,46 +654,37 @@ impl DiskAnalyzerView {
                     size_label.add_css_class("title-3");
                     row.add_suffix(&size_label);
                     
-                    large_folders_group.add(&row);
+                    group.add(&row);
                 }
             }
             Err(e) => {
                 let error_row = adw::ActionRow::new();
                 error_row.set_title("Error scanning folders");
                 error_row.set_subtitle(&e.to_string());
-                large_folders_group.add(&error_row);
+                group.add(&error_row);
             }
         }
+    }
+}
 
-        content.append(&large_folders_group);
-
-        // Cleanup suggestions
-        let suggestions_group = adw::PreferencesGroup::new();
-        suggestions_group.set_title("Cleanup Suggestions");
-        suggestions_group.set_description(Some("Common ways to free up disk space"));
-
-        for suggestion in disk_analyzer::get_cleanup_suggestions() {
-            let row = adw::ActionRow::new();
-            row.set_title(&suggestion);
-            
-            let icon = gtk4::Image::from_icon_name("user-trash-symbolic");
-            row.add_prefix(&icon);
-            
-            suggestions_group.add(&row);
-        }
-
-        content.append(&suggestions_group);
+impl Module for DiskAnalyzerView {
+    fn id(&self) -> &str {
+        "disk"
+    }
 
-        let scrolled = gtk4::ScrolledWindow::new();
-        scrolled.set_vexpand(true);
-        scrolled.set_child(Some(&content));
+    fn name(&self) -> &str {
+        "Disk Analyzer"
+    }
 
-        root.append(&scrolled);
+    fn description(&self) -> &str {
+        "Disk usage by mount point and folder"
+    }
 
-        Self { root }
+    fn icon_name(&self) -> &str {
+        "drive-harddisk-symbolic"
     }
 
-    pub fn build(&self) -> GtkBox {
-        self.root.clone()
+    fn widget(&self) -> gtk4::Widget {
+        self.root.clone().upcast()
     }
 }
@@ -1,92 +1,243 @@
-use gtk4::{Stack, StackTransitionType, Button, Box as GtkBox, Orientation};
+use gtk4::{Stack, StackTransitionType, Button, Box as GtkBox, Orientation, FileChooserAction, FileChooserNative, FileFilter, MessageDialog, ButtonsType, MessageType, ResponseType, glib};
 use libadwaita as adw;
 use adw::prelude::*;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::Duration;
 
-use super::{Sidebar, SystemView, TaskManagerView, DiskAnalyzerView, PackageManagerView, 
-            ServiceManagerView, StartupManagerView, SystemCleanerView};
+use crate::report::{self, ReportFormat};
+
+use super::Sidebar;
+use crate::config::{ColorScheme, Settings};
+use crate::module_loader::ModuleManager;
+
+/// Window width below which the sidebar collapses into an overlay
+const SIDEBAR_COLLAPSE_WIDTH: &str = "max-width: 700sp";
 
 pub struct MainWindow {
-    root: GtkBox,
+    root: adw::BreakpointBin,
+    toast_overlay: adw::ToastOverlay,
 }
 
 impl MainWindow {
-    pub fn new() -> Self {
-        let root = GtkBox::new(Orientation::Vertical, 0);
+    pub fn new(module_manager: &ModuleManager, settings: &Rc<RefCell<Settings>>) -> Self {
+        let content = GtkBox::new(Orientation::Vertical, 0);
         
         // Create stack for switching views
         let stack = Stack::new();
         stack.set_transition_type(StackTransitionType::Crossfade);
         stack.set_transition_duration(200);
 
-        // Add all views to stack
-        let system_view = SystemView::new();
-        stack.add_titled(&system_view.build(), Some("system"), "System Info");
-
-        let task_view = TaskManagerView::new();
-        stack.add_titled(&task_view.build(), Some("tasks"), "Task Manager");
-
-        let disk_view = DiskAnalyzerView::new();
-        stack.add_titled(&disk_view.build(), Some("disk"), "Disk Analyzer");
-
-        let package_view = PackageManagerView::new();
-        stack.add_titled(&package_view.build(), Some("packages"), "Package Manager");
-
-        let service_view = ServiceManagerView::new();
-        stack.add_titled(&service_view.build(), Some("services"), "Service Manager");
-
-        let startup_view = StartupManagerView::new();
-        stack.add_titled(&startup_view.build(), Some("startup"), "Startup Manager");
-
-        let cleaner_view = SystemCleanerView::new();
-        stack.add_titled(&cleaner_view.build(), Some("cleaner"), "System Cleaner");
-
-        // Create sidebar with stack reference
-        let sidebar = Sidebar::new_with_stack(&stack);
-
-        // Create flap (collapsible sidebar)
-        let flap = adw::Flap::new();
-        flap.set_flap(Some(&sidebar.build()));
-        flap.set_content(Some(&stack));
-        flap.set_flap_position(gtk4::PackType::Start);
-        flap.set_fold_policy(adw::FlapFoldPolicy::Auto);
-        flap.set_locked(false);
-        flap.set_reveal_flap(true);
-        flap.set_swipe_to_open(true);
-        flap.set_swipe_to_close(true);
+        // Create sidebar, which adds a stack page per module
+        let sidebar = Sidebar::new_with_stack(&stack, module_manager);
+        
+        // Reopen the module that was shown last time
+        if let Some(last_module) = &settings.borrow().last_module {
+            sidebar.select_module(last_module);
+        }
+
+        // Create split view (collapsible sidebar)
+        let split_view = adw::OverlaySplitView::new();
+        split_view.set_sidebar(Some(&sidebar.build()));
+        split_view.set_content(Some(&stack));
+        split_view.set_sidebar_position(gtk4::PackType::Start);
+        split_view.set_show_sidebar(true);
+        split_view.set_enable_show_gesture(true);
+        split_view.set_enable_hide_gesture(true);
+        
+        // Collapse the sidebar into an overlay on narrow windows
+        let root = adw::BreakpointBin::new();
+        root.set_width_request(360);
+        root.set_height_request(300);
+        let breakpoint = adw::Breakpoint::new(
+            adw::BreakpointCondition::parse(SIDEBAR_COLLAPSE_WIDTH).expect("valid breakpoint condition")
+        );
+        breakpoint.add_setter(&split_view, "collapsed", Some(&true.to_value()));
+        root.add_breakpoint(breakpoint);
 
         // Create header bar with menu button for sidebar
         let header = adw::HeaderBar::new();
         
         // Add sidebar toggle button
         let toggle_button = Button::from_icon_name("open-menu-symbolic");
-        let flap_clone = flap.clone();
+        let split_view_clone = split_view.clone();
         toggle_button.connect_clicked(move |_| {
-            let reveal = flap_clone.reveals_flap();
-            flap_clone.set_reveal_flap(!reveal);
+            let show = split_view_clone.shows_sidebar();
+            split_view_clone.set_show_sidebar(!show);
         });
         header.pack_start(&toggle_button);
         
+        // Theme toggle cycling System → Light → Dark
+        let theme_button = Button::new();
+        Self::update_theme_button(&theme_button, settings.borrow().color_scheme);
+        let settings_clone = settings.clone();
+        theme_button.connect_clicked(move |btn| {
+            let mut settings = settings_clone.borrow_mut();
+            settings.color_scheme = settings.color_scheme.next();
+            adw::StyleManager::default().set_color_scheme(settings.color_scheme.to_adw());
+            Self::update_theme_button(btn, settings.color_scheme);
+            settings.save_or_warn();
+        });
+        header.pack_end(&theme_button);
+        
+        // Safe mode: destructive actions are only simulated
+        let safe_mode_button = gtk4::ToggleButton::new();
+        safe_mode_button.set_icon_name("security-high-symbolic");
+        safe_mode_button.set_tooltip_text(Some("Safe Mode: simulate package and cleanup actions"));
+        safe_mode_button.set_active(settings.borrow().safe_mode);
+        let settings_clone = settings.clone();
+        safe_mode_button.connect_toggled(move |btn| {
+            let mut settings = settings_clone.borrow_mut();
+            settings.safe_mode = btn.is_active();
+            settings.save_or_warn();
+        });
+        header.pack_end(&safe_mode_button);
+        
+        // Export system report button
+        let export_button = Button::from_icon_name("document-save-symbolic");
+        export_button.set_tooltip_text(Some("Export System Report"));
+        export_button.connect_clicked(Self::export_report);
+        header.pack_end(&export_button);
+        
         // Get title from current stack page
-        let title = adw::WindowTitle::new("SysMate", "System Info");
+        let current_page = stack.visible_child()
+            .and_then(|page| stack.page(&page).title())
+            .unwrap_or_default();
+        let title = adw::WindowTitle::new("SysMate", &current_page);
         header.set_title_widget(Some(&title));
         
-        // Update title when stack changes
+        // Update title and remember the module when stack changes
         let title_clone = title.clone();
+        let settings_clone = settings.clone();
+        let split_view_clone = split_view.clone();
         stack.connect_visible_child_notify(move |stack| {
+            // The overlaid sidebar would cover the page that was just picked
+            if split_view_clone.is_collapsed() {
+                split_view_clone.set_show_sidebar(false);
+            }
+            
             if let Some(page) = stack.visible_child() {
                 if let Some(page_title) = stack.page(&page).title() {
                     title_clone.set_subtitle(&page_title);
                 }
             }
+            
+            let mut settings = settings_clone.borrow_mut();
+            let page_name = stack.visible_child_name().map(|name| name.to_string());
+            if page_name.is_some() && settings.last_module != page_name {
+                settings.last_module = page_name;
+                settings.save_or_warn();
+            }
         });
 
-        root.append(&header);
-        root.append(&flap);
+        // Views report finished operations as toasts over the page area
+        let toast_overlay = adw::ToastOverlay::new();
+        toast_overlay.set_child(Some(&split_view));
+        toast_overlay.set_vexpand(true);
 
-        Self { root }
+        content.append(&header);
+        content.append(&toast_overlay);
+        root.set_child(Some(&content));
+
+        Self { root, toast_overlay }
+    }
+
+    fn update_theme_button(btn: &Button, scheme: ColorScheme) {
+        btn.set_icon_name(scheme.icon_name());
+        btn.set_tooltip_text(Some(&format!("Appearance: {}", scheme.label())));
     }
 
-    pub fn build(&self) -> GtkBox {
+    fn export_report(btn: &Button) {
+        let window = btn.root().and_downcast::<gtk4::Window>();
+        
+        let chooser = FileChooserNative::new(
+            Some("Export System Report"),
+            window.as_ref(),
+            FileChooserAction::Save,
+            Some("Export"),
+            Some("Cancel"),
+        );
+        chooser.set_current_name("sysmate-report.md");
+        
+        let markdown_filter = FileFilter::new();
+        markdown_filter.set_name(Some("Markdown"));
+        markdown_filter.add_pattern("*.md");
+        chooser.add_filter(&markdown_filter);
+        
+        let json_filter = FileFilter::new();
+        json_filter.set_name(Some("JSON"));
+        json_filter.add_pattern("*.json");
+        chooser.add_filter(&json_filter);
+        
+        let btn = btn.clone();
+        chooser.connect_response(move |chooser, response| {
+            let path = chooser.file().and_then(|file| file.path());
+            chooser.destroy();
+            
+            let (ResponseType::Accept, Some(path)) = (response, path) else {
+                return;
+            };
+            Self::write_report(&btn, path);
+        });
+        
+        chooser.show();
+    }
+    
+    /// Generate the report on a worker thread and write it to `path`
+    fn write_report(btn: &Button, path: PathBuf) {
+        let format = ReportFormat::from_path(&path);
+        
+        btn.set_sensitive(false);
+        
+        let (sender, receiver) = mpsc::channel::<anyhow::Result<()>>();
+        let path_clone = path.clone();
+        std::thread::spawn(move || {
+            let result = report::generate_report(format)
+                .and_then(|contents| Ok(std::fs::write(&path_clone, contents)?));
+            let _ = sender.send(result);
+        });
+        
+        let btn = btn.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            let result = match receiver.try_recv() {
+                Ok(result) => result,
+                Err(TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(TryRecvError::Disconnected) => Err(anyhow::anyhow!("Report generation stopped unexpectedly")),
+            };
+            btn.set_sensitive(true);
+            
+            let (message_type, title, message) = match result {
+                Ok(()) => (MessageType::Info, "Report Exported", format!("System report saved to {}", path.display())),
+                Err(e) => (MessageType::Error, "Failed to Export Report", e.to_string()),
+            };
+            
+            if let Some(window) = btn.root().and_downcast::<gtk4::Window>() {
+                let dialog = MessageDialog::new(
+                    Some(&window),
+                    gtk4::DialogFlags::MODAL,
+                    message_type,
+                    ButtonsType::Ok,
+                    message
+                );
+                dialog.set_title(Some(title));
+                dialog.connect_response(|dialog, _| {
+                    dialog.close();
+                });
+                dialog.present();
+            }
+            
+            glib::ControlFlow::Break
+        });
+    }
+
+    pub fn build(&self) -> adw::BreakpointBin {
         self.root.clone()
     }
+    
+    /// The overlay over the page area, for toasts from outside the views
+    pub fn toast_overlay(&self) -> adw::ToastOverlay {
+        self.toast_overlay.clone()
+    }
 }
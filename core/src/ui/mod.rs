@@ -2,18 +2,24 @@ mod main_window;
 mod sidebar;
 mod system_view;
 mod disk_analyzer_view;
+mod network_view;
 mod package_manager_view;
 mod service_manager_view;
 mod startup_manager_view;
 mod system_cleaner_view;
 mod task_manager_view;
+mod simulation;
+mod toast;
 
 pub use main_window::MainWindow;
 pub use sidebar::Sidebar;
 pub use system_view::SystemView;
 pub use disk_analyzer_view::DiskAnalyzerView;
+pub use network_view::NetworkView;
 pub use package_manager_view::PackageManagerView;
 pub use service_manager_view::ServiceManagerView;
 pub use startup_manager_view::StartupManagerView;
 pub use system_cleaner_view::SystemCleanerView;
 pub use task_manager_view::TaskManagerView;
+pub use simulation::show_simulation;
+pub use toast::show_toast;
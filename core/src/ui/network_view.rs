@@ -0,0 +1,162 @@
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Orientation, Label, Button, ScrolledWindow, ListBox};
+use libadwaita as adw;
+use adw::prelude::*;
+use gtk4::glib;
+use network_monitor::{get_listening_sockets, SocketInfo};
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::Duration;
+
+use crate::module_loader::Module;
+
+pub struct NetworkView {
+    root: GtkBox,
+}
+
+impl NetworkView {
+    pub fn new() -> Self {
+        let root = GtkBox::new(Orientation::Vertical, 0);
+        root.add_css_class("network-view");
+
+        // Info label and refresh button
+        let header_box = GtkBox::new(Orientation::Horizontal, 12);
+        header_box.set_margin_top(12);
+        header_box.set_margin_bottom(12);
+        header_box.set_margin_start(12);
+        header_box.set_margin_end(12);
+
+        let info_label = Label::new(Some("Programs waiting for connections on this machine. Owners of other users' sockets are only shown as root."));
+        info_label.add_css_class("dim-label");
+        info_label.set_hexpand(true);
+        info_label.set_xalign(0.0);
+        info_label.set_wrap(true);
+        header_box.append(&info_label);
+
+        let refresh_button = Button::from_icon_name("view-refresh-symbolic");
+        refresh_button.set_tooltip_text(Some("Refresh"));
+        header_box.append(&refresh_button);
+
+        root.append(&header_box);
+
+        // Scrolled window for list
+        let scrolled = ScrolledWindow::new();
+        scrolled.set_vexpand(true);
+        scrolled.set_hexpand(true);
+        scrolled.set_min_content_height(300);
+
+        let list_box = ListBox::new();
+        list_box.set_selection_mode(gtk4::SelectionMode::None);
+        list_box.add_css_class("boxed-list");
+        list_box.set_margin_start(12);
+        list_box.set_margin_end(12);
+        list_box.set_margin_bottom(12);
+        list_box.set_valign(gtk4::Align::Start);
+        scrolled.set_child(Some(&list_box));
+
+        root.append(&scrolled);
+
+        // Initial population
+        Self::populate_list(&list_box, &refresh_button);
+
+        let list_box_clone = list_box.clone();
+        refresh_button.connect_clicked(move |btn| {
+            Self::populate_list(&list_box_clone, btn);
+        });
+
+        Self { root }
+    }
+
+    /// Read the listening sockets on a worker thread, since every process's fds are walked
+    fn populate_list(list_box: &ListBox, refresh_button: &Button) {
+        while let Some(child) = list_box.first_child() {
+            list_box.remove(&child);
+        }
+
+        let loading_row = adw::ActionRow::new();
+        loading_row.set_property("title", "Looking for listening sockets...");
+        let spinner = gtk4::Spinner::new();
+        spinner.start();
+        loading_row.add_prefix(&spinner);
+        list_box.append(&loading_row);
+        refresh_button.set_sensitive(false);
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(get_listening_sockets());
+        });
+
+        let list_box = list_box.clone();
+        let refresh_button = refresh_button.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            let result = match rx.try_recv() {
+                Ok(result) => result,
+                Err(TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+            };
+
+            list_box.remove(&loading_row);
+            refresh_button.set_sensitive(true);
+
+            match result {
+                Ok(sockets) if sockets.is_empty() => {
+                    let empty_row = adw::ActionRow::new();
+                    empty_row.set_property("title", "Nothing is listening");
+                    list_box.append(&empty_row);
+                }
+                Ok(sockets) => {
+                    for socket in &sockets {
+                        list_box.append(&Self::create_socket_row(socket));
+                    }
+                }
+                Err(e) => {
+                    let error_row = adw::ActionRow::new();
+                    error_row.set_property("title", format!("Error: {}", e));
+                    list_box.append(&error_row);
+                }
+            }
+
+            glib::ControlFlow::Break
+        });
+    }
+
+    fn create_socket_row(socket: &SocketInfo) -> adw::ActionRow {
+        let row = adw::ActionRow::new();
+        row.set_title(&format!("{} {}", socket.proto.name(), socket.port));
+
+        let owner = match (&socket.process, socket.pid) {
+            (Some(process), Some(pid)) => format!("{} (PID {})", process, pid),
+            (None, Some(pid)) => format!("PID {}", pid),
+            _ => "Unknown process".to_string(),
+        };
+        row.set_subtitle(&format!("{} • {}", owner, socket.address()));
+
+        let reach = if socket.is_local_only() { "This machine only" } else { "Reachable from network" };
+        let reach_label = Label::new(Some(reach));
+        reach_label.add_css_class("dim-label");
+        row.add_suffix(&reach_label);
+
+        row
+    }
+}
+
+impl Module for NetworkView {
+    fn id(&self) -> &str {
+        "network"
+    }
+
+    fn name(&self) -> &str {
+        "Listening Ports"
+    }
+
+    fn description(&self) -> &str {
+        "Programs accepting network connections"
+    }
+
+    fn icon_name(&self) -> &str {
+        "network-wired-symbolic"
+    }
+
+    fn widget(&self) -> gtk4::Widget {
+        self.root.clone().upcast()
+    }
+}
@@ -3,18 +3,51 @@
 //! Shows package statistics and recent activity for APT, Snap, and Flatpak.
 
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Label, Orientation, Button, SearchEntry};
+use gtk4::{Box as GtkBox, Label, Orientation, Button, SearchEntry, CheckButton, MessageDialog, ButtonsType, MessageType, glib};
 use libadwaita as adw;
 use adw::prelude::*;
+use package_manager::{AptSource, AptTransaction, FileIssueKind, HistoryAction, Outcome, PackageBackend, PackageDiff, RebootStatus, UpdateCoverage};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::Duration;
+
+use crate::config::Settings;
+use crate::module_loader::Module;
+use super::{show_simulation, show_toast};
+
+/// Number of saved snapshots compared with the installed packages
+const SNAPSHOTS_SHOWN: usize = 5;
+
+/// Number of files listed when a package fails verification
+const FILE_ISSUES_SHOWN: usize = 20;
+
+/// Number of APT transactions listed in the history
+const HISTORY_SHOWN: usize = 10;
 
 pub struct PackageManagerView {
     root: GtkBox,
 }
 
 impl PackageManagerView {
-    pub fn new() -> Self {
+    pub fn new(settings: Rc<RefCell<Settings>>) -> Self {
         let root = GtkBox::new(Orientation::Vertical, 0);
 
+        // Pending reboot banner
+        let reboot_banner = adw::Banner::new("");
+        match package_manager::reboot_required() {
+            RebootStatus::NotRequired => {}
+            RebootStatus::Required => {
+                reboot_banner.set_title("Restart required to finish installing updates");
+                reboot_banner.set_revealed(true);
+            }
+            RebootStatus::RequiredBy(packages) => {
+                reboot_banner.set_title(&format!("Restart required to finish updating {}", packages.join(", ")));
+                reboot_banner.set_revealed(true);
+            }
+        }
+        root.append(&reboot_banner);
+
         // Action buttons bar
         let action_bar = GtkBox::new(Orientation::Horizontal, 12);
         action_bar.set_margin_top(12);
@@ -31,6 +64,10 @@ impl PackageManagerView {
         autoremove_btn.set_icon_name("user-trash-symbolic");
         action_bar.append(&autoremove_btn);
 
+        let include_extra_check = CheckButton::with_label("Include Snap and Flatpak");
+        include_extra_check.set_tooltip_text(Some("Also update Snap and Flatpak packages when upgrading"));
+        action_bar.append(&include_extra_check);
+
         let search_entry = SearchEntry::new();
         search_entry.set_placeholder_text(Some("Search packages..."));
         search_entry.set_hexpand(true);
@@ -63,13 +100,31 @@ impl PackageManagerView {
                         &stats.auto_removable.to_string(), "user-trash-symbolic");
                 }
                 
+                if stats.held > 0 {
+                    Self::add_stat_row(&overview_group, "Held Packages", 
+                        &stats.held.to_string(), "changes-prevent-symbolic");
+                }
+                
+                if package_manager::detect_backend() == PackageBackend::Apt {
+                    if let Ok(status) = package_manager::get_unattended_upgrades_status() {
+                        let value = match (status.enabled, status.coverage) {
+                            (true, UpdateCoverage::SecurityOnly) => "Security only",
+                            (true, UpdateCoverage::All) => "All updates",
+                            _ => "Off",
+                        };
+                        Self::add_stat_row(&overview_group, "Automatic Updates", 
+                            value, "emblem-synchronizing-symbolic");
+                    }
+                }
+                
                 content.append(&overview_group);
+                content.append(&Self::build_snapshots_group());
                 
                 // Package managers
                 let managers_group = adw::PreferencesGroup::new();
                 managers_group.set_title("Package Managers");
                 
-                Self::add_stat_row(&managers_group, "APT (Debian/Ubuntu)", 
+                Self::add_stat_row(&managers_group, package_manager::detect_backend().name(), 
                     &format!("{} packages", stats.total_installed), "package-x-generic-symbolic");
                 
                 let snap_count = package_manager::get_snap_count();
@@ -85,6 +140,17 @@ impl PackageManagerView {
                 }
                 
                 content.append(&managers_group);
+                
+                // apt-mark holds, offered as a toggle on package rows
+                let held: Vec<String> = if package_manager::detect_backend() == PackageBackend::Apt {
+                    package_manager::list_held_packages()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|pkg| pkg.name)
+                        .collect()
+                } else {
+                    Vec::new()
+                };
 
                 // Upgradeable packages
                 if stats.upgradeable > 0 {
@@ -93,33 +159,108 @@ impl PackageManagerView {
                         upgrade_group.set_title("Upgradeable Packages");
                         upgrade_group.set_description(Some("Packages with available updates"));
                         
+                        let upgrade_selected_btn = Button::with_label("Upgrade Selected");
+                        upgrade_selected_btn.set_valign(gtk4::Align::Center);
+                        upgrade_selected_btn.add_css_class("suggested-action");
+                        upgrade_selected_btn.set_sensitive(false);
+                        upgrade_group.set_header_suffix(Some(&upgrade_selected_btn));
+                        
+                        let mut selection = Vec::new();
                         for pkg in upgradeable.iter().take(20) {
                             let row = adw::ActionRow::new();
                             row.set_title(&pkg.name);
-                            row.set_subtitle(&format!("Current: {}", pkg.version));
+                            row.set_subtitle(&format!("{} → {}", pkg.current_version, pkg.available_version));
+                            
+                            let check = CheckButton::new();
+                            check.set_valign(gtk4::Align::Center);
+                            row.add_prefix(&check);
+                            row.set_activatable_widget(Some(&check));
+                            selection.push((pkg.name.clone(), check));
                             
                             let icon = gtk4::Image::from_icon_name("software-update-available-symbolic");
                             row.add_prefix(&icon);
                             
+                            if package_manager::detect_backend() == PackageBackend::Apt {
+                                row.add_suffix(&Self::build_hold_toggle(&pkg.name, held.contains(&pkg.name), &settings));
+                            }
+                            
                             let upgrade_pkg_btn = Button::with_label("Upgrade");
                             upgrade_pkg_btn.set_valign(gtk4::Align::Center);
                             upgrade_pkg_btn.add_css_class("flat");
                             
                             let pkg_name = pkg.name.clone();
-                            upgrade_pkg_btn.connect_clicked(move |_| {
-                                if let Err(e) = package_manager::install_package(&pkg_name) {
-                                    eprintln!("Failed to upgrade package: {}", e);
-                                }
+                            let settings_clone = settings.clone();
+                            upgrade_pkg_btn.connect_clicked(move |btn| {
+                                let dry_run = settings_clone.borrow().safe_mode;
+                                Self::report_result(btn, &format!("Upgraded {}", pkg_name), "Upgrade Failed", package_manager::install_package(&pkg_name, dry_run));
                             });
                             
                             row.add_suffix(&upgrade_pkg_btn);
                             upgrade_group.add(&row);
                         }
                         
+                        // Only offer "Upgrade Selected" while something is ticked
+                        let selection = Rc::new(selection);
+                        for (_, check) in selection.iter() {
+                            let selection_clone = selection.clone();
+                            let upgrade_selected_btn_clone = upgrade_selected_btn.clone();
+                            check.connect_toggled(move |_| {
+                                let any_selected = selection_clone.iter().any(|(_, check)| check.is_active());
+                                upgrade_selected_btn_clone.set_sensitive(any_selected);
+                            });
+                        }
+                        
+                        let settings_clone = settings.clone();
+                        upgrade_selected_btn.connect_clicked(move |btn| {
+                            let packages: Vec<String> = selection.iter()
+                                .filter(|(_, check)| check.is_active())
+                                .map(|(name, _)| name.clone())
+                                .collect();
+                            Self::confirm_upgrade_selected(btn, packages, settings_clone.borrow().safe_mode);
+                        });
+                        
                         content.append(&upgrade_group);
                     }
                 }
                 
+                // Leftover configuration of removed packages
+                if let Ok(residual) = package_manager::list_residual_config_packages() {
+                    if !residual.is_empty() {
+                        let residual_group = adw::PreferencesGroup::new();
+                        residual_group.set_title("Residual Configuration");
+                        residual_group.set_description(Some("Removed packages that left configuration files behind"));
+                        
+                        let purge_btn = Button::with_label("Purge All");
+                        purge_btn.set_valign(gtk4::Align::Center);
+                        purge_btn.add_css_class("destructive-action");
+                        let settings_clone = settings.clone();
+                        purge_btn.connect_clicked(move |btn| {
+                            let dry_run = settings_clone.borrow().safe_mode;
+                            Self::report_result(btn, "Purged residual configuration", "Purge Failed", package_manager::purge_residual_configs(dry_run));
+                        });
+                        residual_group.set_header_suffix(Some(&purge_btn));
+                        
+                        for pkg in residual.iter().take(20) {
+                            let row = adw::ActionRow::new();
+                            row.set_title(&pkg.name);
+                            row.set_subtitle(&pkg.description);
+                            
+                            let icon = gtk4::Image::from_icon_name("edit-clear-symbolic");
+                            row.add_prefix(&icon);
+                            residual_group.add(&row);
+                        }
+                        
+                        content.append(&residual_group);
+                    }
+                }
+                
+                // Configured repositories
+                if let Ok(sources) = package_manager::list_apt_sources() {
+                    if !sources.is_empty() {
+                        content.append(&Self::build_sources_group(sources));
+                    }
+                }
+                
                 // Recent activity
                 if let Ok(recent) = package_manager::list_recent_packages(10) {
                     if !recent.is_empty() {
@@ -135,16 +276,29 @@ impl PackageManagerView {
                             let icon = gtk4::Image::from_icon_name("package-x-generic-symbolic");
                             row.add_prefix(&icon);
                             
+                            if package_manager::detect_backend() == PackageBackend::Apt {
+                                let verify_btn = Button::with_label("Verify");
+                                verify_btn.set_valign(gtk4::Align::Center);
+                                verify_btn.set_tooltip_text(Some("Check the package's files against their checksums"));
+                                verify_btn.add_css_class("flat");
+                                
+                                let pkg_name = pkg.name.clone();
+                                verify_btn.connect_clicked(move |btn| {
+                                    Self::verify_package(btn, &pkg_name);
+                                });
+                                row.add_suffix(&verify_btn);
+                                row.add_suffix(&Self::build_hold_toggle(&pkg.name, held.contains(&pkg.name), &settings));
+                            }
+                            
                             let remove_btn = Button::with_label("Remove");
                             remove_btn.set_valign(gtk4::Align::Center);
                             remove_btn.add_css_class("flat");
                             remove_btn.add_css_class("destructive-action");
                             
                             let pkg_name = pkg.name.clone();
-                            remove_btn.connect_clicked(move |_| {
-                                if let Err(e) = package_manager::remove_package(&pkg_name) {
-                                    eprintln!("Failed to remove package: {}", e);
-                                }
+                            let settings_clone = settings.clone();
+                            remove_btn.connect_clicked(move |btn| {
+                                Self::confirm_remove(btn, &pkg_name, settings_clone.borrow().safe_mode);
                             });
                             
                             row.add_suffix(&remove_btn);
@@ -154,6 +308,13 @@ impl PackageManagerView {
                         content.append(&recent_group);
                     }
                 }
+                
+                // Transaction history
+                if let Ok(history) = package_manager::get_apt_history(HISTORY_SHOWN) {
+                    if !history.is_empty() {
+                        content.append(&Self::build_history_group(&history, &settings));
+                    }
+                }
             }
             Err(e) => {
                 let status = adw::StatusPage::new();
@@ -165,20 +326,42 @@ impl PackageManagerView {
         }
 
         // Button handlers
-        upgrade_btn.connect_clicked(|_| {
-            gtk4::glib::MainContext::default().spawn_local(async {
-                if let Err(e) = package_manager::upgrade_packages() {
-                    eprintln!("Failed to upgrade packages: {}", e);
+        upgrade_btn.connect_clicked(move |btn| {
+            let Some(window) = btn.root().and_downcast::<gtk4::Window>() else {
+                return;
+            };
+            
+            let message = match package_manager::get_upgrade_download_size() {
+                Ok(size) if size > 0 => format!(
+                    "Upgrade all packages? About {} will be downloaded.",
+                    system_cleaner::format_size(size)
+                ),
+                _ => "Upgrade all packages?".to_string(),
+            };
+            
+            let dialog = MessageDialog::new(
+                Some(&window),
+                gtk4::DialogFlags::MODAL,
+                MessageType::Question,
+                ButtonsType::OkCancel,
+                message
+            );
+            dialog.set_title(Some("Upgrade Packages"));
+            
+            let include_extra = include_extra_check.is_active();
+            dialog.connect_response(move |dialog, response| {
+                dialog.close();
+                if response == gtk4::ResponseType::Ok {
+                    Self::show_upgrade_dialog(Some(window.clone()), include_extra);
                 }
             });
+            
+            dialog.present();
         });
 
-        autoremove_btn.connect_clicked(|_| {
-            gtk4::glib::MainContext::default().spawn_local(async {
-                if let Err(e) = package_manager::autoremove_packages() {
-                    eprintln!("Failed to autoremove packages: {}", e);
-                }
-            });
+        let settings_clone = settings.clone();
+        autoremove_btn.connect_clicked(move |btn| {
+            Self::confirm_autoremove(btn, settings_clone.borrow().safe_mode);
         });
 
         // Search handler
@@ -191,14 +374,12 @@ impl PackageManagerView {
             
             if let Ok(results) = package_manager::search_packages(&query) {
                 // Clear previous search results
-                while let Some(child) = content_clone.last_child() {
+                if let Some(child) = content_clone.last_child() {
                     if let Some(group) = child.downcast_ref::<adw::PreferencesGroup>() {
                         if group.title() == "Search Results" {
                             content_clone.remove(&child);
-                            break;
                         }
                     }
-                    break;
                 }
                 
                 let search_group = adw::PreferencesGroup::new();
@@ -217,10 +398,10 @@ impl PackageManagerView {
                     install_btn.add_css_class("flat");
                     
                     let pkg_name = pkg.name.clone();
-                    install_btn.connect_clicked(move |_| {
-                        if let Err(e) = package_manager::install_package(&pkg_name) {
-                            eprintln!("Failed to install package: {}", e);
-                        }
+                    let settings_clone = settings.clone();
+                    install_btn.connect_clicked(move |btn| {
+                        let dry_run = settings_clone.borrow().safe_mode;
+                        Self::report_result(btn, &format!("Installed {}", pkg_name), "Installation Failed", package_manager::install_package(&pkg_name, dry_run));
                     });
                     
                     row.add_suffix(&install_btn);
@@ -240,6 +421,657 @@ impl PackageManagerView {
         Self { root }
     }
     
+    /// Run the upgrade on a worker thread and tail its output in a dialog
+    /// 
+    /// With `include_extra`, Snap and Flatpak are updated after APT. Each runs
+    /// independently, so one failing or missing does not skip the other.
+    fn show_upgrade_dialog(parent: Option<gtk4::Window>, include_extra: bool) {
+        let dialog = adw::Window::new();
+        dialog.set_title(Some("Upgrading Packages"));
+        dialog.set_default_size(800, 600);
+        dialog.set_modal(true);
+        if let Some(parent) = parent {
+            dialog.set_transient_for(Some(&parent));
+        }
+        
+        let dialog_box = GtkBox::new(Orientation::Vertical, 0);
+        
+        // Header
+        let header = adw::HeaderBar::new();
+        let title_widget = adw::WindowTitle::new("Upgrading Packages", "Running apt-get upgrade...");
+        header.set_title_widget(Some(&title_widget));
+        
+        let close_btn = Button::with_label("Close");
+        header.pack_end(&close_btn);
+        
+        dialog_box.append(&header);
+        
+        // Output text view
+        let scrolled = gtk4::ScrolledWindow::new();
+        scrolled.set_vexpand(true);
+        scrolled.set_hexpand(true);
+        
+        let text_view = gtk4::TextView::new();
+        text_view.set_editable(false);
+        text_view.set_monospace(true);
+        text_view.set_margin_top(12);
+        text_view.set_margin_bottom(12);
+        text_view.set_margin_start(12);
+        text_view.set_margin_end(12);
+        
+        scrolled.set_child(Some(&text_view));
+        dialog_box.append(&scrolled);
+        
+        dialog.set_content(Some(&dialog_box));
+        
+        let dialog_clone = dialog.clone();
+        close_btn.connect_clicked(move |_| {
+            dialog_clone.close();
+        });
+        
+        dialog.present();
+        
+        // Stream lines from the worker thread back to the main loop
+        // The final subtitle covers every step, so it is sent once the worker is done
+        let (tx, rx) = mpsc::channel::<String>();
+        let (summary_tx, summary_rx) = mpsc::channel::<String>();
+        std::thread::spawn(move || {
+            let mut failed = Vec::new();
+            if package_manager::upgrade_packages_streaming(|line| {
+                let _ = tx.send(line);
+            }).is_err() {
+                failed.push("APT");
+            }
+            
+            if include_extra {
+                let _ = tx.send("Refreshing Snap packages...".to_string());
+                let _ = tx.send(match package_manager::update_snaps() {
+                    Ok(()) => "Snap packages refreshed".to_string(),
+                    Err(e) => {
+                        failed.push("Snap");
+                        format!("Snap refresh failed: {}", e)
+                    }
+                });
+                
+                let _ = tx.send("Updating Flatpak applications...".to_string());
+                let _ = tx.send(match package_manager::update_flatpaks() {
+                    Ok(()) => "Flatpak applications updated".to_string(),
+                    Err(e) => {
+                        failed.push("Flatpak");
+                        format!("Flatpak update failed: {}", e)
+                    }
+                });
+            }
+            
+            let _ = summary_tx.send(if failed.is_empty() {
+                "Upgrade complete".to_string()
+            } else {
+                format!("Upgrade failed: {}", failed.join(", "))
+            });
+        });
+        
+        let buffer = text_view.buffer();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            let mut received = false;
+            let finished = loop {
+                match rx.try_recv() {
+                    Ok(line) => {
+                        buffer.insert(&mut buffer.end_iter(), &format!("{}\n", line));
+                        received = true;
+                    }
+                    Err(TryRecvError::Empty) => break false,
+                    Err(TryRecvError::Disconnected) => break true,
+                }
+            };
+            
+            if received {
+                text_view.scroll_to_iter(&mut buffer.end_iter(), 0.0, false, 0.0, 1.0);
+            }
+            
+            if finished {
+                if let Ok(summary) = summary_rx.try_recv() {
+                    title_widget.set_subtitle(&summary);
+                }
+                glib::ControlFlow::Break
+            } else {
+                glib::ControlFlow::Continue
+            }
+        });
+    }
+    
+    /// Upgrade the chosen packages, warning first about any that are on hold
+    /// 
+    /// apt refuses to upgrade held packages, so they are left out of the upgrade.
+    fn confirm_upgrade_selected(btn: &Button, packages: Vec<String>, dry_run: bool) {
+        let held: Vec<String> = package_manager::list_held_packages()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|pkg| pkg.name)
+            .filter(|name| packages.contains(name))
+            .collect();
+        let to_upgrade: Vec<String> = packages.into_iter()
+            .filter(|name| !held.contains(name))
+            .collect();
+        
+        let success = format!("Upgraded {} packages", to_upgrade.len());
+        if held.is_empty() {
+            Self::report_result(btn, &success, "Upgrade Failed", package_manager::upgrade_selected(&to_upgrade, dry_run));
+            return;
+        }
+        
+        let Some(window) = btn.root().and_downcast::<gtk4::Window>() else {
+            return;
+        };
+        
+        let (buttons, message) = if to_upgrade.is_empty() {
+            (ButtonsType::Ok, format!(
+                "All selected packages are on hold and cannot be upgraded: {}",
+                held.join(", ")
+            ))
+        } else {
+            (ButtonsType::OkCancel, format!(
+                "These packages are on hold and will be skipped: {}. Upgrade the other {} selected packages?",
+                held.join(", "),
+                to_upgrade.len()
+            ))
+        };
+        
+        let dialog = MessageDialog::new(
+            Some(&window),
+            gtk4::DialogFlags::MODAL,
+            MessageType::Warning,
+            buttons,
+            message
+        );
+        dialog.set_title(Some("Held Packages"));
+        
+        let btn = btn.clone();
+        dialog.connect_response(move |dialog, response| {
+            dialog.close();
+            if response == gtk4::ResponseType::Ok && !to_upgrade.is_empty() {
+                Self::report_result(&btn, &success, "Upgrade Failed", package_manager::upgrade_selected(&to_upgrade, dry_run));
+            }
+        });
+        
+        dialog.present();
+    }
+    
+    /// List what autoremove would delete and ask before running it
+    fn confirm_autoremove(btn: &Button, dry_run: bool) {
+        let packages = match package_manager::list_autoremovable_packages() {
+            Ok(packages) => packages,
+            Err(e) => {
+                Self::report_result(btn, "", "Auto Remove Failed", Err(e));
+                return;
+            }
+        };
+        if packages.is_empty() {
+            show_toast(btn, "No unused packages to remove");
+            return;
+        }
+        
+        let Some(window) = btn.root().and_downcast::<gtk4::Window>() else {
+            return;
+        };
+        
+        let mut lines: Vec<String> = packages.iter()
+            .take(15)
+            .map(|p| if p.version.is_empty() { p.name.clone() } else { format!("{} ({})", p.name, p.version) })
+            .collect();
+        if packages.len() > lines.len() {
+            lines.push(format!("and {} more", packages.len() - lines.len()));
+        }
+        
+        let dialog = MessageDialog::new(
+            Some(&window),
+            gtk4::DialogFlags::MODAL,
+            MessageType::Question,
+            ButtonsType::OkCancel,
+            format!(
+                "Remove {} {} that {} no longer needed?\n\n{}",
+                packages.len(),
+                if packages.len() == 1 { "package" } else { "packages" },
+                if packages.len() == 1 { "is" } else { "are" },
+                lines.join("\n")
+            )
+        );
+        dialog.set_title(Some("Auto Remove"));
+        
+        let btn = btn.clone();
+        dialog.connect_response(move |dialog, response| {
+            dialog.close();
+            if response == gtk4::ResponseType::Ok {
+                Self::report_result(&btn, "Removed unused packages", "Auto Remove Failed", package_manager::autoremove_packages(dry_run));
+            }
+        });
+        
+        dialog.present();
+    }
+    
+    /// Verify a package's files on a worker thread and report what was found
+    fn verify_package(btn: &Button, package: &str) {
+        btn.set_sensitive(false);
+        
+        let (tx, rx) = mpsc::channel();
+        let package_clone = package.to_string();
+        std::thread::spawn(move || {
+            let _ = tx.send(package_manager::verify_package(&package_clone));
+        });
+        
+        let btn = btn.clone();
+        let package = package.to_string();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            let result = match rx.try_recv() {
+                Ok(result) => result,
+                Err(TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+            };
+            btn.set_sensitive(true);
+            
+            let issues = match result {
+                Ok(issues) if issues.is_empty() => {
+                    show_toast(&btn, &format!("All files of {} are intact", package));
+                    return glib::ControlFlow::Break;
+                }
+                Ok(issues) => issues,
+                Err(e) => {
+                    Self::report_result(&btn, "", "Failed to Verify Package", Err(e));
+                    return glib::ControlFlow::Break;
+                }
+            };
+            
+            let mut lines: Vec<String> = issues.iter()
+                .take(FILE_ISSUES_SHOWN)
+                .map(|issue| match issue.kind {
+                    FileIssueKind::Modified => format!("Modified: {}", issue.path.display()),
+                    FileIssueKind::Missing => format!("Missing: {}", issue.path.display()),
+                })
+                .collect();
+            if issues.len() > FILE_ISSUES_SHOWN {
+                lines.push(format!("…and {} more", issues.len() - FILE_ISSUES_SHOWN));
+            }
+            
+            if let Some(window) = btn.root().and_downcast::<gtk4::Window>() {
+                let dialog = MessageDialog::new(
+                    Some(&window),
+                    gtk4::DialogFlags::MODAL,
+                    MessageType::Warning,
+                    ButtonsType::Ok,
+                    format!("{} files of {} differ from the package:\n\n{}", issues.len(), package, lines.join("\n"))
+                );
+                dialog.set_title(Some("Package Verification"));
+                dialog.connect_response(|dialog, _| {
+                    dialog.close();
+                });
+                dialog.present();
+            }
+            glib::ControlFlow::Break
+        });
+    }
+    
+    /// Remove a package, warning first if installed packages depend on it
+    fn confirm_remove(btn: &Button, package: &str, dry_run: bool) {
+        let rdeps = package_manager::get_reverse_dependencies(package).unwrap_or_default();
+        if rdeps.is_empty() {
+            Self::report_result(btn, &format!("Removed {}", package), "Removal Failed", package_manager::remove_package(package, dry_run));
+            return;
+        }
+        
+        if let Some(window) = btn.root().and_downcast::<gtk4::Window>() {
+            let shown: Vec<&str> = rdeps.iter().take(10).map(|s| s.as_str()).collect();
+            let more = if rdeps.len() > shown.len() {
+                format!(" and {} more", rdeps.len() - shown.len())
+            } else {
+                String::new()
+            };
+            
+            let dialog = MessageDialog::new(
+                Some(&window),
+                gtk4::DialogFlags::MODAL,
+                MessageType::Warning,
+                ButtonsType::OkCancel,
+                format!(
+                    "Remove package '{}'? {} installed packages depend on it: {}{}",
+                    package,
+                    rdeps.len(),
+                    shown.join(", "),
+                    more
+                )
+            );
+            dialog.set_title(Some("Remove Package"));
+            
+            let btn = btn.clone();
+            let package = package.to_string();
+            dialog.connect_response(move |dialog, response| {
+                dialog.close();
+                if response == gtk4::ResponseType::Ok {
+                    Self::report_result(&btn, &format!("Removed {}", package), "Removal Failed", package_manager::remove_package(&package, dry_run));
+                }
+            });
+            
+            dialog.present();
+        }
+    }
+    
+    /// Build a toggle that holds a package at its current version
+    /// 
+    /// If holding or releasing fails, or only a simulation ran, the toggle
+    /// returns to the package's actual state.
+    fn build_hold_toggle(package: &str, held: bool, settings: &Rc<RefCell<Settings>>) -> gtk4::ToggleButton {
+        let toggle = gtk4::ToggleButton::new();
+        toggle.set_icon_name("changes-prevent-symbolic");
+        toggle.set_tooltip_text(Some("Hold at current version"));
+        toggle.set_valign(gtk4::Align::Center);
+        toggle.add_css_class("flat");
+        toggle.set_active(held);
+        
+        let held = Rc::new(Cell::new(held));
+        let package = package.to_string();
+        let settings_clone = settings.clone();
+        toggle.connect_toggled(move |toggle| {
+            let hold = toggle.is_active();
+            if hold == held.get() {
+                return;
+            }
+            
+            let dry_run = settings_clone.borrow().safe_mode;
+            let (result, success, title) = if hold {
+                (package_manager::hold_package(&package, dry_run), format!("Holding {}", package), "Hold Failed")
+            } else {
+                (package_manager::unhold_package(&package, dry_run), format!("Released hold on {}", package), "Release Failed")
+            };
+            
+            let changed = matches!(result, Ok(Outcome::Done(())));
+            Self::report_result(toggle, &success, title, result);
+            if changed {
+                held.set(hold);
+            } else {
+                toggle.set_active(held.get());
+            }
+        });
+        
+        toggle
+    }
+    
+    /// Ask before installing an earlier version of a package
+    fn confirm_rollback(btn: &Button, package: &str, version: &str, dry_run: bool) {
+        let Some(window) = btn.root().and_downcast::<gtk4::Window>() else {
+            return;
+        };
+        
+        let dialog = MessageDialog::new(
+            Some(&window),
+            gtk4::DialogFlags::MODAL,
+            MessageType::Question,
+            ButtonsType::OkCancel,
+            format!("Install version {} of '{}'? Upgrades will offer the newer version again unless the package is held.", version, package)
+        );
+        dialog.set_title(Some("Roll Back Package"));
+        
+        let btn = btn.clone();
+        let package = package.to_string();
+        let version = version.to_string();
+        dialog.connect_response(move |dialog, response| {
+            dialog.close();
+            if response == gtk4::ResponseType::Ok {
+                let result = package_manager::install_package_version(&package, &version, dry_run);
+                Self::report_result(&btn, &format!("Installed {} {}", package, version), "Roll Back Failed", result);
+            }
+        });
+        
+        dialog.present();
+    }
+    
+    /// Show a toast for a finished package operation, or an error dialog if it failed
+    /// 
+    /// A simulated operation shows its planned changes instead. A dismissed
+    /// authentication prompt is the user's own choice, so it is not reported.
+    fn report_result(widget: &impl IsA<gtk4::Widget>, success: &str, title: &str, result: anyhow::Result<Outcome<()>>) {
+        let e = match result {
+            Ok(Outcome::Done(())) => return show_toast(widget, success),
+            Ok(Outcome::Simulated(changes)) => return show_simulation(widget, success, &changes),
+            Err(e) => e,
+        };
+        
+        if e.downcast_ref::<package_manager::AuthenticationDismissed>().is_some() {
+            return;
+        }
+        
+        if let Some(window) = widget.root().and_downcast::<gtk4::Window>() {
+            let dialog = MessageDialog::new(
+                Some(&window),
+                gtk4::DialogFlags::MODAL,
+                MessageType::Error,
+                ButtonsType::Ok,
+                e.to_string()
+            );
+            dialog.set_title(Some(title));
+            dialog.connect_response(|dialog, _| {
+                dialog.close();
+            });
+            dialog.present();
+        }
+    }
+    
+    /// Build the "Package Snapshots" group, comparing saved snapshots with the
+    /// packages installed now
+    fn build_snapshots_group() -> adw::PreferencesGroup {
+        let group = adw::PreferencesGroup::new();
+        group.set_title("Package Snapshots");
+        group.set_description(Some("What changed since a snapshot was taken"));
+        
+        let snapshot_btn = Button::with_label("Take Snapshot");
+        snapshot_btn.set_valign(gtk4::Align::Center);
+        snapshot_btn.set_tooltip_text(Some("Save the installed packages and their versions"));
+        snapshot_btn.connect_clicked(|btn| {
+            let result = package_manager::snapshot_installed_packages()
+                .and_then(|snapshot| package_manager::save_snapshot(&snapshot))
+                .map(|_| Outcome::Done(()));
+            Self::report_result(btn, "Snapshot saved", "Failed to Save Snapshot", result);
+        });
+        group.set_header_suffix(Some(&snapshot_btn));
+        
+        let snapshots = package_manager::list_snapshots().unwrap_or_default();
+        let current = match package_manager::snapshot_installed_packages() {
+            Ok(current) if !snapshots.is_empty() => current,
+            _ => {
+                let row = adw::ActionRow::new();
+                row.set_title("No snapshots yet");
+                row.set_subtitle("Take a snapshot to see which packages change from now on");
+                group.add(&row);
+                return group;
+            }
+        };
+        
+        for snapshot in snapshots.iter().take(SNAPSHOTS_SHOWN) {
+            let diff = package_manager::diff_snapshots(snapshot, &current);
+            let age = current.taken_at.saturating_sub(snapshot.taken_at);
+            
+            let row = adw::ExpanderRow::new();
+            row.set_title(&format!("Snapshot from {}", Self::format_age(age)));
+            row.set_subtitle(&Self::diff_summary(&diff));
+            row.set_enable_expansion(!diff.is_empty());
+            
+            let changes = diff.upgraded.iter()
+                .map(|change| (change.name.clone(), format!("{} → {}", change.old_version, change.new_version)))
+                .chain(diff.added.iter().map(|package| (package.name.clone(), format!("Installed {}", package.version))))
+                .chain(diff.removed.iter().map(|package| (package.name.clone(), format!("Removed {}", package.version))));
+            for (name, change) in changes {
+                let change_row = adw::ActionRow::new();
+                change_row.set_title(&name);
+                change_row.set_subtitle(&change);
+                row.add_row(&change_row);
+            }
+            
+            group.add(&row);
+        }
+        
+        group
+    }
+    
+    /// Build the "History" group, one expandable row per APT transaction
+    fn build_history_group(history: &[AptTransaction], settings: &Rc<RefCell<Settings>>) -> adw::PreferencesGroup {
+        let group = adw::PreferencesGroup::new();
+        group.set_title("History");
+        group.set_description(Some("Recent installs, upgrades and removals"));
+        
+        for transaction in history {
+            let row = adw::ExpanderRow::new();
+            row.set_title(&match transaction.packages.as_slice() {
+                [package] => format!("{} {}", transaction.action.label(), package.name),
+                packages => format!("{} {} packages", transaction.action.label(), packages.len()),
+            });
+            
+            let mut subtitle = transaction.start_time.clone();
+            if let Some(user) = &transaction.requested_by {
+                subtitle.push_str(&format!(" • by {}", user));
+            }
+            row.set_subtitle(&subtitle);
+            if !transaction.command.is_empty() {
+                row.set_tooltip_text(Some(&transaction.command));
+            }
+            
+            for package in &transaction.packages {
+                let change = match (transaction.action, &package.from_version, &package.to_version) {
+                    (HistoryAction::Upgrade | HistoryAction::Downgrade, Some(from), Some(to)) => format!("{} → {}", from, to),
+                    (_, _, Some(version)) | (_, Some(version), None) => version.clone(),
+                    _ => String::new(),
+                };
+                let package_row = adw::ActionRow::new();
+                package_row.set_title(&package.name);
+                package_row.set_subtitle(&change);
+
+                // Go back to the version from before the upgrade
+                if let (HistoryAction::Upgrade, Some(from)) = (transaction.action, &package.from_version) {
+                    let rollback_btn = Button::with_label("Roll Back");
+                    rollback_btn.set_valign(gtk4::Align::Center);
+                    rollback_btn.set_tooltip_text(Some(&format!("Install {} again", from)));
+                    rollback_btn.add_css_class("flat");
+
+                    let pkg_name = package.name.clone();
+                    let version = from.clone();
+                    let settings_clone = settings.clone();
+                    rollback_btn.connect_clicked(move |btn| {
+                        Self::confirm_rollback(btn, &pkg_name, &version, settings_clone.borrow().safe_mode);
+                    });
+                    package_row.add_suffix(&rollback_btn);
+                }
+
+                row.add_row(&package_row);
+            }
+            
+            group.add(&row);
+        }
+        
+        group
+    }
+    
+    /// Summarise a diff, e.g. "12 upgraded, 2 installed"
+    fn diff_summary(diff: &PackageDiff) -> String {
+        if diff.is_empty() {
+            return "No changes".to_string();
+        }
+        
+        [
+            (diff.upgraded.len(), "upgraded"),
+            (diff.added.len(), "installed"),
+            (diff.removed.len(), "removed"),
+        ]
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, label)| format!("{} {}", count, label))
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+    
+    fn format_age(secs: u64) -> String {
+        match secs / 86400 {
+            0 => "today".to_string(),
+            1 => "yesterday".to_string(),
+            7..=13 => "last week".to_string(),
+            days => format!("{} days ago", days),
+        }
+    }
+    
+    /// Build the "Software Sources" group, with a button to check every enabled source
+    fn build_sources_group(sources: Vec<AptSource>) -> adw::PreferencesGroup {
+        let group = adw::PreferencesGroup::new();
+        group.set_title("Software Sources");
+        group.set_description(Some("Repositories APT downloads packages from"));
+        
+        let check_btn = Button::with_label("Check");
+        check_btn.set_valign(gtk4::Align::Center);
+        check_btn.set_tooltip_text(Some("Check that each enabled source is reachable"));
+        group.set_header_suffix(Some(&check_btn));
+        
+        let mut rows = Vec::new();
+        for source in &sources {
+            let row = adw::ActionRow::new();
+            row.set_title(&source.uri);
+            let mut subtitle = format!("{} {}", source.suite, source.components.join(" ")).trim().to_string();
+            if !source.enabled {
+                subtitle.push_str(" · Disabled");
+                row.add_css_class("dim-label");
+            }
+            row.set_subtitle(&subtitle);
+            row.set_tooltip_text(Some(&source.file.display().to_string()));
+            
+            let icon = gtk4::Image::from_icon_name("network-server-symbolic");
+            row.add_prefix(&icon);
+            
+            let status = gtk4::Image::new();
+            status.set_visible(false);
+            row.add_suffix(&status);
+            
+            group.add(&row);
+            rows.push((row, status, subtitle));
+        }
+        
+        let rows = Rc::new(rows);
+        check_btn.connect_clicked(move |btn| {
+            btn.set_sensitive(false);
+            for (row, status, subtitle) in rows.iter() {
+                row.set_subtitle(subtitle);
+                status.set_visible(false);
+            }
+            
+            let sources = sources.clone();
+            let (tx, rx) = mpsc::channel();
+            std::thread::spawn(move || {
+                for (index, source) in sources.iter().enumerate().filter(|(_, s)| s.enabled) {
+                    let _ = tx.send((index, package_manager::check_source_reachability(source)));
+                }
+            });
+            
+            let btn = btn.clone();
+            let rows = rows.clone();
+            glib::timeout_add_local(Duration::from_millis(100), move || {
+                loop {
+                    match rx.try_recv() {
+                        Ok((index, result)) => {
+                            let (row, status, _) = &rows[index];
+                            match result {
+                                Ok(()) => status.set_icon_name(Some("emblem-ok-symbolic")),
+                                Err(e) => {
+                                    status.set_icon_name(Some("dialog-warning-symbolic"));
+                                    row.set_subtitle(&e.to_string());
+                                }
+                            }
+                            status.set_visible(true);
+                        }
+                        Err(TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                        Err(TryRecvError::Disconnected) => {
+                            btn.set_sensitive(true);
+                            return glib::ControlFlow::Break;
+                        }
+                    }
+                }
+            });
+        });
+        
+        group
+    }
+    
     fn add_stat_row(group: &adw::PreferencesGroup, title: &str, value: &str, icon_name: &str) {
         let row = adw::ActionRow::new();
         row.set_title(title);
@@ -253,8 +1085,26 @@ impl PackageManagerView {
         
         group.add(&row);
     }
+}
+
+impl Module for PackageManagerView {
+    fn id(&self) -> &str {
+        "packages"
+    }
+
+    fn name(&self) -> &str {
+        "Package Manager"
+    }
+
+    fn description(&self) -> &str {
+        "Install, remove and upgrade packages"
+    }
+
+    fn icon_name(&self) -> &str {
+        "package-x-generic-symbolic"
+    }
 
-    pub fn build(&self) -> GtkBox {
-        self.root.clone()
+    fn widget(&self) -> gtk4::Widget {
+        self.root.clone().upcast()
     }
 }
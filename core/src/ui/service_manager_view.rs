@@ -3,11 +3,99 @@
 //! Lists systemd services grouped by status with expandable details and management controls.
 
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Label, Orientation, Button, SearchEntry, DropDown, MessageDialog, ButtonsType, MessageType};
+use gtk4::{Box as GtkBox, Label, Orientation, Button, SearchEntry, DropDown, MessageDialog, ButtonsType, MessageType, glib};
 use libadwaita as adw;
 use adw::prelude::*;
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::Duration;
+
+use crate::module_loader::Module;
+use super::show_toast;
+
+/// History offered by the log window, in lines; 0 loads the whole journal
+const LOG_LINE_COUNTS: [usize; 4] = [100, 500, 1000, 0];
+
+/// Text tag marking every search match in a text dialog
+const SEARCH_MATCH_TAG: &str = "search-match";
+
+/// Text tag marking the match that was scrolled to
+const SEARCH_CURRENT_TAG: &str = "search-current";
+
+/// Search state of a text dialog
+/// 
+/// Matches are stored as character offsets into the buffer.
+struct LogSearch {
+    text_view: gtk4::TextView,
+    match_label: Label,
+    matches: RefCell<Vec<(i32, i32)>>,
+    current: RefCell<Option<usize>>,
+}
+
+impl LogSearch {
+    /// Highlight all case-insensitive matches of `query` and jump to the first
+    fn find(&self, query: &str) {
+        let buffer = self.text_view.buffer();
+        let (start, end) = buffer.bounds();
+        buffer.remove_tag_by_name(SEARCH_MATCH_TAG, &start, &end);
+        buffer.remove_tag_by_name(SEARCH_CURRENT_TAG, &start, &end);
+        
+        let mut matches = Vec::new();
+        if !query.is_empty() {
+            let mut iter = buffer.start_iter();
+            while let Some((match_start, match_end)) = iter.forward_search(query, gtk4::TextSearchFlags::CASE_INSENSITIVE, None) {
+                buffer.apply_tag_by_name(SEARCH_MATCH_TAG, &match_start, &match_end);
+                matches.push((match_start.offset(), match_end.offset()));
+                iter = match_end;
+            }
+        }
+        
+        let current = if matches.is_empty() { None } else { Some(0) };
+        *self.matches.borrow_mut() = matches;
+        self.select(current);
+        
+        if query.is_empty() {
+            self.match_label.set_text("");
+        }
+    }
+    
+    /// Move to the next or previous match, wrapping around
+    fn step(&self, forward: bool) {
+        let count = self.matches.borrow().len();
+        if count == 0 {
+            return;
+        }
+        
+        let current = match *self.current.borrow() {
+            Some(index) if forward => (index + 1) % count,
+            Some(index) => (index + count - 1) % count,
+            None => 0,
+        };
+        self.select(Some(current));
+    }
+    
+    /// Mark a match as current and scroll to it
+    fn select(&self, index: Option<usize>) {
+        let buffer = self.text_view.buffer();
+        let (start, end) = buffer.bounds();
+        buffer.remove_tag_by_name(SEARCH_CURRENT_TAG, &start, &end);
+        
+        let matches = self.matches.borrow();
+        *self.current.borrow_mut() = index;
+        
+        let Some(&(match_start, match_end)) = index.and_then(|i| matches.get(i)) else {
+            self.match_label.set_text("No matches");
+            return;
+        };
+        
+        let mut match_start = buffer.iter_at_offset(match_start);
+        let match_end = buffer.iter_at_offset(match_end);
+        buffer.apply_tag_by_name(SEARCH_CURRENT_TAG, &match_start, &match_end);
+        self.text_view.scroll_to_iter(&mut match_start, 0.1, false, 0.0, 0.0);
+        self.match_label.set_text(&format!("{} of {}", index.unwrap_or(0) + 1, matches.len()));
+    }
+}
 
 pub struct ServiceManagerView {
     root: GtkBox,
@@ -27,7 +115,7 @@ impl ServiceManagerView {
         let filter_label = Label::new(Some("Filter:"));
         toolbar.append(&filter_label);
 
-        let filter_list = gtk4::StringList::new(&["All", "Active", "Failed", "Inactive"]);
+        let filter_list = gtk4::StringList::new(&["All", "Active", "Failed", "Inactive", "Masked"]);
         let filter_dropdown = DropDown::new(Some(filter_list), None::<gtk4::Expression>);
         toolbar.append(&filter_dropdown);
 
@@ -52,7 +140,14 @@ impl ServiceManagerView {
                 content.remove(&child);
             }
 
-            match service_manager::list_services_with_limit(Some(100)) {
+            // Failed units are few, and listing them directly isn't capped like the full list
+            let services = if filter_state == Some(service_manager::ServiceState::Failed) {
+                service_manager::get_failed_services()
+            } else {
+                service_manager::list_services_with_limit(Some(100), true)
+            };
+            
+            match services {
                 Ok(mut services) => {
                     *services_rc.borrow_mut() = services.clone();
 
@@ -78,6 +173,8 @@ impl ServiceManagerView {
                         let active: Vec<_> = services.iter().filter(|s| s.state == service_manager::ServiceState::Active).collect();
                         let failed: Vec<_> = services.iter().filter(|s| s.state == service_manager::ServiceState::Failed).collect();
                         let inactive: Vec<_> = services.iter().filter(|s| s.state == service_manager::ServiceState::Inactive).collect();
+                        let transitional: Vec<_> = services.iter().filter(|s| s.state.is_transitional()).collect();
+                        let masked: Vec<_> = services.iter().filter(|s| s.state == service_manager::ServiceState::Masked).collect();
                         
                         // Active services
                         if !active.is_empty() {
@@ -91,11 +188,31 @@ impl ServiceManagerView {
                             content.append(&active_group);
                         }
                         
+                        // Services that are starting or stopping
+                        if !transitional.is_empty() {
+                            let transitional_group = adw::PreferencesGroup::new();
+                            transitional_group.set_title(&format!("Starting or Stopping ({})", transitional.len()));
+                            
+                            for service in transitional.iter() {
+                                Self::add_service_row(&transitional_group, service);
+                            }
+                            
+                            content.append(&transitional_group);
+                        }
+                        
                         // Failed services
                         if !failed.is_empty() {
                             let failed_group = adw::PreferencesGroup::new();
                             failed_group.set_title(&format!("Failed Services ({})", failed.len()));
                             
+                            let reset_all_btn = Button::with_label("Reset All");
+                            reset_all_btn.set_valign(gtk4::Align::Center);
+                            reset_all_btn.set_tooltip_text(Some("Clear the failed state of every failed service"));
+                            reset_all_btn.connect_clicked(|btn| {
+                                Self::report_result(btn, "Reset all failed services", "Failed to Reset Services", service_manager::reset_all_failed());
+                            });
+                            failed_group.set_header_suffix(Some(&reset_all_btn));
+                            
                             for service in failed.iter() {
                                 Self::add_service_row(&failed_group, service);
                             }
@@ -114,6 +231,18 @@ impl ServiceManagerView {
                             
                             content.append(&inactive_group);
                         }
+                        
+                        // Masked services
+                        if !masked.is_empty() {
+                            let masked_group = adw::PreferencesGroup::new();
+                            masked_group.set_title(&format!("Masked Services ({})", masked.len()));
+                            
+                            for service in masked.iter() {
+                                Self::add_service_row(&masked_group, service);
+                            }
+                            
+                            content.append(&masked_group);
+                        }
                     }
                 }
                 Err(e) => {
@@ -137,6 +266,7 @@ impl ServiceManagerView {
                 1 => Some(service_manager::ServiceState::Active),
                 2 => Some(service_manager::ServiceState::Failed),
                 3 => Some(service_manager::ServiceState::Inactive),
+                4 => Some(service_manager::ServiceState::Masked),
                 _ => None,
             };
             populate_services(&content_clone, &services_clone, filter_state, None);
@@ -186,22 +316,31 @@ impl ServiceManagerView {
         state_row.add_suffix(&state_detail_label);
         expander.add_row(&state_row);
 
+        let scope_row = adw::ActionRow::new();
+        scope_row.set_title("Scope");
+        let scope_label = Label::new(Some(service.scope.as_str()));
+        scope_label.add_css_class("dim-label");
+        scope_row.add_suffix(&scope_label);
+        expander.add_row(&scope_row);
+
         // Control buttons
         let controls_row = adw::ActionRow::new();
         controls_row.set_title("Controls");
 
         let controls_box = GtkBox::new(Orientation::Horizontal, 6);
 
-        // Start button
-        if service.state != service_manager::ServiceState::Active {
+        // Start button (masked units refuse to start)
+        if service.state != service_manager::ServiceState::Active && service.state != service_manager::ServiceState::Masked {
             let start_btn = Button::with_label("Start");
             start_btn.set_icon_name("media-playback-start-symbolic");
             start_btn.add_css_class("flat");
             start_btn.add_css_class("suggested-action");
             
             let service_name = service.name.clone();
+            let scope = service.scope;
             start_btn.connect_clicked(move |btn| {
                 let service_name_clone = service_name.clone();
+                let btn = btn.clone();
                 
                 // Show confirmation dialog
                 if let Some(window) = btn.root().and_downcast::<gtk4::Window>() {
@@ -210,17 +349,15 @@ impl ServiceManagerView {
                         gtk4::DialogFlags::MODAL,
                         MessageType::Question,
                         ButtonsType::OkCancel,
-                        &format!("Start service '{}'?", service_name)
+                        format!("Start service '{}'?", service_name)
                     );
                     dialog.set_title(Some("Start Service"));
                     
                     dialog.connect_response(move |dialog, response| {
+                        dialog.close();
                         if response == gtk4::ResponseType::Ok {
-                            if let Err(e) = service_manager::start_service(&service_name_clone) {
-                                eprintln!("Failed to start service: {}", e);
-                            }
+                            Self::report_result(&btn, &format!("Started {}", service_name_clone), "Failed to Start Service", service_manager::start_service(&service_name_clone, scope));
                         }
-                        dialog.close();
                     });
                     
                     dialog.present();
@@ -238,8 +375,10 @@ impl ServiceManagerView {
             stop_btn.add_css_class("destructive-action");
             
             let service_name = service.name.clone();
+            let scope = service.scope;
             stop_btn.connect_clicked(move |btn| {
                 let service_name_clone = service_name.clone();
+                let btn = btn.clone();
                 
                 // Show confirmation dialog
                 if let Some(window) = btn.root().and_downcast::<gtk4::Window>() {
@@ -248,17 +387,15 @@ impl ServiceManagerView {
                         gtk4::DialogFlags::MODAL,
                         MessageType::Warning,
                         ButtonsType::OkCancel,
-                        &format!("Stop service '{}'? This may affect system functionality.", service_name)
+                        format!("Stop service '{}'? This may affect system functionality.", service_name)
                     );
                     dialog.set_title(Some("Stop Service"));
                     
                     dialog.connect_response(move |dialog, response| {
+                        dialog.close();
                         if response == gtk4::ResponseType::Ok {
-                            if let Err(e) = service_manager::stop_service(&service_name_clone) {
-                                eprintln!("Failed to stop service: {}", e);
-                            }
+                            Self::report_result(&btn, &format!("Stopped {}", service_name_clone), "Failed to Stop Service", service_manager::stop_service(&service_name_clone, scope));
                         }
-                        dialog.close();
                     });
                     
                     dialog.present();
@@ -268,36 +405,89 @@ impl ServiceManagerView {
             controls_box.append(&stop_btn);
         }
 
+        // Reset button, so a failed unit can be restarted cleanly
+        if service.state == service_manager::ServiceState::Failed {
+            let reset_btn = Button::with_label("Reset");
+            reset_btn.set_icon_name("edit-clear-symbolic");
+            reset_btn.set_tooltip_text(Some("Clear the failed state"));
+            reset_btn.add_css_class("flat");
+            
+            let service_name = service.name.clone();
+            let scope = service.scope;
+            reset_btn.connect_clicked(move |btn| {
+                Self::report_result(btn, &format!("Reset {}", service_name), "Failed to Reset Service", service_manager::reset_failed_service(&service_name, scope));
+            });
+            
+            controls_box.append(&reset_btn);
+        }
+
         // Restart button
         let restart_btn = Button::with_label("Restart");
         restart_btn.set_icon_name("view-refresh-symbolic");
         restart_btn.add_css_class("flat");
         
         let service_name = service.name.clone();
-        restart_btn.connect_clicked(move |_| {
-            if let Err(e) = service_manager::restart_service(&service_name) {
-                eprintln!("Failed to restart service: {}", e);
+        let scope = service.scope;
+        restart_btn.connect_clicked(move |btn| {
+            let service_name_clone = service_name.clone();
+            let btn = btn.clone();
+            
+            // Show confirmation dialog
+            if let Some(window) = btn.root().and_downcast::<gtk4::Window>() {
+                let dialog = MessageDialog::new(
+                    Some(&window),
+                    gtk4::DialogFlags::MODAL,
+                    MessageType::Question,
+                    ButtonsType::OkCancel,
+                    format!("Restart service '{}'?", service_name)
+                );
+                dialog.set_title(Some("Restart Service"));
+                
+                dialog.connect_response(move |dialog, response| {
+                    dialog.close();
+                    if response == gtk4::ResponseType::Ok {
+                        Self::report_result(&btn, &format!("Restarted {}", service_name_clone), "Failed to Restart Service", service_manager::restart_service(&service_name_clone, scope));
+                    }
+                });
+                
+                dialog.present();
             }
         });
         
         controls_box.append(&restart_btn);
 
-        // Enable/Disable toggle
-        let toggle_btn = Button::with_label(if service.enabled { "Disable" } else { "Enable" });
+        // Enable/Disable toggle, or Unmask for masked units
+        let masked = service.state == service_manager::ServiceState::Masked;
+        let toggle_label = if masked {
+            "Unmask"
+        } else if service.enabled {
+            "Disable"
+        } else {
+            "Enable"
+        };
+        let toggle_btn = Button::with_label(toggle_label);
         toggle_btn.add_css_class("flat");
         
         let service_name = service.name.clone();
         let enabled = service.enabled;
-        toggle_btn.connect_clicked(move |_| {
-            let result = if enabled {
-                service_manager::disable_service(&service_name)
+        let scope = service.scope;
+        toggle_btn.connect_clicked(move |btn| {
+            let result = if masked {
+                service_manager::unmask_service(&service_name, scope)
+            } else if enabled {
+                service_manager::disable_service(&service_name, scope)
             } else {
-                service_manager::enable_service(&service_name)
+                service_manager::enable_service(&service_name, scope)
             };
             
-            if let Err(e) = result {
-                eprintln!("Failed to toggle service: {}", e);
-            }
+            let (success, title) = if masked {
+                ("Unmasked", "Failed to Unmask Service")
+            } else if enabled {
+                ("Disabled", "Failed to Disable Service")
+            } else {
+                ("Enabled", "Failed to Enable Service")
+            };
+            Self::report_result(btn, &format!("{} {}", success, service_name), title, result);
         });
         
         controls_box.append(&toggle_btn);
@@ -314,71 +504,358 @@ impl ServiceManagerView {
         logs_btn.add_css_class("flat");
         
         let service_name = service.name.clone();
+        let scope = service.scope;
         logs_btn.connect_clicked(move |btn| {
-            if let Ok(logs) = service_manager::get_service_logs(&service_name, 100) {
-                // Create a dialog to show logs
-                let window = btn.root().and_downcast::<gtk4::Window>();
-                
-                let dialog = adw::Window::new();
-                dialog.set_title(Some(&format!("Logs: {}", service_name)));
-                dialog.set_default_size(800, 600);
-                dialog.set_modal(true);
-                if let Some(parent) = window {
-                    dialog.set_transient_for(Some(&parent));
-                }
-                
-                let dialog_box = GtkBox::new(Orientation::Vertical, 0);
-                
-                // Header
-                let header = adw::HeaderBar::new();
-                let title_widget = adw::WindowTitle::new(&format!("Logs: {}", service_name), "Last 100 lines");
-                header.set_title_widget(Some(&title_widget));
-                
-                let close_btn = Button::with_label("Close");
-                close_btn.add_css_class("suggested-action");
-                header.pack_end(&close_btn);
-                
-                dialog_box.append(&header);
-                
-                // Logs text view
-                let scrolled = gtk4::ScrolledWindow::new();
-                scrolled.set_vexpand(true);
-                scrolled.set_hexpand(true);
-                
-                let text_view = gtk4::TextView::new();
-                text_view.set_editable(false);
-                text_view.set_monospace(true);
-                text_view.set_margin_top(12);
-                text_view.set_margin_bottom(12);
-                text_view.set_margin_start(12);
-                text_view.set_margin_end(12);
-                
-                let buffer = text_view.buffer();
-                buffer.set_text(&logs);
-                
-                scrolled.set_child(Some(&text_view));
-                dialog_box.append(&scrolled);
-                
-                dialog.set_content(Some(&dialog_box));
-                
-                let dialog_clone = dialog.clone();
-                close_btn.connect_clicked(move |_| {
-                    dialog_clone.close();
-                });
-                
-                dialog.present();
-            } else {
-                eprintln!("Failed to get logs for {}", service_name);
-            }
+            Self::show_live_logs(btn, &service_name, scope);
         });
         
         logs_row.add_suffix(&logs_btn);
         expander.add_row(&logs_row);
+
+        // Dependencies, loaded on demand since each lookup spawns systemctl
+        let deps_row = adw::ActionRow::new();
+        deps_row.set_title("Dependencies");
+        deps_row.set_subtitle("Units this service pulls in and units that need it");
+        
+        let deps_btn = Button::with_label("Show");
+        deps_btn.add_css_class("flat");
+        
+        let service_name = service.name.clone();
+        let scope = service.scope;
+        deps_btn.connect_clicked(move |btn| {
+            let format_units = |result: anyhow::Result<Vec<String>>| match result {
+                Ok(units) if units.is_empty() => "  (none)".to_string(),
+                Ok(units) => units.iter().map(|u| format!("  {}", u)).collect::<Vec<_>>().join("\n"),
+                Err(e) => format!("  Error: {}", e),
+            };
+            
+            let text = format!(
+                "Required by:\n{}\n\nDepends on:\n{}\n",
+                format_units(service_manager::get_service_reverse_dependencies(&service_name, scope)),
+                format_units(service_manager::get_service_dependencies(&service_name, scope))
+            );
+            Self::show_text_dialog(btn, &format!("Dependencies: {}", service_name), "Stopping this service affects the units that require it", &text);
+        });
+        
+        deps_row.add_suffix(&deps_btn);
+        expander.add_row(&deps_row);
+        
+        // Unit file and overrides, also loaded on demand
+        let config_row = adw::ActionRow::new();
+        config_row.set_title("Configuration");
+        config_row.set_subtitle("Unit file and the drop-in files overriding it");
+        
+        let config_btn = Button::with_label("Show");
+        config_btn.add_css_class("flat");
+        
+        let service_name = service.name.clone();
+        let scope = service.scope;
+        config_btn.connect_clicked(move |btn| {
+            let text = Self::unit_configuration(&service_name, scope);
+            Self::show_text_dialog(btn, &format!("Configuration: {}", service_name), "Later drop-ins override earlier settings", &text);
+        });
+        
+        config_row.add_suffix(&config_btn);
+        expander.add_row(&config_row);
         
         group.add(&expander);
     }
 
-    pub fn build(&self) -> GtkBox {
-        self.root.clone()
+    /// The unit file followed by its drop-ins, each headed by its path like `systemctl cat`
+    fn unit_configuration(service_name: &str, scope: service_manager::Scope) -> String {
+        let mut text = match service_manager::get_service_fragment_path(service_name, scope) {
+            Ok(Some(path)) => match std::fs::read_to_string(&path) {
+                Ok(contents) => format!("# {}\n{}", path.display(), contents),
+                Err(e) => format!("# {}\n# Error: {}\n", path.display(), e),
+            },
+            Ok(None) => "# No unit file\n".to_string(),
+            Err(e) => format!("# Error: {}\n", e),
+        };
+        
+        match service_manager::get_service_drop_ins(service_name, scope) {
+            Ok(drop_ins) => {
+                for (path, contents) in drop_ins {
+                    text.push_str(&format!("\n# {}\n{}", path.display(), contents));
+                }
+            }
+            Err(e) => text.push_str(&format!("\n# Drop-ins: {}\n", e)),
+        }
+        
+        text
+    }
+
+    /// Show read-only monospace text in a modal window
+    fn show_text_dialog(widget: &impl IsA<gtk4::Widget>, title: &str, subtitle: &str, text: &str) {
+        let (dialog, _, text_view) = Self::build_text_dialog(widget, title, subtitle);
+        text_view.buffer().set_text(text);
+        dialog.present();
+    }
+
+    /// Live-tail a service's journal in a modal window
+    /// 
+    /// journalctl is stopped when the window closes.
+    fn show_live_logs(widget: &impl IsA<gtk4::Widget>, service_name: &str, scope: service_manager::Scope) {
+        let (dialog, header, text_view) = Self::build_text_dialog(widget, &format!("Logs: {}", service_name), "");
+        let title = header.title_widget().and_downcast::<adw::WindowTitle>();
+        
+        // How much history to load, and the minimum priority
+        let line_labels: Vec<String> = LOG_LINE_COUNTS.iter()
+            .map(|&lines| if lines == 0 { "All Lines".to_string() } else { format!("{} Lines", lines) })
+            .collect();
+        let line_labels: Vec<&str> = line_labels.iter().map(|s| s.as_str()).collect();
+        let lines_dropdown = DropDown::from_strings(&line_labels);
+        header.pack_start(&lines_dropdown);
+        
+        let priority_labels: Vec<&str> = service_manager::LogPriority::ALL.iter().map(|p| p.label()).collect();
+        let priority_dropdown = DropDown::from_strings(&priority_labels);
+        header.pack_start(&priority_dropdown);
+        
+        // Dropping the follower kills journalctl, which disconnects the channel.
+        // Lines still queued from a replaced follower are ignored via the generation.
+        let follower = Rc::new(RefCell::new(None));
+        let generation = Rc::new(std::cell::Cell::new(0u32));
+        
+        let service_name = service_name.to_string();
+        let follower_clone = follower.clone();
+        let lines_dropdown_clone = lines_dropdown.clone();
+        let priority_dropdown_clone = priority_dropdown.clone();
+        let start = Rc::new(move || {
+            let lines = LOG_LINE_COUNTS[lines_dropdown_clone.selected() as usize];
+            let priority = service_manager::LogPriority::ALL[priority_dropdown_clone.selected() as usize];
+            
+            follower_clone.borrow_mut().take();
+            generation.set(generation.get() + 1);
+            let current_generation = generation.get();
+            
+            let buffer = text_view.buffer();
+            buffer.set_text("");
+            if let Some(title) = &title {
+                let history = if lines == 0 {
+                    "All entries".to_string()
+                } else {
+                    format!("Last {} lines", lines)
+                };
+                let filter = match priority {
+                    service_manager::LogPriority::All => String::new(),
+                    _ => format!(", {} and above", priority.label().to_lowercase()),
+                };
+                title.set_subtitle(&format!("{}{}, following new entries", history, filter));
+            }
+            
+            let (tx, rx) = mpsc::channel();
+            match service_manager::follow_service_logs(&service_name, lines, priority, scope, move |line| {
+                let _ = tx.send(line);
+            }) {
+                Ok(new_follower) => *follower_clone.borrow_mut() = Some(new_follower),
+                Err(e) => {
+                    buffer.set_text(&format!("Failed to read logs: {}", e));
+                    return;
+                }
+            }
+            
+            let text_view = text_view.clone();
+            let generation = generation.clone();
+            glib::timeout_add_local(Duration::from_millis(100), move || {
+                let mut appended = false;
+                loop {
+                    if generation.get() != current_generation {
+                        return glib::ControlFlow::Break;
+                    }
+                    match rx.try_recv() {
+                        Ok(line) => {
+                            let mut end = buffer.end_iter();
+                            buffer.insert(&mut end, &format!("{}\n", line));
+                            appended = true;
+                        }
+                        Err(TryRecvError::Empty) => break,
+                        Err(TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+                    }
+                }
+                if appended {
+                    let mark = buffer.create_mark(None, &buffer.end_iter(), false);
+                    text_view.scroll_mark_onscreen(&mark);
+                    buffer.delete_mark(&mark);
+                }
+                glib::ControlFlow::Continue
+            });
+        });
+        
+        let start_clone = start.clone();
+        lines_dropdown.connect_selected_notify(move |_| start_clone());
+        let start_clone = start.clone();
+        priority_dropdown.connect_selected_notify(move |_| start_clone());
+        start();
+        
+        dialog.connect_close_request(move |_| {
+            follower.borrow_mut().take();
+            glib::Propagation::Proceed
+        });
+
+        dialog.present();
+    }
+
+    /// Build a modal window with a read-only monospace text view
+    fn build_text_dialog(widget: &impl IsA<gtk4::Widget>, title: &str, subtitle: &str) -> (adw::Window, adw::HeaderBar, gtk4::TextView) {
+        let window = widget.root().and_downcast::<gtk4::Window>();
+        
+        let dialog = adw::Window::new();
+        dialog.set_title(Some(title));
+        dialog.set_default_size(800, 600);
+        dialog.set_modal(true);
+        if let Some(parent) = window {
+            dialog.set_transient_for(Some(&parent));
+        }
+        
+        let dialog_box = GtkBox::new(Orientation::Vertical, 0);
+        
+        // Header
+        let header = adw::HeaderBar::new();
+        let title_widget = adw::WindowTitle::new(title, subtitle);
+        header.set_title_widget(Some(&title_widget));
+        
+        let close_btn = Button::with_label("Close");
+        close_btn.add_css_class("suggested-action");
+        header.pack_end(&close_btn);
+        
+        dialog_box.append(&header);
+        
+        // Text view
+        let scrolled = gtk4::ScrolledWindow::new();
+        scrolled.set_vexpand(true);
+        scrolled.set_hexpand(true);
+        
+        let text_view = gtk4::TextView::new();
+        text_view.set_editable(false);
+        text_view.set_monospace(true);
+        text_view.set_margin_top(12);
+        text_view.set_margin_bottom(12);
+        text_view.set_margin_start(12);
+        text_view.set_margin_end(12);
+        
+        dialog_box.append(&Self::build_search_bar(&text_view));
+        
+        scrolled.set_child(Some(&text_view));
+        dialog_box.append(&scrolled);
+        
+        dialog.set_content(Some(&dialog_box));
+        
+        let dialog_clone = dialog.clone();
+        close_btn.connect_clicked(move |_| {
+            dialog_clone.close();
+        });
+        
+        (dialog, header, text_view)
+    }
+
+    /// Build a search bar that highlights matches in a text view
+    /// 
+    /// Every match is highlighted and the current one is scrolled to; Enter or the
+    /// arrow buttons move between matches. Lines appended later are searched the
+    /// next time the query changes.
+    fn build_search_bar(text_view: &gtk4::TextView) -> GtkBox {
+        let search_box = GtkBox::new(Orientation::Horizontal, 6);
+        search_box.set_margin_top(6);
+        search_box.set_margin_bottom(6);
+        search_box.set_margin_start(12);
+        search_box.set_margin_end(12);
+        
+        let search_entry = SearchEntry::new();
+        search_entry.set_placeholder_text(Some("Find in text..."));
+        search_entry.set_hexpand(true);
+        search_box.append(&search_entry);
+        
+        let match_label = Label::new(None);
+        match_label.add_css_class("dim-label");
+        search_box.append(&match_label);
+        
+        let prev_btn = Button::from_icon_name("go-up-symbolic");
+        prev_btn.set_tooltip_text(Some("Previous Match"));
+        prev_btn.add_css_class("flat");
+        search_box.append(&prev_btn);
+        
+        let next_btn = Button::from_icon_name("go-down-symbolic");
+        next_btn.set_tooltip_text(Some("Next Match"));
+        next_btn.add_css_class("flat");
+        search_box.append(&next_btn);
+        
+        let buffer = text_view.buffer();
+        buffer.create_tag(Some(SEARCH_MATCH_TAG), &[("background", &"#f6d32d"), ("foreground", &"#000000")]);
+        buffer.create_tag(Some(SEARCH_CURRENT_TAG), &[("background", &"#ff7800"), ("foreground", &"#000000")]);
+        
+        let search = Rc::new(LogSearch {
+            text_view: text_view.clone(),
+            match_label,
+            matches: RefCell::new(Vec::new()),
+            current: RefCell::new(None),
+        });
+        
+        let search_clone = search.clone();
+        search_entry.connect_search_changed(move |entry| {
+            search_clone.find(&entry.text());
+        });
+        
+        let search_clone = search.clone();
+        search_entry.connect_activate(move |_| search_clone.step(true));
+        let search_clone = search.clone();
+        search_entry.connect_next_match(move |_| search_clone.step(true));
+        let search_clone = search.clone();
+        search_entry.connect_previous_match(move |_| search_clone.step(false));
+        
+        let search_clone = search.clone();
+        next_btn.connect_clicked(move |_| search_clone.step(true));
+        prev_btn.connect_clicked(move |_| search.step(false));
+        
+        search_box
+    }
+
+    /// Show a toast if a service action succeeded, or an error dialog if it failed
+    /// 
+    /// Dismissing the pkexec prompt is not treated as an error.
+    fn report_result(widget: &impl IsA<gtk4::Widget>, success: &str, title: &str, result: anyhow::Result<()>) {
+        let Err(e) = result else {
+            show_toast(widget, success);
+            return;
+        };
+        
+        if e.downcast_ref::<service_manager::AuthenticationDismissed>().is_some() {
+            return;
+        }
+        
+        if let Some(window) = widget.root().and_downcast::<gtk4::Window>() {
+            let dialog = MessageDialog::new(
+                Some(&window),
+                gtk4::DialogFlags::MODAL,
+                MessageType::Error,
+                ButtonsType::Ok,
+                e.to_string()
+            );
+            dialog.set_title(Some(title));
+            dialog.connect_response(|dialog, _| {
+                dialog.close();
+            });
+            dialog.present();
+        }
+    }
+}
+
+impl Module for ServiceManagerView {
+    fn id(&self) -> &str {
+        "services"
+    }
+
+    fn name(&self) -> &str {
+        "Service Manager"
+    }
+
+    fn description(&self) -> &str {
+        "Start, stop and inspect systemd services"
+    }
+
+    fn icon_name(&self) -> &str {
+        "preferences-system-symbolic"
+    }
+
+    fn widget(&self) -> gtk4::Widget {
+        self.root.clone().upcast()
     }
 }
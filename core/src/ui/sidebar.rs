@@ -1,55 +1,146 @@
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, ListBox, Orientation, SelectionMode, Stack};
+use gtk4::{Box as GtkBox, Label, ListBox, Orientation, SearchEntry, SelectionMode, Stack, glib};
 use libadwaita as adw;
 use adw::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::Duration;
+
+use crate::module_loader::ModuleManager;
+use crate::search::{self, SearchHit};
+
+/// Extra wait after typing stops before a search is started, on top of the
+/// search entry's own delay
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(250);
 
 pub struct Sidebar {
     root: GtkBox,
+    list_box: ListBox,
+    /// Stack page name of each module row, in row order
+    page_names: Rc<Vec<String>>,
 }
 
 impl Sidebar {
-    pub fn new_with_stack(stack: &Stack) -> Self {
+    /// Build the sidebar, adding a row and a stack page for every registered module
+    pub fn new_with_stack(stack: &Stack, module_manager: &ModuleManager) -> Self {
         let root = GtkBox::new(Orientation::Vertical, 0);
         root.set_width_request(250);
         root.add_css_class("sidebar");
 
+        // Global search
+        let search_entry = SearchEntry::new();
+        search_entry.set_placeholder_text(Some("Search packages, services..."));
+        search_entry.set_margin_top(12);
+        search_entry.set_margin_bottom(6);
+        search_entry.set_margin_start(12);
+        search_entry.set_margin_end(12);
+        root.append(&search_entry);
+        
+        let search_status = Label::new(None);
+        search_status.add_css_class("dim-label");
+        search_status.set_margin_top(6);
+        search_status.set_margin_bottom(6);
+        search_status.set_visible(false);
+        root.append(&search_status);
+        
+        let results_list = ListBox::new();
+        results_list.set_selection_mode(SelectionMode::None);
+        results_list.add_css_class("navigation-sidebar");
+        results_list.set_visible(false);
+        root.append(&results_list);
+
         // Module list
         let list_box = ListBox::new();
         list_box.set_selection_mode(SelectionMode::Single);
         list_box.add_css_class("navigation-sidebar");
 
-        // Add modules with their stack page names
-        let modules = vec![
-            ("System Info", "computer-symbolic", "system"),
-            ("Task Manager", "utilities-system-monitor-symbolic", "tasks"),
-            ("Disk Analyzer", "drive-harddisk-symbolic", "disk"),
-            ("Package Manager", "package-x-generic-symbolic", "packages"),
-            ("Service Manager", "preferences-system-symbolic", "services"),
-            ("Startup Manager", "system-run-symbolic", "startup"),
-            ("System Cleaner", "edit-clear-all-symbolic", "cleaner"),
-        ];
-
-        for (name, icon, _page_name) in modules {
+        // Add a row and a stack page per module; row order matches page_names
+        let mut page_names = Vec::new();
+        for module in module_manager.get_modules() {
+            stack.add_titled(&module.widget(), Some(module.id()), module.name());
+            
             let row = adw::ActionRow::new();
-            row.set_title(name);
+            row.set_title(module.name());
+            row.set_tooltip_text(Some(module.description()));
             
-            let icon_widget = gtk4::Image::from_icon_name(icon);
+            let icon_widget = gtk4::Image::from_icon_name(module.icon_name());
             row.add_prefix(&icon_widget);
 
             list_box.append(&row);
+            page_names.push(module.id().to_string());
         }
+        let page_names = Rc::new(page_names);
 
         // Connect row selection to stack switching
         let stack_clone = stack.clone();
+        let page_names_clone = page_names.clone();
         list_box.connect_row_selected(move |_, row| {
             if let Some(row) = row {
                 let index = row.index() as usize;
-                let page_names = ["system", "tasks", "disk", "packages", "services", "startup", "cleaner"];
-                if let Some(&page_name) = page_names.get(index) {
+                if let Some(page_name) = page_names_clone.get(index) {
                     stack_clone.set_visible_child_name(page_name);
                 }
             }
         });
+        
+        // Searches run on a worker thread; the generation counter cancels
+        // pending searches and drops results of queries superseded by newer input
+        let generation = Rc::new(Cell::new(0u64));
+        let list_box_clone = list_box.clone();
+        let page_names_clone = page_names.clone();
+        search_entry.connect_search_changed(move |entry| {
+            generation.set(generation.get() + 1);
+            let current = generation.get();
+            
+            while let Some(child) = results_list.first_child() {
+                results_list.remove(&child);
+            }
+            
+            let query = entry.text().to_string();
+            if query.trim().is_empty() {
+                search_status.set_visible(false);
+                results_list.set_visible(false);
+                return;
+            }
+            
+            search_status.set_text("Searching...");
+            search_status.set_visible(true);
+            
+            let generation = generation.clone();
+            let search_status = search_status.clone();
+            let results_list = results_list.clone();
+            let list_box = list_box_clone.clone();
+            let page_names = page_names_clone.clone();
+            glib::timeout_add_local_once(SEARCH_DEBOUNCE, move || {
+                if generation.get() != current {
+                    return;
+                }
+                
+                let (sender, receiver) = mpsc::channel::<Vec<SearchHit>>();
+                std::thread::spawn(move || {
+                    let _ = sender.send(search::search_all(&query));
+                });
+                
+                glib::timeout_add_local(Duration::from_millis(100), move || {
+                    if generation.get() != current {
+                        return glib::ControlFlow::Break;
+                    }
+                    
+                    match receiver.try_recv() {
+                        Ok(hits) => {
+                            Self::show_search_hits(&hits, &search_status, &results_list, &list_box, &page_names);
+                            glib::ControlFlow::Break
+                        }
+                        Err(TryRecvError::Empty) => glib::ControlFlow::Continue,
+                        Err(TryRecvError::Disconnected) => {
+                            search_status.set_text("Search failed");
+                            glib::ControlFlow::Break
+                        }
+                    }
+                });
+            });
+        });
 
         // Select first row by default
         if let Some(row) = list_box.row_at_index(0) {
@@ -62,7 +153,54 @@ impl Sidebar {
         
         root.append(&scrolled);
 
-        Self { root }
+        Self { root, list_box, page_names }
+    }
+
+    /// Select a module's row, switching the stack to its page
+    /// 
+    /// Does nothing if no module has that id.
+    pub fn select_module(&self, id: &str) {
+        let Some(index) = self.page_names.iter().position(|name| name == id) else {
+            return;
+        };
+        if let Some(row) = self.list_box.row_at_index(index as i32) {
+            self.list_box.select_row(Some(&row));
+        }
+    }
+
+    fn show_search_hits(
+        hits: &[SearchHit],
+        search_status: &Label,
+        results_list: &ListBox,
+        list_box: &ListBox,
+        page_names: &[String],
+    ) {
+        if hits.is_empty() {
+            search_status.set_text("No results");
+            return;
+        }
+        search_status.set_visible(false);
+        
+        for hit in hits {
+            let row = adw::ActionRow::new();
+            row.set_title(&glib::markup_escape_text(&hit.title));
+            row.set_subtitle(&glib::markup_escape_text(&hit.subtitle));
+            row.set_subtitle_lines(1);
+            row.add_prefix(&gtk4::Image::from_icon_name(hit.kind.icon_name()));
+            
+            // Jump by selecting the module row, which switches the stack
+            let page_index = page_names.iter().position(|name| name == hit.kind.page_name());
+            let list_box = list_box.clone();
+            row.set_activatable(true);
+            row.connect_activated(move |_| {
+                if let Some(module_row) = page_index.and_then(|i| list_box.row_at_index(i as i32)) {
+                    list_box.select_row(Some(&module_row));
+                }
+            });
+            
+            results_list.append(&row);
+        }
+        results_list.set_visible(true);
     }
 
     pub fn build(&self) -> GtkBox {
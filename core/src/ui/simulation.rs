@@ -0,0 +1,64 @@
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Orientation};
+use libadwaita as adw;
+use adw::prelude::*;
+
+/// Show the changes a dry run would have made, in a modal window
+/// 
+/// Used instead of the usual result when safe mode is on.
+pub fn show_simulation(widget: &impl IsA<gtk4::Widget>, action: &str, changes: &[String]) {
+    let window = widget.root().and_downcast::<gtk4::Window>();
+    
+    let dialog = adw::Window::new();
+    dialog.set_title(Some("Safe Mode"));
+    dialog.set_default_size(700, 500);
+    dialog.set_modal(true);
+    if let Some(parent) = window {
+        dialog.set_transient_for(Some(&parent));
+    }
+    
+    let dialog_box = GtkBox::new(Orientation::Vertical, 0);
+    
+    // Header
+    let header = adw::HeaderBar::new();
+    let title_widget = adw::WindowTitle::new(&format!("Simulated: {}", action), "No changes were made");
+    header.set_title_widget(Some(&title_widget));
+    
+    let close_btn = Button::with_label("Close");
+    close_btn.add_css_class("suggested-action");
+    header.pack_end(&close_btn);
+    
+    dialog_box.append(&header);
+    
+    // Planned changes
+    let scrolled = gtk4::ScrolledWindow::new();
+    scrolled.set_vexpand(true);
+    scrolled.set_hexpand(true);
+    
+    let text_view = gtk4::TextView::new();
+    text_view.set_editable(false);
+    text_view.set_monospace(true);
+    text_view.set_margin_top(12);
+    text_view.set_margin_bottom(12);
+    text_view.set_margin_start(12);
+    text_view.set_margin_end(12);
+    
+    let text = if changes.is_empty() {
+        "Nothing to do".to_string()
+    } else {
+        changes.join("\n")
+    };
+    text_view.buffer().set_text(&text);
+    
+    scrolled.set_child(Some(&text_view));
+    dialog_box.append(&scrolled);
+    
+    dialog.set_content(Some(&dialog_box));
+    
+    let dialog_clone = dialog.clone();
+    close_btn.connect_clicked(move |_| {
+        dialog_clone.close();
+    });
+    
+    dialog.present();
+}
@@ -1,28 +1,77 @@
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Orientation, Label, Button, ScrolledWindow, ListBox};
+use gtk4::{Box as GtkBox, Orientation, Label, Button, ScrolledWindow, ListBox, Entry, MessageDialog, ButtonsType, MessageType};
 use libadwaita as adw;
 use adw::prelude::*;
-use startup_manager::{list_autostart_apps, enable_autostart, disable_autostart, remove_autostart, AutostartApp};
+use startup_manager::{list_autostart_apps, enable_autostart, disable_autostart, remove_autostart, create_autostart_entry, update_autostart_entry, set_autostart_delay, AutostartApp};
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::time::Duration;
+
+use crate::module_loader::Module;
+use crate::system_info::SystemInfo;
+use super::show_toast;
+
+/// Number of running startup applications listed by resource usage
+const HEAVIEST_SHOWN: usize = 5;
+
+/// How long the delay spin button has to rest before the value is written
+const DELAY_SETTLE_DELAY: Duration = Duration::from_millis(600);
 
 pub struct StartupManagerView {
     root: GtkBox,
 }
 
 impl StartupManagerView {
-    pub fn new() -> Self {
+    pub fn new(system: Rc<RefCell<SystemInfo>>) -> Self {
         let root = GtkBox::new(Orientation::Vertical, 0);
         root.add_css_class("startup-manager-view");
 
-        // Info label
+        // Info label and add button
+        let header_box = GtkBox::new(Orientation::Horizontal, 12);
+        header_box.set_margin_top(12);
+        header_box.set_margin_bottom(12);
+        header_box.set_margin_start(12);
+        header_box.set_margin_end(12);
+
         let info_label = Label::new(Some("Autostart applications run when you log in to your desktop session"));
-        info_label.set_margin_top(12);
-        info_label.set_margin_bottom(12);
-        info_label.set_margin_start(12);
-        info_label.set_margin_end(12);
         info_label.add_css_class("dim-label");
-        root.append(&info_label);
+        info_label.set_hexpand(true);
+        info_label.set_xalign(0.0);
+        header_box.append(&info_label);
+
+        let add_button = Button::with_label("Add Application");
+        add_button.set_icon_name("list-add-symbolic");
+        add_button.set_tooltip_text(Some("Add Application"));
+        header_box.append(&add_button);
+
+        root.append(&header_box);
+
+        // Running startup applications using the most memory
+        let impact_group = adw::PreferencesGroup::new();
+        impact_group.set_title("Heaviest Startup Apps");
+        impact_group.set_description(Some("Resources used right now by applications started at login"));
+        impact_group.set_margin_start(12);
+        impact_group.set_margin_end(12);
+        impact_group.set_margin_bottom(12);
+        
+        let impact_refresh_btn = Button::from_icon_name("view-refresh-symbolic");
+        impact_refresh_btn.set_tooltip_text(Some("Refresh"));
+        impact_refresh_btn.set_valign(gtk4::Align::Center);
+        impact_refresh_btn.add_css_class("flat");
+        impact_group.set_header_suffix(Some(&impact_refresh_btn));
+        
+        let impact_list = ListBox::new();
+        impact_list.set_selection_mode(gtk4::SelectionMode::None);
+        impact_list.add_css_class("boxed-list");
+        impact_group.add(&impact_list);
+        root.append(&impact_group);
+        
+        Self::populate_impact(&impact_list, &system);
+        
+        let impact_list_clone = impact_list.clone();
+        impact_refresh_btn.connect_clicked(move |_| {
+            Self::populate_impact(&impact_list_clone, &system);
+        });
 
         // Scrolled window for list
         let scrolled = ScrolledWindow::new();
@@ -42,6 +91,12 @@ impl StartupManagerView {
         // Initial population
         Self::populate_list(&list_box, &apps);
 
+        let list_box_clone = list_box.clone();
+        let apps_clone = apps.clone();
+        add_button.connect_clicked(move |btn| {
+            Self::show_entry_dialog(btn, &list_box_clone, &apps_clone, None);
+        });
+
         Self {
             root,
         }
@@ -71,9 +126,51 @@ impl StartupManagerView {
             }
             Err(e) => {
                 let error_row = adw::ActionRow::new();
-                error_row.set_property("title", &format!("Error: {}", e));
+                error_row.set_property("title", format!("Error: {}", e));
+                list_box.append(&error_row);
+            }
+        }
+    }
+
+    fn populate_impact(list_box: &ListBox, system: &Rc<RefCell<SystemInfo>>) {
+        while let Some(child) = list_box.first_child() {
+            list_box.remove(&child);
+        }
+        
+        let mut sys = system.borrow_mut();
+        sys.refresh_processes();
+        
+        let impacts = match startup_manager::analyze_startup_impact(sys.processes()) {
+            Ok(impacts) => impacts,
+            Err(e) => {
+                let error_row = adw::ActionRow::new();
+                error_row.set_title(&format!("Error: {}", e));
                 list_box.append(&error_row);
+                return;
             }
+        };
+        
+        let running: Vec<_> = impacts.iter()
+            .filter_map(|impact| impact.matched_pid.map(|pid| (impact, pid)))
+            .take(HEAVIEST_SHOWN)
+            .collect();
+        if running.is_empty() {
+            let empty_row = adw::ActionRow::new();
+            empty_row.set_title("No startup applications are running");
+            list_box.append(&empty_row);
+            return;
+        }
+        
+        for (impact, pid) in running {
+            let row = adw::ActionRow::new();
+            row.set_title(&impact.app.name);
+            row.set_subtitle(&format!(
+                "PID: {} • CPU: {:.1}% • Memory: {}",
+                pid,
+                impact.cpu,
+                SystemInfo::format_memory(impact.memory)
+            ));
+            list_box.append(&row);
         }
     }
 
@@ -81,11 +178,29 @@ impl StartupManagerView {
         let row = adw::ActionRow::new();
         row.set_property("title", &app.name);
         
-        let subtitle = if !app.comment.is_empty() {
-            format!("{}\nCommand: {}", app.comment, app.exec)
+        let mut subtitle = if !app.comment.is_empty() {
+            format!("{}\nCommand: {}", app.comment, app.clean_exec)
         } else {
-            format!("Command: {}", app.exec)
+            format!("Command: {}", app.clean_exec)
         };
+        
+        // Entries restricted to other desktops never run in this session
+        if !app.applies_to_current_desktop() {
+            let note = if !app.only_show_in.is_empty() {
+                format!("Only runs in {}", app.only_show_in.join(", "))
+            } else {
+                format!("Does not run in {}", app.not_show_in.join(", "))
+            };
+            subtitle.push_str(&format!("\n{}", note));
+            row.add_css_class("dim-label");
+        }
+        // Entries whose program is gone are safe to remove
+        if app.executable_missing {
+            subtitle.push_str("\nProgram not installed");
+            let icon = gtk4::Image::from_icon_name("dialog-warning-symbolic");
+            icon.set_tooltip_text(Some("The program this entry starts could not be found"));
+            row.add_prefix(&icon);
+        }
         row.set_property("subtitle", &subtitle);
 
         // Enable/Disable switch
@@ -104,7 +219,7 @@ impl StartupManagerView {
             };
             
             if let Err(e) = result {
-                eprintln!("Failed to toggle autostart: {}", e);
+                show_toast(&list_box_clone, &format!("Failed to toggle autostart: {}", e));
             } else {
                 // Refresh the list
                 Self::populate_list(&list_box_clone, &apps_clone);
@@ -113,8 +228,56 @@ impl StartupManagerView {
             gtk4::glib::Propagation::Proceed
         });
 
+        // Startup delay in seconds
+        let delay_spin = gtk4::SpinButton::with_range(0.0, 600.0, 5.0);
+        delay_spin.set_value(app.delay.unwrap_or(0) as f64);
+        delay_spin.set_valign(gtk4::Align::Center);
+        delay_spin.set_tooltip_text(Some("Delay after login (seconds)"));
+
+        // Write once the value has settled, then reload so the row points at
+        // the user copy the delay was written to
+        let app_clone = app.clone();
+        let list_box_clone = list_box.clone();
+        let apps_clone = apps.clone();
+        let pending: Rc<RefCell<Option<gtk4::glib::SourceId>>> = Rc::new(RefCell::new(None));
+        delay_spin.connect_value_changed(move |spin| {
+            if let Some(source) = pending.borrow_mut().take() {
+                source.remove();
+            }
+            let seconds = spin.value_as_int() as u32;
+            let app = app_clone.clone();
+            let list_box = list_box_clone.clone();
+            let apps = apps_clone.clone();
+            let pending_clone = pending.clone();
+            let source = gtk4::glib::timeout_add_local_once(DELAY_SETTLE_DELAY, move || {
+                pending_clone.borrow_mut().take();
+                match set_autostart_delay(&app, seconds) {
+                    Ok(()) => Self::populate_list(&list_box, &apps),
+                    Err(e) => show_toast(&list_box, &format!("Failed to set autostart delay: {}", e)),
+                }
+            });
+            *pending.borrow_mut() = Some(source);
+        });
+
+        row.add_suffix(&delay_spin);
         row.add_suffix(&switch);
 
+        // Edit button
+        let edit_btn = Button::new();
+        edit_btn.set_icon_name("document-edit-symbolic");
+        edit_btn.set_valign(gtk4::Align::Center);
+        edit_btn.add_css_class("flat");
+        edit_btn.set_tooltip_text(Some("Edit"));
+
+        let app_clone = app.clone();
+        let list_box_clone = list_box.clone();
+        let apps_clone = apps.clone();
+        edit_btn.connect_clicked(move |btn| {
+            Self::show_entry_dialog(btn, &list_box_clone, &apps_clone, Some(app_clone.clone()));
+        });
+
+        row.add_suffix(&edit_btn);
+
         // Remove button (only for user directory apps)
         if app.path.to_str().unwrap_or("").contains(".config/autostart") {
             let remove_btn = Button::new();
@@ -128,7 +291,7 @@ impl StartupManagerView {
             let apps_clone = apps.clone();
             remove_btn.connect_clicked(move |_| {
                 if let Err(e) = remove_autostart(&app_clone) {
-                    eprintln!("Failed to remove autostart: {}", e);
+                    show_toast(&list_box_clone, &format!("Failed to remove autostart: {}", e));
                 } else {
                     Self::populate_list(&list_box_clone, &apps_clone);
                 }
@@ -140,7 +303,119 @@ impl StartupManagerView {
         row
     }
 
-    pub fn build(&self) -> GtkBox {
-        self.root.clone()
+    /// Ask for a name and command, then create a new autostart entry or,
+    /// when `existing` is given, update that one
+    fn show_entry_dialog(btn: &Button, list_box: &ListBox, apps: &Rc<RefCell<Vec<AutostartApp>>>, existing: Option<AutostartApp>) {
+        let window = btn.root().and_downcast::<gtk4::Window>();
+
+        let dialog = adw::Window::new();
+        dialog.set_title(Some(if existing.is_some() { "Edit Startup Application" } else { "Add Startup Application" }));
+        dialog.set_default_size(480, -1);
+        dialog.set_modal(true);
+        if let Some(parent) = &window {
+            dialog.set_transient_for(Some(parent));
+        }
+
+        let dialog_box = GtkBox::new(Orientation::Vertical, 0);
+
+        let header = adw::HeaderBar::new();
+        header.set_show_end_title_buttons(false);
+        let cancel_btn = Button::with_label("Cancel");
+        header.pack_start(&cancel_btn);
+        let add_btn = Button::with_label(if existing.is_some() { "Save" } else { "Add" });
+        add_btn.add_css_class("suggested-action");
+        header.pack_end(&add_btn);
+        dialog_box.append(&header);
+
+        let form = GtkBox::new(Orientation::Vertical, 12);
+        form.set_margin_top(12);
+        form.set_margin_bottom(12);
+        form.set_margin_start(12);
+        form.set_margin_end(12);
+
+        let name_entry = Entry::new();
+        name_entry.set_placeholder_text(Some("Name"));
+        form.append(&name_entry);
+
+        let exec_entry = Entry::new();
+        exec_entry.set_placeholder_text(Some("Command, e.g. syncthing --no-browser"));
+        form.append(&exec_entry);
+
+        let comment_entry = Entry::new();
+        comment_entry.set_placeholder_text(Some("Comment (optional)"));
+        form.append(&comment_entry);
+
+        if let Some(app) = &existing {
+            name_entry.set_text(&app.name);
+            exec_entry.set_text(&app.exec);
+            comment_entry.set_text(&app.comment);
+        }
+
+        dialog_box.append(&form);
+        dialog.set_content(Some(&dialog_box));
+
+        let dialog_clone = dialog.clone();
+        cancel_btn.connect_clicked(move |_| {
+            dialog_clone.close();
+        });
+
+        let dialog_clone = dialog.clone();
+        let list_box = list_box.clone();
+        let apps = apps.clone();
+        add_btn.connect_clicked(move |_| {
+            let result = match &existing {
+                Some(app) => update_autostart_entry(
+                    app,
+                    Some(&name_entry.text()),
+                    Some(&exec_entry.text()),
+                    Some(&comment_entry.text()),
+                ),
+                None => create_autostart_entry(&name_entry.text(), &exec_entry.text(), &comment_entry.text()).map(|_| ()),
+            };
+            match result {
+                Ok(()) => {
+                    dialog_clone.close();
+                    Self::populate_list(&list_box, &apps);
+                }
+                Err(e) => {
+                    let error_dialog = MessageDialog::new(
+                        Some(&dialog_clone),
+                        gtk4::DialogFlags::MODAL,
+                        MessageType::Error,
+                        ButtonsType::Ok,
+                        e.to_string()
+                    );
+                    error_dialog.set_title(Some("Could Not Save Application"));
+                    error_dialog.connect_response(|dialog, _| {
+                        dialog.close();
+                    });
+                    error_dialog.present();
+                }
+            }
+        });
+
+        dialog.present();
+    }
+}
+
+impl Module for StartupManagerView {
+    fn id(&self) -> &str {
+        "startup"
+    }
+
+    fn name(&self) -> &str {
+        "Startup Manager"
+    }
+
+    fn description(&self) -> &str {
+        "Applications started on login"
+    }
+
+    fn icon_name(&self) -> &str {
+        "system-run-symbolic"
+    }
+
+    fn widget(&self) -> gtk4::Widget {
+        self.root.clone().upcast()
     }
 }
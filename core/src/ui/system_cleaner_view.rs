@@ -2,16 +2,29 @@ use gtk4::prelude::*;
 use gtk4::{Box as GtkBox, Orientation, Label, Button, ScrolledWindow, ListBox, CheckButton, MessageDialog, ButtonsType, MessageType};
 use libadwaita as adw;
 use adw::prelude::*;
-use system_cleaner::{scan_cleanable_items, clean_category, format_size, CleanupItem};
+use gtk4::glib;
+use system_cleaner::{scan_cleanable_items_with_progress, clean_categories, clean_category, format_size, AuthenticationDismissed, CleanupCategory, CleanupItem, Outcome};
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::Duration;
+
+use crate::config::Settings;
+use crate::module_loader::Module;
+use super::{show_simulation, show_toast};
+
+/// Messages sent from the scanning thread to the UI
+enum ScanMessage {
+    Progress(CleanupCategory),
+    Done(anyhow::Result<Vec<CleanupItem>>),
+}
 
 pub struct SystemCleanerView {
     root: GtkBox,
 }
 
 impl SystemCleanerView {
-    pub fn new() -> Self {
+    pub fn new(settings: Rc<RefCell<Settings>>) -> Self {
         let root = GtkBox::new(Orientation::Vertical, 0);
         root.add_css_class("system-cleaner-view");
 
@@ -63,7 +76,7 @@ impl SystemCleanerView {
         let items = Rc::new(RefCell::new(Vec::new()));
         
         // Initial scan
-        Self::scan_items(&list_box, &items, &total_label);
+        Self::scan_items(&list_box, &items, &total_label, &settings.borrow().cleanup_exclusions);
         clean_button.set_sensitive(true);
         
         // Clean button handler
@@ -71,7 +84,14 @@ impl SystemCleanerView {
         let items_clone = items.clone();
         let total_label_clone = total_label.clone();
         clean_button.connect_clicked(move |btn| {
-            let cleaned_count = Self::clean_selected(&list_box_clone, &items_clone);
+            let categories = Self::selected_categories(&list_box_clone, &items_clone);
+            let exclusions = settings.borrow().cleanup_exclusions.clone();
+            if settings.borrow().safe_mode {
+                Self::simulate_clean(btn, &categories, &exclusions);
+                return;
+            }
+            
+            let (cleaned_count, freed) = Self::clean_selected(&list_box_clone, &categories, &exclusions);
             
             // Show result dialog
             if cleaned_count > 0 {
@@ -81,7 +101,7 @@ impl SystemCleanerView {
                         gtk4::DialogFlags::MODAL,
                         MessageType::Info,
                         ButtonsType::Ok,
-                        &format!("Successfully cleaned {} categories", cleaned_count)
+                        format!("Cleaned {} categories. Freed {}", cleaned_count, format_size(freed))
                     );
                     dialog.set_title(Some("Cleanup Complete"));
                     dialog.connect_response(|dialog, _| {
@@ -92,18 +112,14 @@ impl SystemCleanerView {
             }
             
             // Rescan
-            Self::scan_items(&list_box_clone, &items_clone, &total_label_clone);
+            Self::scan_items(&list_box_clone, &items_clone, &total_label_clone, &exclusions);
             btn.set_sensitive(true);
         });
 
-        // Initial scan
-        Self::scan_items(&list_box, &items, &total_label);
-        clean_button.set_sensitive(true);
-
         Self { root }
     }
 
-    fn scan_items(list_box: &ListBox, items: &Rc<RefCell<Vec<CleanupItem>>>, total_label: &Label) {
+    fn scan_items(list_box: &ListBox, items: &Rc<RefCell<Vec<CleanupItem>>>, total_label: &Label, exclusions: &[String]) {
         // Clear existing items
         while let Some(child) = list_box.first_child() {
             list_box.remove(&child);
@@ -112,10 +128,49 @@ impl SystemCleanerView {
         // Show scanning message
         let scanning_row = adw::ActionRow::new();
         scanning_row.set_property("title", "Scanning system...");
+        let spinner = gtk4::Spinner::new();
+        spinner.start();
+        scanning_row.add_prefix(&spinner);
         list_box.append(&scanning_row);
 
-        // Scan for cleanable items
-        match scan_cleanable_items() {
+        // Scan on a worker thread so large directories don't block the UI
+        let (tx, rx) = mpsc::channel();
+        let exclusions = exclusions.to_vec();
+        std::thread::spawn(move || {
+            let progress_tx = tx.clone();
+            let result = scan_cleanable_items_with_progress(&[], &exclusions, |category| {
+                let _ = progress_tx.send(ScanMessage::Progress(category.clone()));
+            });
+            let _ = tx.send(ScanMessage::Done(result));
+        });
+
+        let list_box = list_box.clone();
+        let items = items.clone();
+        let total_label = total_label.clone();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            loop {
+                match rx.try_recv() {
+                    Ok(ScanMessage::Progress(category)) => {
+                        scanning_row.set_property("subtitle", format!("Scanned {}", category.name()));
+                    }
+                    Ok(ScanMessage::Done(result)) => {
+                        Self::show_scan_result(&list_box, &items, &total_label, result);
+                        return glib::ControlFlow::Break;
+                    }
+                    Err(TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                    Err(TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+                }
+            }
+        });
+    }
+
+    fn show_scan_result(
+        list_box: &ListBox,
+        items: &Rc<RefCell<Vec<CleanupItem>>>,
+        total_label: &Label,
+        result: anyhow::Result<Vec<CleanupItem>>,
+    ) {
+        match result {
             Ok(scanned_items) => {
                 *items.borrow_mut() = scanned_items.clone();
                 
@@ -153,7 +208,7 @@ impl SystemCleanerView {
                 }
                 
                 let error_row = adw::ActionRow::new();
-                error_row.set_property("title", &format!("Error: {}", e));
+                error_row.set_property("title", format!("Error: {}", e));
                 list_box.append(&error_row);
             }
         }
@@ -162,12 +217,16 @@ impl SystemCleanerView {
     fn create_cleanup_row(item: CleanupItem) -> (adw::ActionRow, CheckButton) {
         let row = adw::ActionRow::new();
         row.set_property("title", item.category.name());
-        row.set_property("subtitle", &format!(
+        let mut subtitle = format!(
             "{}\nSize: {} | Files: {}",
             item.category.description(),
             format_size(item.size),
             item.count
-        ));
+        );
+        if item.excluded > 0 {
+            subtitle.push_str(&format!(" | Excluded: {}", format_size(item.excluded)));
+        }
+        row.set_property("subtitle", subtitle);
 
         // Checkbox for selection
         let check = CheckButton::new();
@@ -179,7 +238,8 @@ impl SystemCleanerView {
         (row, check)
     }
 
-    fn clean_selected(list_box: &ListBox, items: &Rc<RefCell<Vec<CleanupItem>>>) -> usize {
+    /// Categories of the rows whose checkbox is ticked
+    fn selected_categories(list_box: &ListBox, items: &Rc<RefCell<Vec<CleanupItem>>>) -> Vec<CleanupCategory> {
         let items_borrowed = items.borrow();
         
         // Collect items to clean
@@ -207,23 +267,64 @@ impl SystemCleanerView {
             child = row_widget.next_sibling();
         }
         
-        drop(items_borrowed);
-        
-        // Clean selected items
+        to_clean.into_iter().map(|item| item.category).collect()
+    }
+    
+    /// Clean the given categories, authenticating once for all of them
+    fn clean_selected(list_box: &ListBox, categories: &[CleanupCategory], exclusions: &[String]) -> (usize, u64) {
         let mut cleaned_count = 0;
-        for item in to_clean {
-            if let Err(e) = clean_category(&item.category) {
-                eprintln!("Failed to clean {}: {}", item.category.name(), e);
-            } else {
-                println!("Successfully cleaned: {}", item.category.name());
-                cleaned_count += 1;
+        let mut freed = 0u64;
+        for (category, result) in categories.iter().zip(clean_categories(categories, exclusions)) {
+            match result {
+                Ok(bytes) => {
+                    tracing::info!("Cleaned {} ({})", category.name(), format_size(bytes));
+                    cleaned_count += 1;
+                    freed += bytes;
+                }
+                Err(e) if e.is::<AuthenticationDismissed>() => {}
+                Err(e) => show_toast(list_box, &format!("Failed to clean {}: {}", category.name(), e)),
             }
         }
         
-        cleaned_count
+        (cleaned_count, freed)
+    }
+    
+    /// Show what cleaning the given categories would delete, without deleting anything
+    fn simulate_clean(btn: &Button, categories: &[CleanupCategory], exclusions: &[String]) {
+        let mut changes = Vec::new();
+        for category in categories {
+            match clean_category(category, exclusions, true) {
+                Ok(Outcome::Simulated(planned)) => {
+                    changes.push(format!("{}:", category.name()));
+                    changes.extend(planned.into_iter().map(|change| format!("  {}", change)));
+                }
+                Ok(Outcome::Done(_)) => {}
+                Err(e) => changes.push(format!("{}: {}", category.name(), e)),
+            }
+        }
+        
+        show_simulation(btn, "Clean Selected", &changes);
+    }
+}
+
+impl Module for SystemCleanerView {
+    fn id(&self) -> &str {
+        "cleaner"
+    }
+
+    fn name(&self) -> &str {
+        "System Cleaner"
+    }
+
+    fn description(&self) -> &str {
+        "Free space taken by caches, logs and trash"
+    }
+
+    fn icon_name(&self) -> &str {
+        "edit-clear-all-symbolic"
     }
 
-    pub fn build(&self) -> GtkBox {
-        self.root.clone()
+    fn widget(&self) -> gtk4::Widget {
+        self.root.clone().upcast()
     }
 }
@@ -1,20 +1,68 @@
 //! System Information View
 //! 
 //! Displays OS info, hardware specs, and memory/swap usage with expandable details.
+//! Uptime, CPU, memory, swap and temperature figures refresh every few seconds.
 
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Label, Orientation, ProgressBar};
+use gtk4::{Box as GtkBox, Label, Orientation, ProgressBar, glib};
 use libadwaita as adw;
 use adw::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
 
 use crate::system_info::SystemInfo;
+use crate::module_loader::Module;
+use crate::power;
+use super::show_toast;
+
+/// Maximum number of temperature sensors shown
+const MAX_TEMPERATURE_SENSORS: usize = 10;
+
+/// How long the brightness slider has to rest before the value is written
+const BRIGHTNESS_SETTLE_DELAY: Duration = Duration::from_millis(400);
 
 pub struct SystemView {
     root: GtkBox,
 }
 
+/// Handles to the widgets the periodic refresh updates in place
+struct LiveWidgets {
+    uptime: Label,
+    load: Label,
+    tasks: Label,
+    /// CPU, memory and I/O pressure, when the kernel reports it
+    pressure: Option<[Label; 3]>,
+    core_bars: Vec<ProgressBar>,
+    /// Per-core rows, whose subtitle shows the clock frequency
+    core_rows: Vec<adw::ActionRow>,
+    memory: UsageWidgets,
+    memory_available: Label,
+    memory_free: Label,
+    memory_cache: Label,
+    swap: Option<UsageWidgets>,
+    temperatures: Vec<TemperatureWidgets>,
+}
+
+/// Expander subtitle, progress bar and breakdown labels of a memory or swap row
+struct UsageWidgets {
+    expander: adw::ExpanderRow,
+    progress: ProgressBar,
+    total: Label,
+    used: Label,
+    free: Option<Label>,
+}
+
+struct TemperatureWidgets {
+    sensor: String,
+    expander: adw::ExpanderRow,
+    icon: gtk4::Image,
+    temperature: Label,
+    status: Label,
+}
+
 impl SystemView {
-    pub fn new() -> Self {
+    pub fn new(shared_info: Rc<RefCell<SystemInfo>>) -> Self {
         let root = GtkBox::new(Orientation::Vertical, 0);
 
         // Content area
@@ -26,7 +74,7 @@ impl SystemView {
         content.set_vexpand(true);
 
         // Get system info
-        let sys_info = SystemInfo::new();
+        let mut sys_info = shared_info.borrow_mut();
 
         // System details group
         let system_group = adw::PreferencesGroup::new();
@@ -35,7 +83,14 @@ impl SystemView {
         Self::add_info_row(&system_group, "Operating System", &sys_info.os_version());
         Self::add_info_row(&system_group, "Hostname", &sys_info.hostname());
         Self::add_info_row(&system_group, "Kernel Version", &sys_info.kernel_version());
-        Self::add_info_row(&system_group, "Uptime", &sys_info.format_uptime());
+        let uptime = Self::add_info_row(&system_group, "Uptime", "");
+        let load = Self::add_info_row(&system_group, "Load (1 / 5 / 15 min)", "");
+        let tasks = Self::add_info_row(&system_group, "Processes", "");
+        let pressure = SystemInfo::get_pressure().map(|_| [
+            Self::add_info_row(&system_group, "CPU Pressure", ""),
+            Self::add_info_row(&system_group, "Memory Pressure", ""),
+            Self::add_info_row(&system_group, "I/O Pressure", ""),
+        ]);
 
         content.append(&system_group);
 
@@ -43,126 +98,219 @@ impl SystemView {
         let hardware_group = adw::PreferencesGroup::new();
         hardware_group.set_title("Hardware");
         
-        Self::add_info_row(&hardware_group, "CPU Cores", &sys_info.cpu_count().to_string());
+        match SystemInfo::cpu_details() {
+            Some(cpu) => {
+                // Expandable CPU model row with vendor and feature flags
+                let model_expander = adw::ExpanderRow::new();
+                model_expander.set_title("CPU Model");
+                model_expander.set_subtitle(&cpu.model);
+                Self::add_expander_detail(&model_expander, "Vendor", &cpu.vendor);
+                
+                let flags_row = adw::ActionRow::new();
+                flags_row.set_title("Flags");
+                flags_row.set_use_markup(false);
+                flags_row.set_subtitle(&cpu.flags.join(" "));
+                model_expander.add_row(&flags_row);
+                hardware_group.add(&model_expander);
+                
+                let virtualization = match (cpu.virtualization.technology, cpu.virtualization.kvm_usable) {
+                    (Some(technology), true) => format!("{} (KVM available)", technology),
+                    (Some(technology), false) => technology.to_string(),
+                    (None, _) => "Not supported".to_string(),
+                };
+                Self::add_info_row(&hardware_group, "Virtualization", &virtualization);
+            }
+            None => {
+                Self::add_info_row(&hardware_group, "CPU Model", &sys_info.cpu_brand());
+            }
+        }
         
-        // Expandable Memory Row
-        let memory_expander = adw::ExpanderRow::new();
-        memory_expander.set_title("Memory");
+        // Expandable CPU row with a bar per core
+        let cpu_expander = adw::ExpanderRow::new();
+        cpu_expander.set_title("CPU Cores");
         
-        let total_mem = sys_info.total_memory();
-        let used_mem = sys_info.used_memory();
-        let available_mem = sys_info.available_memory();
-        let free_mem = sys_info.free_memory();
-        let used_percentage = (used_mem as f64 / total_mem as f64) * 100.0;
+        // Cores usually share one governor; show it if so
+        let frequencies = SystemInfo::get_cpu_frequencies();
+        let governor = frequencies.first()
+            .and_then(|freq| freq.governor.as_ref())
+            .filter(|governor| frequencies.iter().all(|freq| freq.governor.as_ref() == Some(*governor)));
+        match governor {
+            Some(governor) => cpu_expander.set_subtitle(&format!("{} • {} governor", sys_info.cpu_count(), governor)),
+            None => cpu_expander.set_subtitle(&sys_info.cpu_count().to_string()),
+        }
         
-        memory_expander.set_subtitle(&format!(
-            "{} / {} ({:.1}% used)",
-            SystemInfo::format_memory(used_mem),
-            SystemInfo::format_memory(total_mem),
-            used_percentage
-        ));
+        let mut core_bars = Vec::new();
+        let mut core_rows = Vec::new();
+        for i in 0..sys_info.cpu_count() {
+            let core_row = adw::ActionRow::new();
+            core_row.set_title(&format!("Core {}", i));
+            
+            let core_progress = ProgressBar::new();
+            core_progress.set_show_text(true);
+            core_progress.set_valign(gtk4::Align::Center);
+            core_progress.set_hexpand(true);
+            core_row.add_suffix(&core_progress);
+            
+            cpu_expander.add_row(&core_row);
+            core_bars.push(core_progress);
+            core_rows.push(core_row);
+        }
         
-        // Progress bar for memory
-        let mem_progress_box = GtkBox::new(Orientation::Vertical, 6);
-        mem_progress_box.set_margin_top(12);
-        mem_progress_box.set_margin_bottom(12);
-        mem_progress_box.set_margin_start(12);
-        mem_progress_box.set_margin_end(12);
+        hardware_group.add(&cpu_expander);
         
-        let mem_progress = ProgressBar::new();
-        mem_progress.set_fraction(used_percentage / 100.0);
-        mem_progress.set_show_text(true);
-        mem_progress.set_text(Some(&format!("{:.1}%", used_percentage)));
-        mem_progress_box.append(&mem_progress);
+        // Expandable Memory Row
+        let memory_expander = adw::ExpanderRow::new();
+        memory_expander.set_title("Memory");
         
-        memory_expander.add_row(&mem_progress_box);
+        let mem_progress = Self::add_expander_progress(&memory_expander);
         
         // Memory breakdown rows
-        Self::add_expander_detail(&memory_expander, "Total Memory", &SystemInfo::format_memory(total_mem));
-        Self::add_expander_detail(&memory_expander, "Used Memory", &SystemInfo::format_memory(used_mem));
-        Self::add_expander_detail(&memory_expander, "Available Memory", &SystemInfo::format_memory(available_mem));
-        Self::add_expander_detail(&memory_expander, "Free Memory", &SystemInfo::format_memory(free_mem));
-        Self::add_expander_detail(&memory_expander, "Buffers/Cache", 
-            &SystemInfo::format_memory(used_mem.saturating_sub(total_mem - available_mem)));
+        let memory = UsageWidgets {
+            expander: memory_expander.clone(),
+            progress: mem_progress,
+            total: Self::add_expander_detail(&memory_expander, "Total Memory", ""),
+            used: Self::add_expander_detail(&memory_expander, "Used Memory", ""),
+            free: None,
+        };
+        let memory_available = Self::add_expander_detail(&memory_expander, "Available Memory", "");
+        let memory_free = Self::add_expander_detail(&memory_expander, "Free Memory", "");
+        let memory_cache = Self::add_expander_detail(&memory_expander, "Buffers/Cache", "");
         
         hardware_group.add(&memory_expander);
         
         // Expandable Swap Row if swap exists
-        let total_swap = sys_info.total_swap();
-        if total_swap > 0 {
+        let swap = (sys_info.total_swap() > 0).then(|| {
             let swap_expander = adw::ExpanderRow::new();
             swap_expander.set_title("Swap Memory");
             
-            let used_swap = sys_info.used_swap();
-            let swap_percentage = (used_swap as f64 / total_swap as f64) * 100.0;
+            let swap_progress = Self::add_expander_progress(&swap_expander);
             
-            swap_expander.set_subtitle(&format!(
-                "{} / {} ({:.1}% used)",
-                SystemInfo::format_memory(used_swap),
-                SystemInfo::format_memory(total_swap),
-                swap_percentage
-            ));
+            let swap = UsageWidgets {
+                expander: swap_expander.clone(),
+                progress: swap_progress,
+                total: Self::add_expander_detail(&swap_expander, "Total Swap", ""),
+                used: Self::add_expander_detail(&swap_expander, "Used Swap", ""),
+                free: Some(Self::add_expander_detail(&swap_expander, "Free Swap", "")),
+            };
             
-            // Progress bar for swap
-            let swap_progress_box = GtkBox::new(Orientation::Vertical, 6);
-            swap_progress_box.set_margin_top(12);
-            swap_progress_box.set_margin_bottom(12);
-            swap_progress_box.set_margin_start(12);
-            swap_progress_box.set_margin_end(12);
+            hardware_group.add(&swap_expander);
+            swap
+        });
+
+        content.append(&hardware_group);
+        
+        // Battery group, laptops only
+        if let Some(battery) = SystemInfo::get_battery() {
+            let battery_group = adw::PreferencesGroup::new();
+            battery_group.set_title("Battery");
             
-            let swap_progress = ProgressBar::new();
-            swap_progress.set_fraction(swap_percentage / 100.0);
-            swap_progress.set_show_text(true);
-            swap_progress.set_text(Some(&format!("{:.1}%", swap_percentage)));
-            swap_progress_box.append(&swap_progress);
+            let battery_expander = adw::ExpanderRow::new();
+            battery_expander.set_title("Charge");
             
-            swap_expander.add_row(&swap_progress_box);
+            let state = if battery.charging { "Charging" } else { "On battery" };
+            battery_expander.set_subtitle(&format!("{:.0}% ({})", battery.percentage, state));
             
-            Self::add_expander_detail(&swap_expander, "Total Swap", &SystemInfo::format_memory(total_swap));
-            Self::add_expander_detail(&swap_expander, "Used Swap", &SystemInfo::format_memory(used_swap));
-            Self::add_expander_detail(&swap_expander, "Free Swap", &SystemInfo::format_memory(total_swap - used_swap));
+            let battery_progress_box = GtkBox::new(Orientation::Vertical, 6);
+            battery_progress_box.set_margin_top(12);
+            battery_progress_box.set_margin_bottom(12);
+            battery_progress_box.set_margin_start(12);
+            battery_progress_box.set_margin_end(12);
             
-            hardware_group.add(&swap_expander);
+            let battery_progress = ProgressBar::new();
+            battery_progress.set_fraction(battery.percentage as f64 / 100.0);
+            battery_progress.set_show_text(true);
+            battery_progress.set_text(Some(&format!("{:.0}%", battery.percentage)));
+            battery_progress_box.append(&battery_progress);
+            
+            battery_expander.add_row(&battery_progress_box);
+            
+            if let Some(remaining) = battery.time_remaining {
+                let minutes = remaining.as_secs() / 60;
+                let title = if battery.charging { "Time Until Full" } else { "Time Remaining" };
+                Self::add_expander_detail(&battery_expander, title,
+                    &format!("{} hours, {} minutes", minutes / 60, minutes % 60));
+            }
+            if let Some(health) = battery.health {
+                Self::add_expander_detail(&battery_expander, "Health", &format!("{:.0}%", health));
+            }
+            
+            battery_group.add(&battery_expander);
+            content.append(&battery_group);
+        }
+        
+        // Power group, when brightness or power profiles can be controlled
+        if let Some(power_group) = Self::build_power_group() {
+            content.append(&power_group);
+        }
+        
+        // Network group
+        let interfaces: Vec<_> = SystemInfo::get_network_interfaces()
+            .into_iter()
+            .filter(|iface| iface.name != "lo")
+            .collect();
+        if !interfaces.is_empty() {
+            let network_group = adw::PreferencesGroup::new();
+            network_group.set_title("Network");
+            
+            for iface in &interfaces {
+                let iface_expander = adw::ExpanderRow::new();
+                iface_expander.set_title(&iface.name);
+                
+                let addresses: Vec<String> = iface.ipv4.iter().map(|ip| ip.to_string())
+                    .chain(iface.ipv6.iter().map(|ip| ip.to_string()))
+                    .collect();
+                let state = if iface.is_up { "Up" } else { "Down" };
+                match addresses.first() {
+                    Some(address) => iface_expander.set_subtitle(&format!("{} · {}", state, address)),
+                    None => iface_expander.set_subtitle(state),
+                }
+                
+                if let Some(mac) = &iface.mac {
+                    Self::add_expander_detail(&iface_expander, "MAC Address", mac);
+                }
+                for address in &addresses {
+                    Self::add_expander_detail(&iface_expander, "Address", address);
+                }
+                Self::add_expander_detail(&iface_expander, "Received", &SystemInfo::format_memory(iface.rx_bytes));
+                Self::add_expander_detail(&iface_expander, "Sent", &SystemInfo::format_memory(iface.tx_bytes));
+                
+                network_group.add(&iface_expander);
+            }
+            
+            content.append(&network_group);
         }
-
-        content.append(&hardware_group);
         
         // Temperature group
+        let mut temperature_widgets = Vec::new();
         let temperatures = SystemInfo::get_temperatures();
         if !temperatures.is_empty() {
             let temp_group = adw::PreferencesGroup::new();
             temp_group.set_title("System Temperatures");
             
-            for sensor in temperatures.iter().take(10) {
+            for sensor in temperatures.iter().take(MAX_TEMPERATURE_SENSORS) {
                 let temp_expander = adw::ExpanderRow::new();
                 temp_expander.set_title(&sensor.name);
                 
-                let status = SystemInfo::temperature_status(sensor.temperature);
-                let temp_display = format!("{} ({})", 
-                    SystemInfo::format_temperature(sensor.temperature),
-                    status
-                );
-                temp_expander.set_subtitle(&temp_display);
-                
-                // Add icon based on temperature
-                let icon_name = if sensor.temperature < 50.0 {
-                    "temperature-cold-symbolic"
-                } else if sensor.temperature < 70.0 {
-                    "temperature-warm-symbolic"
-                } else {
-                    "temperature-hot-symbolic"
-                };
-                
-                let temp_icon = gtk4::Image::from_icon_name(icon_name);
+                let temp_icon = gtk4::Image::new();
                 temp_expander.add_prefix(&temp_icon);
                 
                 // Add detailed info
-                Self::add_expander_detail(&temp_expander, "Temperature", 
-                    &SystemInfo::format_temperature(sensor.temperature));
-                Self::add_expander_detail(&temp_expander, "Status", status);
+                let temperature = Self::add_expander_detail(&temp_expander, "Temperature", "");
+                let status = Self::add_expander_detail(&temp_expander, "Status", "");
+                if let Some(critical) = sensor.critical {
+                    Self::add_expander_detail(&temp_expander, "Critical Temperature",
+                        &SystemInfo::format_temperature(critical));
+                }
                 Self::add_expander_detail(&temp_expander, "Sensor Label", &sensor.label);
                 
                 temp_group.add(&temp_expander);
+                temperature_widgets.push(TemperatureWidgets {
+                    sensor: sensor.name.clone(),
+                    expander: temp_expander,
+                    icon: temp_icon,
+                    temperature,
+                    status,
+                });
             }
             
             content.append(&temp_group);
@@ -174,10 +322,230 @@ impl SystemView {
 
         root.append(&scrolled);
 
+        let widgets = LiveWidgets {
+            uptime,
+            load,
+            tasks,
+            pressure,
+            core_bars,
+            core_rows,
+            memory,
+            memory_available,
+            memory_free,
+            memory_cache,
+            swap,
+            temperatures: temperature_widgets,
+        };
+
+        // Initial update
+        let core_usage = sys_info.per_core_usage();
+        Self::update_live(&sys_info, &core_usage, &widgets);
+        drop(sys_info);
+
+        // Refresh every 3 seconds, only what this page shows
+        glib::timeout_add_local(Duration::from_secs(3), move || {
+            let mut sys_info = shared_info.borrow_mut();
+            let core_usage = sys_info.per_core_usage();
+            sys_info.refresh_memory();
+            sys_info.refresh_processes();
+            Self::update_live(&sys_info, &core_usage, &widgets);
+            glib::ControlFlow::Continue
+        });
+
         Self { root }
     }
 
-    fn add_info_row(group: &adw::PreferencesGroup, title: &str, value: &str) {
+    fn build_power_group() -> Option<adw::PreferencesGroup> {
+        let backlight = power::get_backlight();
+        let profiles = power::get_power_profile();
+        if backlight.is_none() && profiles.is_none() {
+            return None;
+        }
+        
+        let power_group = adw::PreferencesGroup::new();
+        power_group.set_title("Power");
+        
+        if let Some(backlight) = backlight {
+            let scale = gtk4::Scale::with_range(Orientation::Horizontal, 1.0, 100.0, 1.0);
+            scale.set_value(backlight.percent());
+            scale.set_hexpand(true);
+            scale.set_width_request(200);
+            scale.set_valign(gtk4::Align::Center);
+            
+            let brightness_row = adw::ActionRow::new();
+            brightness_row.set_title("Brightness");
+            brightness_row.add_prefix(&gtk4::Image::from_icon_name("display-brightness-symbolic"));
+            brightness_row.add_suffix(&scale);
+            power_group.add(&brightness_row);
+            
+            // Each write may go through the privileged helper, so only write
+            // once the slider has settled
+            let pending: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+            scale.connect_value_changed(move |scale| {
+                if let Some(source) = pending.borrow_mut().take() {
+                    source.remove();
+                }
+                let scale = scale.clone();
+                let pending_clone = pending.clone();
+                let source = glib::timeout_add_local_once(BRIGHTNESS_SETTLE_DELAY, move || {
+                    pending_clone.borrow_mut().take();
+                    Self::report_power_result(&scale, "Failed to set brightness", power::set_backlight(scale.value()));
+                });
+                *pending.borrow_mut() = Some(source);
+            });
+        }
+        
+        if let Some(profiles) = profiles {
+            let labels: Vec<String> = profiles.available.iter()
+                .map(|profile| Self::power_profile_label(profile))
+                .collect();
+            let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+            
+            let profile_row = adw::ComboRow::new();
+            profile_row.set_title("Power Mode");
+            profile_row.add_prefix(&gtk4::Image::from_icon_name("power-profile-balanced-symbolic"));
+            profile_row.set_model(Some(&gtk4::StringList::new(&labels)));
+            if let Some(index) = profiles.available.iter().position(|p| *p == profiles.active) {
+                profile_row.set_selected(index as u32);
+            }
+            
+            profile_row.connect_selected_notify(move |row| {
+                if let Some(profile) = profiles.available.get(row.selected() as usize) {
+                    Self::report_power_result(row, "Failed to change power mode", power::set_power_profile(profile));
+                }
+            });
+            power_group.add(&profile_row);
+        }
+        
+        Some(power_group)
+    }
+    
+    /// Display name of a power-profiles-daemon profile
+    fn power_profile_label(profile: &str) -> String {
+        match profile {
+            "power-saver" => "Power Saver".to_string(),
+            "balanced" => "Balanced".to_string(),
+            "performance" => "Performance".to_string(),
+            other => other.to_string(),
+        }
+    }
+    
+    /// Show a toast when a power setting failed; a dismissed prompt is not reported
+    fn report_power_result(widget: &impl IsA<gtk4::Widget>, message: &str, result: anyhow::Result<()>) {
+        if let Err(e) = result {
+            if e.downcast_ref::<privileged::AuthenticationDismissed>().is_none() {
+                show_toast(widget, &format!("{}: {}", message, e));
+            }
+        }
+    }
+
+    fn update_live(sys_info: &SystemInfo, core_usage: &[f32], widgets: &LiveWidgets) {
+        widgets.uptime.set_text(&sys_info.format_uptime());
+        
+        let (one, five, fifteen) = SystemInfo::load_average();
+        widgets.load.set_text(&format!("{:.2} / {:.2} / {:.2}", one, five, fifteen));
+        widgets.tasks.set_text(&format!("{} ({} threads)",
+            sys_info.process_count(),
+            sys_info.thread_count()
+        ));
+        
+        // Share of the last 10 seconds some task spent stalled on the resource
+        if let (Some(labels), Some(pressure)) = (&widgets.pressure, SystemInfo::get_pressure()) {
+            for (label, resource) in labels.iter().zip([pressure.cpu, pressure.memory, pressure.io]) {
+                label.set_text(&format!("{:.1}%", resource.some.avg10));
+            }
+        }
+        
+        for (bar, usage) in widgets.core_bars.iter().zip(core_usage) {
+            bar.set_fraction((*usage as f64 / 100.0).clamp(0.0, 1.0));
+            bar.set_text(Some(&format!("{:.1}%", usage)));
+        }
+        
+        for freq in SystemInfo::get_cpu_frequencies() {
+            if let Some(row) = widgets.core_rows.get(freq.core) {
+                row.set_subtitle(&format!("{} of {}",
+                    SystemInfo::format_frequency(freq.current_mhz),
+                    SystemInfo::format_frequency(freq.max_mhz)));
+                row.set_tooltip_text(Some(&format!("Range: {} to {}",
+                    SystemInfo::format_frequency(freq.min_mhz),
+                    SystemInfo::format_frequency(freq.max_mhz))));
+            }
+        }
+        
+        let total_mem = sys_info.total_memory();
+        let used_mem = sys_info.used_memory();
+        let available_mem = sys_info.available_memory();
+        Self::update_usage(&widgets.memory, used_mem, total_mem);
+        widgets.memory_available.set_text(&SystemInfo::format_memory(available_mem));
+        widgets.memory_free.set_text(&SystemInfo::format_memory(sys_info.free_memory()));
+        widgets.memory_cache.set_text(
+            &SystemInfo::format_memory(used_mem.saturating_sub(total_mem - available_mem)));
+        
+        if let Some(swap) = &widgets.swap {
+            Self::update_usage(swap, sys_info.used_swap(), sys_info.total_swap());
+        }
+        
+        let temperatures = SystemInfo::get_temperatures();
+        for widget in &widgets.temperatures {
+            // Sensors can vanish, e.g. when a USB device is unplugged
+            let Some(sensor) = temperatures.iter().find(|s| s.name == widget.sensor) else {
+                continue;
+            };
+            
+            let status = SystemInfo::temperature_status(sensor);
+            widget.expander.set_subtitle(&format!("{} ({})",
+                SystemInfo::format_temperature(sensor.temperature),
+                status
+            ));
+            
+            // Icon based on temperature
+            let icon_name = match status {
+                "Normal" => "temperature-cold-symbolic",
+                "Warm" => "temperature-warm-symbolic",
+                _ => "temperature-hot-symbolic",
+            };
+            widget.icon.set_icon_name(Some(icon_name));
+            
+            widget.temperature.set_text(&SystemInfo::format_temperature(sensor.temperature));
+            widget.status.set_text(status);
+        }
+    }
+    
+    fn update_usage(widgets: &UsageWidgets, used: u64, total: u64) {
+        let percentage = if total > 0 { (used as f64 / total as f64) * 100.0 } else { 0.0 };
+        
+        widgets.expander.set_subtitle(&format!(
+            "{} / {} ({:.1}% used)",
+            SystemInfo::format_memory(used),
+            SystemInfo::format_memory(total),
+            percentage
+        ));
+        widgets.progress.set_fraction(percentage / 100.0);
+        widgets.progress.set_text(Some(&format!("{:.1}%", percentage)));
+        widgets.total.set_text(&SystemInfo::format_memory(total));
+        widgets.used.set_text(&SystemInfo::format_memory(used));
+        if let Some(free) = &widgets.free {
+            free.set_text(&SystemInfo::format_memory(total.saturating_sub(used)));
+        }
+    }
+    
+    /// Add a padded progress bar as the first row of an expander
+    fn add_expander_progress(expander: &adw::ExpanderRow) -> ProgressBar {
+        let progress_box = GtkBox::new(Orientation::Vertical, 6);
+        progress_box.set_margin_top(12);
+        progress_box.set_margin_bottom(12);
+        progress_box.set_margin_start(12);
+        progress_box.set_margin_end(12);
+        
+        let progress = ProgressBar::new();
+        progress.set_show_text(true);
+        progress_box.append(&progress);
+        
+        expander.add_row(&progress_box);
+        progress
+    }
+
+    fn add_info_row(group: &adw::PreferencesGroup, title: &str, value: &str) -> Label {
         let row = adw::ActionRow::new();
         row.set_title(title);
         
@@ -186,9 +554,10 @@ impl SystemView {
         row.add_suffix(&value_label);
         
         group.add(&row);
+        value_label
     }
     
-    fn add_expander_detail(expander: &adw::ExpanderRow, title: &str, value: &str) {
+    fn add_expander_detail(expander: &adw::ExpanderRow, title: &str, value: &str) -> Label {
         let row = adw::ActionRow::new();
         row.set_title(title);
         
@@ -197,9 +566,28 @@ impl SystemView {
         row.add_suffix(&value_label);
         
         expander.add_row(&row);
+        value_label
+    }
+}
+
+impl Module for SystemView {
+    fn id(&self) -> &str {
+        "system"
+    }
+
+    fn name(&self) -> &str {
+        "System Info"
+    }
+
+    fn description(&self) -> &str {
+        "Operating system, hardware and sensor overview"
+    }
+
+    fn icon_name(&self) -> &str {
+        "computer-symbolic"
     }
 
-    pub fn build(&self) -> GtkBox {
-        self.root.clone()
+    fn widget(&self) -> gtk4::Widget {
+        self.root.clone().upcast()
     }
 }
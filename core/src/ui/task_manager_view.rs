@@ -1,24 +1,80 @@
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Label, Orientation, ScrolledWindow, ListBox, ProgressBar, glib};
+use gtk4::{Box as GtkBox, Label, Orientation, ScrolledWindow, ListBox, ProgressBar, Button, DropDown, MessageDialog, ButtonsType, MessageType, glib};
 use libadwaita as adw;
 use adw::prelude::*;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
-use std::time::Duration;
-use sysinfo::System;
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sysinfo::{Process, ProcessStatus};
+use nix::sys::signal::Signal;
+
+use crate::process_manager::{self, ProcessNode, ProcessSortKey};
+use crate::system_info::{DiskIoStat, SystemInfo};
+use crate::config::Settings;
+use crate::module_loader::Module;
+
+/// Refresh interval choices; `None` pauses updates
+const REFRESH_INTERVALS: [(&str, Option<Duration>); 4] = [
+    ("1 second", Some(Duration::from_secs(1))),
+    ("2 seconds", Some(Duration::from_secs(2))),
+    ("5 seconds", Some(Duration::from_secs(5))),
+    ("Paused", None),
+];
+
+/// Index into `REFRESH_INTERVALS` used when the saved interval isn't offered
+const DEFAULT_REFRESH_INTERVAL: usize = 1;
+
+/// Dialog response for sending SIGKILL instead of SIGTERM
+const RESPONSE_FORCE_KILL: gtk4::ResponseType = gtk4::ResponseType::Other(1);
 
 pub struct TaskManagerView {
     root: GtkBox,
 }
 
+/// Layout of the process list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ProcessListView {
+    /// Top processes as a flat list
+    #[default]
+    Processes,
+    /// All processes nested under their parents
+    Tree,
+    /// Processes sharing a name added up
+    Applications,
+}
+
+impl ProcessListView {
+    const ALL: [ProcessListView; 3] = [
+        ProcessListView::Processes,
+        ProcessListView::Tree,
+        ProcessListView::Applications,
+    ];
+    
+    fn label(&self) -> &'static str {
+        match self {
+            ProcessListView::Processes => "Processes",
+            ProcessListView::Tree => "Tree",
+            ProcessListView::Applications => "Applications",
+        }
+    }
+}
+
+/// How the process list is shown, kept across refreshes
+#[derive(Default)]
+struct ProcessListState {
+    sort_key: Cell<ProcessSortKey>,
+    view: Cell<ProcessListView>,
+    /// Processes whose children are shown in the tree view
+    expanded: RefCell<HashSet<sysinfo::Pid>>,
+}
+
 impl TaskManagerView {
-    pub fn new() -> Self {
+    pub fn new(settings: Rc<RefCell<Settings>>, system: Rc<RefCell<SystemInfo>>) -> Self {
         let root = GtkBox::new(Orientation::Vertical, 0);
         root.add_css_class("view");
 
-        // Create system info wrapper
-        let system = Rc::new(RefCell::new(System::new_all()));
-
         // Resource overview section
         let overview_box = GtkBox::new(Orientation::Vertical, 12);
         overview_box.set_margin_start(24);
@@ -68,14 +124,74 @@ impl TaskManagerView {
         
         overview_box.append(&mem_group);
 
+        // Disk activity
+        let disk_group = adw::PreferencesGroup::new();
+        disk_group.set_title("Disk Activity");
+        
+        let disk_label = Label::new(Some("Measuring..."));
+        disk_label.set_halign(gtk4::Align::Start);
+        disk_label.add_css_class("dim-label");
+        
+        let disk_row = adw::ActionRow::new();
+        disk_row.set_child(Some(&disk_label));
+        disk_group.add(&disk_row);
+        
+        overview_box.append(&disk_group);
+
         root.append(&overview_box);
 
+        // Zombies, only shown while there are any
+        let zombie_group = adw::PreferencesGroup::new();
+        zombie_group.set_description(Some("Exited processes their parent hasn't collected. They can't be killed; ending or restarting the parent clears them."));
+        zombie_group.set_margin_start(24);
+        zombie_group.set_margin_end(24);
+        zombie_group.set_margin_bottom(12);
+        zombie_group.set_visible(false);
+        
+        let zombie_list = ListBox::new();
+        zombie_list.add_css_class("boxed-list");
+        zombie_group.add(&zombie_list);
+        root.append(&zombie_group);
+
         // Process list section
         let process_group = adw::PreferencesGroup::new();
         process_group.set_title("Running Processes");
         process_group.set_margin_start(24);
         process_group.set_margin_end(24);
         process_group.set_margin_bottom(24);
+        
+        let list_state = Rc::new(ProcessListState::default());
+        let sort_labels: Vec<&str> = ProcessSortKey::ALL.iter().map(|key| key.label()).collect();
+        let sort_dropdown = DropDown::from_strings(&sort_labels);
+        
+        let sort_box = GtkBox::new(Orientation::Horizontal, 12);
+        sort_box.set_margin_bottom(12);
+        sort_box.append(&Label::new(Some("Sort by:")));
+        sort_box.append(&sort_dropdown);
+        
+        let view_labels: Vec<&str> = ProcessListView::ALL.iter().map(|view| view.label()).collect();
+        let view_dropdown = DropDown::from_strings(&view_labels);
+        
+        let view_label = Label::new(Some("Show:"));
+        view_label.set_margin_start(12);
+        sort_box.append(&view_label);
+        sort_box.append(&view_dropdown);
+        
+        let refresh_labels: Vec<&str> = REFRESH_INTERVALS.iter().map(|(label, _)| *label).collect();
+        let refresh_dropdown = DropDown::from_strings(&refresh_labels);
+        
+        // A saved interval of 0 means paused
+        let saved_secs = settings.borrow().process_refresh_secs;
+        let refresh_index = REFRESH_INTERVALS.iter()
+            .position(|(_, interval)| interval.map_or(0, |i| i.as_secs()) == saved_secs)
+            .unwrap_or(DEFAULT_REFRESH_INTERVAL);
+        refresh_dropdown.set_selected(refresh_index as u32);
+        
+        let refresh_label = Label::new(Some("Refresh:"));
+        refresh_label.set_margin_start(12);
+        sort_box.append(&refresh_label);
+        sort_box.append(&refresh_dropdown);
+        process_group.add(&sort_box);
 
         let process_list = ListBox::new();
         process_list.add_css_class("boxed-list");
@@ -95,19 +211,11 @@ impl TaskManagerView {
         let mem_bar_clone = mem_bar.clone();
         let mem_label_clone = mem_label.clone();
         let process_list_clone = process_list.clone();
-
-        // Initial update
-        Self::update_system_info(
-            &system_clone,
-            &cpu_bar_clone,
-            &cpu_label_clone,
-            &mem_bar_clone,
-            &mem_label_clone,
-            &process_list_clone,
-        );
-
-        // Refresh every 2 seconds
-        glib::timeout_add_local(Duration::from_secs(2), move || {
+        let list_state_clone = list_state.clone();
+        let zombie_group_clone = zombie_group.clone();
+        let zombie_list_clone = zombie_list.clone();
+        
+        let update = Rc::new(move || {
             Self::update_system_info(
                 &system_clone,
                 &cpu_bar_clone,
@@ -115,20 +223,112 @@ impl TaskManagerView {
                 &mem_bar_clone,
                 &mem_label_clone,
                 &process_list_clone,
+                &list_state_clone,
             );
+            Self::update_zombies(&system_clone.borrow(), &zombie_group_clone, &zombie_list_clone);
+        });
+
+        // Initial update
+        update();
+
+        // Re-sort right away instead of waiting for the next refresh
+        let update_clone = update.clone();
+        let list_state_clone = list_state.clone();
+        sort_dropdown.connect_selected_notify(move |dropdown| {
+            if let Some(key) = ProcessSortKey::ALL.get(dropdown.selected() as usize) {
+                list_state_clone.sort_key.set(*key);
+                update_clone();
+            }
+        });
+        
+        let update_clone = update.clone();
+        view_dropdown.connect_selected_notify(move |dropdown| {
+            if let Some(view) = ProcessListView::ALL.get(dropdown.selected() as usize) {
+                list_state.view.set(*view);
+                update_clone();
+            }
+        });
+
+        // Periodic refresh; only one timer runs at a time, none while paused
+        let refresh_source: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+        let update: Rc<dyn Fn()> = update;
+        
+        if let Some(interval) = REFRESH_INTERVALS[refresh_index].1 {
+            *refresh_source.borrow_mut() = Some(Self::start_refresh(update.clone(), interval));
+        }
+        
+        refresh_dropdown.connect_selected_notify(move |dropdown| {
+            if let Some(source) = refresh_source.borrow_mut().take() {
+                source.remove();
+            }
+            let Some((_, interval)) = REFRESH_INTERVALS.get(dropdown.selected() as usize) else {
+                return;
+            };
+            if let Some(interval) = interval {
+                *refresh_source.borrow_mut() = Some(Self::start_refresh(update.clone(), *interval));
+            }
+            
+            let mut settings = settings.borrow_mut();
+            settings.process_refresh_secs = interval.map_or(0, |i| i.as_secs());
+            settings.save_or_warn();
+        });
+
+        // Sampling disk I/O blocks, so do it on a worker thread that stops
+        // once the receiver is gone
+        let (sender, receiver) = mpsc::channel::<Vec<DiskIoStat>>();
+        std::thread::spawn(move || loop {
+            if sender.send(SystemInfo::get_disk_io()).is_err() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1500));
+        });
+        
+        let disk_label_weak = disk_label.downgrade();
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            let Some(disk_label) = disk_label_weak.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            if let Some(stats) = receiver.try_iter().last() {
+                Self::update_disk_activity(&disk_label, &stats);
+            }
             glib::ControlFlow::Continue
         });
 
         Self { root }
     }
 
+    fn start_refresh(update: Rc<dyn Fn()>, interval: Duration) -> glib::SourceId {
+        glib::timeout_add_local(interval, move || {
+            update();
+            glib::ControlFlow::Continue
+        })
+    }
+
+    fn update_disk_activity(disk_label: &Label, stats: &[DiskIoStat]) {
+        if stats.is_empty() {
+            disk_label.set_text("No disks found");
+            return;
+        }
+        
+        let lines: Vec<String> = stats.iter()
+            .map(|stat| format!(
+                "{}: {}/s read • {}/s written",
+                stat.device,
+                Self::format_bytes(stat.read_bytes_per_sec as u64),
+                Self::format_bytes(stat.write_bytes_per_sec as u64)
+            ))
+            .collect();
+        disk_label.set_text(&lines.join("\n"));
+    }
+
     fn update_system_info(
-        system: &Rc<RefCell<System>>,
+        system: &Rc<RefCell<SystemInfo>>,
         cpu_bar: &ProgressBar,
         cpu_label: &Label,
         mem_bar: &ProgressBar,
         mem_label: &Label,
         process_list: &ListBox,
+        list_state: &Rc<ProcessListState>,
     ) {
         let mut sys = system.borrow_mut();
         sys.refresh_cpu();
@@ -136,8 +336,8 @@ impl TaskManagerView {
         sys.refresh_processes();
 
         // Update CPU
-        let global_cpu_usage = sys.global_cpu_info().cpu_usage();
-        let cpu_count = sys.cpus().len();
+        let global_cpu_usage = sys.global_cpu_usage();
+        let cpu_count = sys.cpu_count();
         cpu_bar.set_fraction((global_cpu_usage / 100.0) as f64);
         cpu_bar.set_text(Some(&format!("{:.1}%", global_cpu_usage)));
         cpu_label.set_text(&format!("{} logical processors", cpu_count));
@@ -161,34 +361,352 @@ impl TaskManagerView {
             process_list.remove(&child);
         }
 
-        // Get top processes by CPU usage
-        let mut processes: Vec<_> = sys.processes().iter().collect();
-        processes.sort_by(|a, b| {
-            b.1.cpu_usage()
-                .partial_cmp(&a.1.cpu_usage())
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
+        let sort_key = list_state.sort_key.get();
+        
+        match list_state.view.get() {
+            ProcessListView::Processes => {}
+            ProcessListView::Tree => {
+                for node in process_manager::build_process_tree(sys.processes(), sort_key) {
+                    process_list.append(&Self::build_process_node(&node, sys.processes(), list_state));
+                }
+                return;
+            }
+            ProcessListView::Applications => {
+                let mut applications = process_manager::aggregate_by_name(sys.processes());
+                applications.sort_by(|a, b| sort_key.compare_aggregated(a, b));
+                
+                // Shared memory and tmpfs pages aren't charged to any process, so
+                // the sum can fall short of the system total
+                if sys.used_swap() > 0 {
+                    let attributed: u64 = applications.iter().map(|application| application.swap).sum();
+                    let row = adw::ActionRow::new();
+                    row.set_title("Swap");
+                    row.set_subtitle(&format!(
+                        "{} used by processes • {} in use system-wide",
+                        Self::format_bytes(attributed),
+                        Self::format_bytes(sys.used_swap())
+                    ));
+                    process_list.append(&row);
+                }
+                
+                for application in applications.iter().take(20) {
+                    let row = adw::ActionRow::new();
+                    row.set_title(&application.name);
+                    row.set_subtitle(&format!(
+                        "CPU: {:.1}% • Memory: {} • Swap: {} • {} {}",
+                        application.cpu_usage,
+                        Self::format_bytes(application.memory),
+                        Self::format_bytes(application.swap),
+                        application.count(),
+                        if application.count() == 1 { "process" } else { "processes" }
+                    ));
+                    process_list.append(&row);
+                }
+                return;
+            }
+        }
+
+        // Get top processes by the selected key
+        let mut processes: Vec<_> = sys.processes().values().collect();
+        processes.sort_by(|a, b| sort_key.compare(a, b));
 
         // Show top 20 processes
-        for (pid, process) in processes.iter().take(20) {
+        for process in processes.iter().take(20) {
             let row = adw::ActionRow::new();
+            row.set_title(process.name());
+            row.set_subtitle(&Self::process_subtitle(process));
+            if let Some(kill_btn) = Self::build_kill_button(process) {
+                row.add_suffix(&kill_btn);
+            }
             
-            let name = process.name();
-            let cpu = process.cpu_usage();
-            let mem = process.memory();
-            
-            row.set_title(name);
-            row.set_subtitle(&format!(
-                "PID: {} • CPU: {:.1}% • Memory: {}",
-                pid,
-                cpu,
-                Self::format_bytes(mem)
-            ));
+            let details = process_manager::get_process_details(process);
+            let name = process.name().to_string();
+            let pid = process.pid();
+            row.set_activatable(true);
+            row.connect_activated(move |row| {
+                Self::show_process_details(row, pid, &name, &details);
+            });
 
             process_list.append(&row);
         }
     }
 
+    fn update_zombies(sys: &SystemInfo, zombie_group: &adw::PreferencesGroup, zombie_list: &ListBox) {
+        while let Some(child) = zombie_list.first_child() {
+            zombie_list.remove(&child);
+        }
+        
+        let processes = sys.processes();
+        let zombies = process_manager::get_zombie_processes(processes);
+        zombie_group.set_visible(!zombies.is_empty());
+        zombie_group.set_title(&format!("Zombies ({})", zombies.len()));
+        
+        for zombie in zombies {
+            let parent = match zombie.parent() {
+                Some(ppid) => match processes.get(&ppid) {
+                    Some(parent) => format!("{} (PID {})", parent.name(), ppid),
+                    None => format!("PID {}", ppid),
+                },
+                None => "Unknown".to_string(),
+            };
+            
+            let row = adw::ActionRow::new();
+            row.set_title(zombie.name());
+            row.set_subtitle(&format!("PID: {} • Parent: {}", zombie.pid(), parent));
+            zombie_list.append(&row);
+        }
+    }
+
+    /// Build the row for a process tree node, nesting its children in an expander
+    fn build_process_node(
+        node: &ProcessNode,
+        processes: &HashMap<sysinfo::Pid, Process>,
+        list_state: &Rc<ProcessListState>,
+    ) -> gtk4::Widget {
+        let Some(process) = processes.get(&node.pid) else {
+            return adw::ActionRow::new().upcast();
+        };
+        
+        let details = process_manager::get_process_details(process);
+        let name = process.name().to_string();
+        let pid = node.pid;
+        
+        if node.children.is_empty() {
+            let row = adw::ActionRow::new();
+            row.set_title(process.name());
+            row.set_subtitle(&Self::process_subtitle(process));
+            if let Some(kill_btn) = Self::build_kill_button(process) {
+                row.add_suffix(&kill_btn);
+            }
+            
+            row.set_activatable(true);
+            row.connect_activated(move |row| {
+                Self::show_process_details(row, pid, &name, &details);
+            });
+            return row.upcast();
+        }
+        
+        let expander = adw::ExpanderRow::new();
+        expander.set_title(process.name());
+        expander.set_subtitle(&format!("{} • {} child processes",
+            Self::process_subtitle(process),
+            node.children.len()
+        ));
+        
+        let details_btn = Button::from_icon_name("dialog-information-symbolic");
+        details_btn.set_tooltip_text(Some("Details"));
+        details_btn.set_valign(gtk4::Align::Center);
+        details_btn.add_css_class("flat");
+        details_btn.connect_clicked(move |btn| {
+            Self::show_process_details(btn, pid, &name, &details);
+        });
+        expander.add_suffix(&details_btn);
+        if let Some(kill_btn) = Self::build_kill_button(process) {
+            expander.add_suffix(&kill_btn);
+        }
+        
+        for child in &node.children {
+            expander.add_row(&Self::build_process_node(child, processes, list_state));
+        }
+        
+        // Collapsed by default; remember what the user expanded across refreshes
+        expander.set_expanded(list_state.expanded.borrow().contains(&pid));
+        let list_state = list_state.clone();
+        expander.connect_expanded_notify(move |expander| {
+            let mut expanded = list_state.expanded.borrow_mut();
+            if expander.is_expanded() {
+                expanded.insert(pid);
+            } else {
+                expanded.remove(&pid);
+            }
+        });
+        
+        expander.upcast()
+    }
+
+    fn process_subtitle(process: &Process) -> String {
+        let subtitle = format!(
+            "PID: {} • CPU: {:.1}% • Memory: {}",
+            process.pid(),
+            process.cpu_usage(),
+            Self::format_bytes(process.memory())
+        );
+        match process_manager::unusual_state(process) {
+            Some(state) => format!("{} • {}", subtitle, state),
+            None => subtitle,
+        }
+    }
+
+    /// End Process button, `None` for zombies since signals don't reach them
+    fn build_kill_button(process: &Process) -> Option<Button> {
+        if process.status() == ProcessStatus::Zombie {
+            return None;
+        }
+        
+        let kill_btn = Button::from_icon_name("process-stop-symbolic");
+        kill_btn.set_tooltip_text(Some("End Process"));
+        kill_btn.set_valign(gtk4::Align::Center);
+        kill_btn.add_css_class("flat");
+        
+        let pid = process.pid();
+        let name = process.name().to_string();
+        kill_btn.connect_clicked(move |btn| {
+            Self::confirm_kill(btn, pid, &name);
+        });
+        Some(kill_btn)
+    }
+
+    fn show_process_details(
+        widget: &impl IsA<gtk4::Widget>,
+        pid: sysinfo::Pid,
+        name: &str,
+        details: &process_manager::ProcessDetails,
+    ) {
+        let window = widget.root().and_downcast::<gtk4::Window>();
+        
+        let dialog = adw::Window::new();
+        dialog.set_title(Some(name));
+        dialog.set_default_size(600, 400);
+        dialog.set_modal(true);
+        if let Some(parent) = window {
+            dialog.set_transient_for(Some(&parent));
+        }
+        
+        let dialog_box = GtkBox::new(Orientation::Vertical, 0);
+        
+        // Header
+        let header = adw::HeaderBar::new();
+        let title_widget = adw::WindowTitle::new(name, &format!("PID {}", pid));
+        header.set_title_widget(Some(&title_widget));
+        dialog_box.append(&header);
+        
+        let details_group = adw::PreferencesGroup::new();
+        details_group.set_margin_top(24);
+        details_group.set_margin_bottom(24);
+        details_group.set_margin_start(24);
+        details_group.set_margin_end(24);
+        
+        // The command line can be long, so it gets a wrapping label of its own
+        let command_label = Label::new(Some(&details.command_line));
+        command_label.set_wrap(true);
+        command_label.set_wrap_mode(gtk4::pango::WrapMode::WordChar);
+        command_label.set_selectable(true);
+        command_label.set_xalign(0.0);
+        command_label.add_css_class("monospace");
+        command_label.set_margin_top(12);
+        command_label.set_margin_bottom(12);
+        command_label.set_margin_start(12);
+        command_label.set_margin_end(12);
+        
+        let command_row = adw::ActionRow::new();
+        command_row.set_title("Command Line");
+        command_row.set_child(Some(&command_label));
+        details_group.add(&command_row);
+        
+        let started = UNIX_EPOCH + Duration::from_secs(details.start_time);
+        let running_for = SystemTime::now().duration_since(started).unwrap_or_default();
+        let open_files = details.open_files
+            .map(|count| count.to_string())
+            .unwrap_or_else(|| "Not permitted".to_string());
+        
+        for (title, value) in [
+            ("User", details.user.clone()),
+            ("Started", format!("{} ago", Self::format_elapsed(running_for))),
+            ("Open Files", open_files),
+        ] {
+            let row = adw::ActionRow::new();
+            row.set_title(title);
+            
+            let value_label = Label::new(Some(&value));
+            value_label.add_css_class("dim-label");
+            row.add_suffix(&value_label);
+            
+            details_group.add(&row);
+        }
+        
+        let scrolled = ScrolledWindow::new();
+        scrolled.set_vexpand(true);
+        scrolled.set_child(Some(&details_group));
+        dialog_box.append(&scrolled);
+        
+        dialog.set_content(Some(&dialog_box));
+        dialog.present();
+    }
+
+    fn format_elapsed(elapsed: Duration) -> String {
+        let secs = elapsed.as_secs();
+        let days = secs / 86400;
+        let hours = (secs % 86400) / 3600;
+        let minutes = (secs % 3600) / 60;
+
+        if days > 0 {
+            format!("{} days, {} hours", days, hours)
+        } else if hours > 0 {
+            format!("{} hours, {} minutes", hours, minutes)
+        } else if minutes > 0 {
+            format!("{} minutes", minutes)
+        } else {
+            format!("{} seconds", secs)
+        }
+    }
+
+    fn confirm_kill(btn: &Button, pid: sysinfo::Pid, name: &str) {
+        let Some(window) = btn.root().and_downcast::<gtk4::Window>() else {
+            return;
+        };
+        
+        let dialog = MessageDialog::new(
+            Some(&window),
+            gtk4::DialogFlags::MODAL,
+            MessageType::Warning,
+            ButtonsType::None,
+            format!("End process '{}' (PID {})? Unsaved data in it may be lost.", name, pid)
+        );
+        dialog.set_title(Some("End Process"));
+        dialog.add_button("Cancel", gtk4::ResponseType::Cancel);
+        dialog.add_button("Force Kill", RESPONSE_FORCE_KILL);
+        dialog.add_button("End Process", gtk4::ResponseType::Ok);
+        
+        let btn = btn.clone();
+        dialog.connect_response(move |dialog, response| {
+            dialog.close();
+            let signal = match response {
+                gtk4::ResponseType::Ok => Signal::SIGTERM,
+                RESPONSE_FORCE_KILL => Signal::SIGKILL,
+                _ => return,
+            };
+            let pid = nix::unistd::Pid::from_raw(pid.as_u32() as i32);
+            Self::report_result(&btn, "Failed to End Process", process_manager::kill_process(pid, signal));
+        });
+        
+        dialog.present();
+    }
+
+    fn report_result(widget: &impl IsA<gtk4::Widget>, title: &str, result: anyhow::Result<()>) {
+        let Err(e) = result else {
+            return;
+        };
+        
+        if e.downcast_ref::<process_manager::AuthenticationDismissed>().is_some() {
+            return;
+        }
+        
+        if let Some(window) = widget.root().and_downcast::<gtk4::Window>() {
+            let dialog = MessageDialog::new(
+                Some(&window),
+                gtk4::DialogFlags::MODAL,
+                MessageType::Error,
+                ButtonsType::Ok,
+                e.to_string()
+            );
+            dialog.set_title(Some(title));
+            dialog.connect_response(|dialog, _| {
+                dialog.close();
+            });
+            dialog.present();
+        }
+    }
+
     fn format_bytes(bytes: u64) -> String {
         const GB: u64 = 1024 * 1024 * 1024;
         const MB: u64 = 1024 * 1024;
@@ -204,8 +722,26 @@ impl TaskManagerView {
             format!("{} B", bytes)
         }
     }
+}
+
+impl Module for TaskManagerView {
+    fn id(&self) -> &str {
+        "tasks"
+    }
+
+    fn name(&self) -> &str {
+        "Task Manager"
+    }
+
+    fn description(&self) -> &str {
+        "Running processes and resource usage"
+    }
+
+    fn icon_name(&self) -> &str {
+        "utilities-system-monitor-symbolic"
+    }
 
-    pub fn build(&self) -> GtkBox {
-        self.root.clone()
+    fn widget(&self) -> gtk4::Widget {
+        self.root.clone().upcast()
     }
 }
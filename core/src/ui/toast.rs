@@ -0,0 +1,15 @@
+use gtk4::prelude::*;
+use libadwaita as adw;
+
+/// Show a short notification in the toast overlay that contains `widget`
+///
+/// Views don't hold the overlay themselves; the main window wraps every page in one.
+pub fn show_toast(widget: &impl IsA<gtk4::Widget>, message: &str) {
+    let overlay = widget.ancestor(adw::ToastOverlay::static_type())
+        .and_downcast::<adw::ToastOverlay>();
+    
+    match overlay {
+        Some(overlay) => overlay.add_toast(adw::Toast::new(message)),
+        None => tracing::warn!("No toast overlay to show: {}", message),
+    }
+}
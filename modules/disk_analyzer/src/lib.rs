@@ -1,10 +1,20 @@
 //! Disk Analyzer Module
 //! 
-//! Provides disk usage information for mounted filesystems and folder analysis.
+//! Provides disk usage information for mounted filesystems and folder analysis,
+//! and SMART health of the physical disks.
 
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
 use std::path::{Path, PathBuf};
 use std::fs;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use anyhow::Result;
+use privileged::{AuthenticationDismissed, HelperCommand};
 
 #[derive(Debug, Clone)]
 pub struct MountPoint {
@@ -14,6 +24,15 @@ pub struct MountPoint {
     pub total: u64,
     pub used: u64,
     pub available: u64,
+    pub total_inodes: u64,
+    pub used_inodes: u64,
+    pub available_inodes: u64,
+    /// NFS, SMB or SSHFS share
+    pub is_network: bool,
+    /// Backed by a block device the kernel flags as removable (USB sticks, SD cards)
+    pub is_removable: bool,
+    /// Mount options from /proc/mounts, e.g. `rw`, `noexec`, `relatime`
+    pub options: Vec<String>,
 }
 
 impl MountPoint {
@@ -26,6 +45,22 @@ impl MountPoint {
         }
     }
 
+    /// Whether the filesystem is mounted read-only
+    pub fn is_readonly(&self) -> bool {
+        self.options.iter().any(|o| o == "ro")
+    }
+
+    /// Calculate the percentage of inodes used
+    /// 
+    /// Filesystems without a fixed inode table (e.g. btrfs) report zero inodes.
+    pub fn inode_used_percentage(&self) -> f64 {
+        if self.total_inodes == 0 {
+            0.0
+        } else {
+            (self.used_inodes as f64 / self.total_inodes as f64) * 100.0
+        }
+    }
+
     /// Format bytes into human-readable size string
     pub fn format_size(bytes: u64) -> String {
         const GB: u64 = 1024 * 1024 * 1024;
@@ -44,53 +79,213 @@ impl MountPoint {
 #[derive(Debug, Clone)]
 pub struct FolderInfo {
     pub path: PathBuf,
+    /// Apparent size, the sum of file lengths
     pub size: u64,
+    /// Space actually allocated on disk
+    /// 
+    /// Less than `size` for sparse files, more for small files that still take
+    /// up a whole block. This is what deleting the folder would reclaim.
+    pub size_on_disk: u64,
     pub file_count: usize,
     pub dir_count: usize,
 }
 
 impl FolderInfo {
+    fn new(path: PathBuf, totals: FolderTotals) -> Self {
+        Self {
+            path,
+            size: totals.size,
+            size_on_disk: totals.size_on_disk,
+            file_count: totals.file_count,
+            dir_count: totals.dir_count,
+        }
+    }
+    
     pub fn format_size(&self) -> String {
         MountPoint::format_size(self.size)
     }
+    
+    pub fn format_size_on_disk(&self) -> String {
+        MountPoint::format_size(self.size_on_disk)
+    }
+}
+
+/// Space allocated to a file, from its 512-byte block count
+fn allocated_size(metadata: &fs::Metadata) -> u64 {
+    metadata.blocks() * 512
+}
+
+/// Running totals for one folder walk
+#[derive(Debug, Default, Clone, Copy)]
+struct FolderTotals {
+    size: u64,
+    size_on_disk: u64,
+    file_count: usize,
+    dir_count: usize,
+}
+
+impl std::ops::AddAssign for FolderTotals {
+    fn add_assign(&mut self, other: Self) {
+        self.size += other.size;
+        self.size_on_disk += other.size_on_disk;
+        self.file_count += other.file_count;
+        self.dir_count += other.dir_count;
+    }
+}
+
+/// Filesystem types that live on another machine
+const NETWORK_FS_TYPES: &[&str] = &["nfs", "nfs4", "cifs", "smb3", "smbfs", "fuse.sshfs"];
+
+/// Check whether a filesystem type is a network filesystem
+fn is_network_fs(fs_type: &str) -> bool {
+    NETWORK_FS_TYPES.contains(&fs_type)
+}
+
+/// Check whether a device node belongs to a removable block device
+/// 
+/// Partitions are resolved to their parent disk, since only whole disks carry
+/// the `removable` attribute in sysfs.
+fn is_removable_device(device: &str) -> bool {
+    let Ok(device) = fs::canonicalize(device) else {
+        return false;
+    };
+    let Some(name) = device.file_name() else {
+        return false;
+    };
+    let Ok(mut sys_path) = fs::canonicalize(Path::new("/sys/class/block").join(name)) else {
+        return false;
+    };
+    
+    if sys_path.join("partition").exists() {
+        sys_path.pop();
+    }
+    
+    fs::read_to_string(sys_path.join("removable"))
+        .map(|v| v.trim() == "1")
+        .unwrap_or(false)
+}
+
+/// One line of /proc/mounts
+#[derive(Debug, Clone, PartialEq)]
+struct MountEntry {
+    device: String,
+    mount_point: PathBuf,
+    fs_type: String,
+    options: Vec<String>,
+}
+
+/// Parse /proc/mounts, keeping one entry per real filesystem
+/// 
+/// Virtual filesystems are dropped, as are squashfs images (snap packages)
+/// unless `include_squashfs` is set. Bind mounts and filesystems mounted at
+/// several paths share a device, so entries are deduplicated by device and
+/// type, keeping the shortest mount path. Escaped characters in device names
+/// and mount points, such as `\040` for a space, are decoded.
+fn parse_mounts(contents: &str, include_squashfs: bool) -> Vec<MountEntry> {
+    let mut entries: Vec<MountEntry> = Vec::new();
+    
+    for line in contents.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 3 {
+            continue;
+        }
+        
+        let device = String::from_utf8_lossy(&unescape_mount_field(parts[0])).to_string();
+        let mount_point = PathBuf::from(OsStr::from_bytes(&unescape_mount_field(parts[1])));
+        let fs_type = parts[2].to_string();
+        let options: Vec<String> = parts.get(3)
+            .map(|o| o.split(',').map(String::from).collect())
+            .unwrap_or_default();
+        
+        // Skip virtual filesystems
+        if (fs_type.starts_with("fuse") && !is_network_fs(&fs_type)) || 
+           fs_type == "tmpfs" || 
+           fs_type == "devtmpfs" ||
+           fs_type == "proc" ||
+           fs_type == "sysfs" ||
+           fs_type == "cgroup" ||
+           fs_type == "devpts" {
+            continue;
+        }
+        
+        if fs_type == "squashfs" && !include_squashfs {
+            continue;
+        }
+        
+        let entry = MountEntry { device, mount_point, fs_type, options };
+        match entries.iter_mut().find(|e| e.device == entry.device && e.fs_type == entry.fs_type) {
+            Some(existing) => {
+                if entry.mount_point.as_os_str().len() < existing.mount_point.as_os_str().len() {
+                    *existing = entry;
+                }
+            }
+            None => entries.push(entry),
+        }
+    }
+    
+    entries
+}
+
+/// Decode the octal escapes the kernel writes in /proc/mounts fields
+/// 
+/// Space, tab, newline and backslash appear as `\040`, `\011`, `\012` and
+/// `\134`; anything else is kept as written.
+fn unescape_mount_field(field: &str) -> Vec<u8> {
+    let bytes = field.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    
+    while i < bytes.len() {
+        let octal = bytes.get(i + 1..i + 4)
+            .filter(|digits| bytes[i] == b'\\' && digits.iter().all(|b| (b'0'..=b'7').contains(b)))
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        match octal {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 4;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    
+    decoded
 }
 
 /// Get information about all mounted filesystems
 /// 
 /// Reads /proc/mounts and queries disk usage statistics.
-/// Virtual filesystems (proc, sysfs, tmpfs, etc.) are filtered out.
+/// Virtual filesystems (proc, sysfs, tmpfs, etc.) are filtered out; FUSE
+/// filesystems are too, except network ones like sshfs. Each filesystem is
+/// listed once, and snap squashfs images are left out.
 pub fn get_mount_points() -> Result<Vec<MountPoint>> {
+    scan_mount_points(false)
+}
+
+/// Get information about mounted filesystems, optionally with squashfs images
+/// 
+/// Like `get_mount_points`, but with `include_squashfs` set read-only squashfs
+/// mounts such as snap packages are listed too.
+pub fn scan_mount_points(include_squashfs: bool) -> Result<Vec<MountPoint>> {
     let mut mounts = Vec::new();
     
     // Read /proc/mounts for mounted filesystems
     if let Ok(contents) = std::fs::read_to_string("/proc/mounts") {
-        for line in contents.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() < 3 {
-                continue;
-            }
-            
-            let device = parts[0].to_string();
-            let mount_point = PathBuf::from(parts[1]);
-            let fs_type = parts[2].to_string();
-            
-            // Skip virtual filesystems
-            if fs_type.starts_with("fuse") || 
-               fs_type == "tmpfs" || 
-               fs_type == "devtmpfs" ||
-               fs_type == "proc" ||
-               fs_type == "sysfs" ||
-               fs_type == "cgroup" ||
-               fs_type == "devpts" {
-                continue;
-            }
-            
+        for MountEntry { device, mount_point, fs_type, options } in parse_mounts(&contents, include_squashfs) {
             // Get disk usage stats using statvfs
             if let Ok(stat) = nix::sys::statvfs::statvfs(&mount_point) {
                 let block_size = stat.block_size();
                 let total = stat.blocks() * block_size;
                 let available = stat.blocks_available() * block_size;
                 let used = total - (stat.blocks_free() * block_size);
+                let total_inodes = stat.files();
+                let used_inodes = total_inodes.saturating_sub(stat.files_free());
+                let available_inodes = stat.files_available();
+                let is_network = is_network_fs(&fs_type);
+                let is_removable = !is_network && is_removable_device(&device);
                 
                 mounts.push(MountPoint {
                     device,
@@ -99,6 +294,12 @@ pub fn get_mount_points() -> Result<Vec<MountPoint>> {
                     total,
                     used,
                     available,
+                    total_inodes,
+                    used_inodes,
+                    available_inodes,
+                    is_network,
+                    is_removable,
+                    options,
                 });
             }
         }
@@ -109,66 +310,476 @@ pub fn get_mount_points() -> Result<Vec<MountPoint>> {
 
 /// Analyze folder sizes in a given directory
 /// 
-/// Scans immediate subdirectories and calculates their sizes.
+/// Scans immediate subdirectories and calculates their sizes. This blocks until
+/// the whole tree is walked; use `scan_folder` from a worker thread in the UI.
 pub fn analyze_folder(path: &Path, max_depth: usize) -> Result<Vec<FolderInfo>> {
-    let mut folders = Vec::new();
-    
+    scan_folder(path, max_depth, Arc::new(AtomicBool::new(false)), |_| {})
+}
+
+/// Analyze folder sizes in a given directory, with cancellation and progress
+/// 
+/// `cancel` is checked at every directory boundary; once it is set the scan
+/// stops and the folders sized so far are returned, the one in progress with a
+/// partial size. `progress` receives the running total of bytes scanned.
+/// 
+/// This still does blocking I/O. Views should spawn it on a worker thread,
+/// send the result back over a channel and poll that from the main loop, and
+/// set `cancel` when the view goes away or a new scan replaces this one.
+pub fn scan_folder(
+    path: &Path,
+    max_depth: usize,
+    cancel: Arc<AtomicBool>,
+    mut progress: impl FnMut(u64),
+) -> Result<Vec<FolderInfo>> {
     if !path.is_dir() {
-        return Ok(folders);
+        return Ok(Vec::new());
     }
     
-    if let Ok(entries) = fs::read_dir(path) {
-        for entry in entries.flatten() {
-            if let Ok(metadata) = entry.metadata() {
-                if metadata.is_dir() {
-                    let folder_path = entry.path();
-                    let (size, file_count, dir_count) = calculate_folder_size(&folder_path, 0, max_depth);
-                    
-                    folders.push(FolderInfo {
-                        path: folder_path,
-                        size,
-                        file_count,
-                        dir_count,
-                    });
+    let mut scan = FolderScan {
+        cancel: &cancel,
+        progress: &mut progress,
+        scanned: 0,
+        device: None,
+        visited: HashSet::new(),
+    };
+    Ok(scan.subfolders(path, max_depth))
+}
+
+/// Analyze the subfolders of a user-supplied directory
+/// 
+/// `path` may start with `~`, which is expanded to the home directory. Unlike
+/// `scan_folder` this rejects paths that are not directories and reports an
+/// unreadable root instead of returning nothing. With `stay_on_filesystem` set,
+/// directories on other mounts (e.g. `/proc` or a network share under `/`) are
+/// not descended into.
+pub fn analyze_folder_for_path(
+    path: &str,
+    max_depth: usize,
+    stay_on_filesystem: bool,
+    cancel: Arc<AtomicBool>,
+    mut progress: impl FnMut(u64),
+) -> Result<Vec<FolderInfo>> {
+    let path = expand_home(path.trim());
+    
+    let metadata = match fs::metadata(&path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            anyhow::bail!("{} does not exist", path.display());
+        }
+        Err(e) => anyhow::bail!("Cannot access {}: {}", path.display(), e),
+    };
+    if !metadata.is_dir() {
+        anyhow::bail!("{} is not a directory", path.display());
+    }
+    if let Err(e) = fs::read_dir(&path) {
+        if e.kind() == std::io::ErrorKind::PermissionDenied {
+            anyhow::bail!("Permission denied reading {}", path.display());
+        }
+        anyhow::bail!("Cannot read {}: {}", path.display(), e);
+    }
+    
+    let mut scan = FolderScan {
+        cancel: &cancel,
+        progress: &mut progress,
+        scanned: 0,
+        device: stay_on_filesystem.then(|| metadata.dev()),
+        visited: HashSet::new(),
+    };
+    Ok(scan.subfolders(&path, max_depth))
+}
+
+/// Find the largest individual files below a directory
+/// 
+/// Returns up to `limit` files of at least `min_size` bytes with their sizes,
+/// largest first. Like `analyze_folder` this blocks until the whole tree is
+/// walked; use `scan_largest_files` from a worker thread in the UI.
+pub fn get_largest_files(path: &Path, limit: usize, min_size: u64) -> Result<Vec<(PathBuf, u64)>> {
+    scan_largest_files(path, limit, min_size, Arc::new(AtomicBool::new(false)), |_| {})
+}
+
+/// Find the largest individual files below a directory, with cancellation and progress
+/// 
+/// Only the current `limit` largest files are kept while walking, so memory
+/// stays bounded however big the tree is. The walk stays on the filesystem
+/// of `path` and does not follow symlinks; extra hard links to a file already
+/// seen are skipped. `cancel` and `progress` work as in `scan_folder`; a
+/// cancelled scan returns the largest files found so far.
+pub fn scan_largest_files(
+    path: &Path,
+    limit: usize,
+    min_size: u64,
+    cancel: Arc<AtomicBool>,
+    mut progress: impl FnMut(u64),
+) -> Result<Vec<(PathBuf, u64)>> {
+    let metadata = fs::metadata(path)?;
+    fs::read_dir(path)?;
+    
+    let mut scan = FolderScan {
+        cancel: &cancel,
+        progress: &mut progress,
+        scanned: 0,
+        device: Some(metadata.dev()),
+        visited: HashSet::from([(metadata.dev(), metadata.ino())]),
+    };
+    let mut largest = BinaryHeap::new();
+    if limit > 0 {
+        scan.largest_files(path, limit, min_size, &mut largest);
+    }
+    
+    Ok(largest.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((size, path))| (path, size))
+        .collect())
+}
+
+/// Expand a leading `~` to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_default();
+    if path == "~" {
+        PathBuf::from(home)
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        PathBuf::from(home).join(rest)
+    } else {
+        PathBuf::from(path)
+    }
+}
+
+/// State shared across one recursive folder walk
+/// 
+/// Entries are inspected with `DirEntry::metadata`, which does not follow
+/// symlinks, so a symlinked directory is neither descended into nor counted.
+/// Bind mounts can still make a directory reachable twice or from inside
+/// itself, so directories are only entered on their first visit.
+struct FolderScan<'a> {
+    cancel: &'a AtomicBool,
+    progress: &'a mut dyn FnMut(u64),
+    scanned: u64,
+    /// When set, directories on other devices are skipped
+    device: Option<u64>,
+    /// Device and inode of every directory entered so far
+    visited: HashSet<(u64, u64)>,
+}
+
+impl FolderScan<'_> {
+    fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+    
+    /// Whether a directory lies on a different filesystem than the one we stay on
+    fn crosses_mount(&self, metadata: &fs::Metadata) -> bool {
+        self.device.is_some_and(|device| metadata.dev() != device)
+    }
+    
+    /// Whether to descend into a directory: on our filesystem and not seen yet
+    fn should_enter(&mut self, metadata: &fs::Metadata) -> bool {
+        !self.crosses_mount(metadata) && self.visited.insert((metadata.dev(), metadata.ino()))
+    }
+    
+    /// Size each immediate subfolder of `path`, largest first
+    fn subfolders(&mut self, path: &Path, max_depth: usize) -> Vec<FolderInfo> {
+        let mut folders = Vec::new();
+        
+        if let Ok(metadata) = fs::metadata(path) {
+            self.visited.insert((metadata.dev(), metadata.ino()));
+        }
+        
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                if self.is_cancelled() {
+                    break;
+                }
+                
+                if let Ok(metadata) = entry.metadata() {
+                    if metadata.is_dir() && self.should_enter(&metadata) {
+                        let folder_path = entry.path();
+                        let totals = self.folder_size(&folder_path, 0, max_depth);
+                        folders.push(FolderInfo::new(folder_path, totals));
+                    }
                 }
             }
         }
+        
+        // Sort by size (largest first)
+        folders.sort_by_key(|f| std::cmp::Reverse(f.size));
+        
+        folders
     }
     
-    // Sort by size (largest first)
-    folders.sort_by(|a, b| b.size.cmp(&a.size));
+    /// Collect the `limit` largest files below `path` into a min-heap
+    fn largest_files(
+        &mut self,
+        path: &Path,
+        limit: usize,
+        min_size: u64,
+        largest: &mut BinaryHeap<Reverse<(u64, PathBuf)>>,
+    ) {
+        if self.is_cancelled() {
+            return;
+        }
+        
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                if let Ok(metadata) = entry.metadata() {
+                    if metadata.is_file() {
+                        self.scanned += metadata.len();
+                        if metadata.len() < min_size
+                            || (metadata.nlink() > 1 && !self.visited.insert((metadata.dev(), metadata.ino())))
+                        {
+                            continue;
+                        }
+                        
+                        largest.push(Reverse((metadata.len(), entry.path())));
+                        if largest.len() > limit {
+                            largest.pop();
+                        }
+                    } else if metadata.is_dir() && self.should_enter(&metadata) {
+                        self.largest_files(&entry.path(), limit, min_size, largest);
+                    }
+                }
+            }
+        }
+        
+        (self.progress)(self.scanned);
+    }
     
-    Ok(folders)
+    /// Calculate the total size of a folder recursively
+    fn folder_size(&mut self, path: &Path, current_depth: usize, max_depth: usize) -> FolderTotals {
+        let mut totals = FolderTotals::default();
+        
+        if current_depth > max_depth || self.is_cancelled() {
+            return totals;
+        }
+        
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                if let Ok(metadata) = entry.metadata() {
+                    if metadata.is_file() {
+                        totals.size += metadata.len();
+                        totals.size_on_disk += allocated_size(&metadata);
+                        totals.file_count += 1;
+                        self.scanned += metadata.len();
+                    } else if metadata.is_dir() && self.should_enter(&metadata) {
+                        totals.dir_count += 1;
+                        totals += self.folder_size(&entry.path(), current_depth + 1, max_depth);
+                    }
+                }
+            }
+        }
+        
+        (self.progress)(self.scanned);
+        totals
+    }
 }
 
 /// Calculate the total size of a folder recursively
-fn calculate_folder_size(path: &Path, current_depth: usize, max_depth: usize) -> (u64, usize, usize) {
-    let mut total_size = 0u64;
-    let mut file_count = 0usize;
-    let mut dir_count = 0usize;
-    
-    if current_depth > max_depth {
-        return (total_size, file_count, dir_count);
+fn calculate_folder_size(path: &Path, current_depth: usize, max_depth: usize) -> FolderTotals {
+    let cancel = AtomicBool::new(false);
+    FolderScan {
+        cancel: &cancel,
+        progress: &mut |_| {},
+        scanned: 0,
+        device: None,
+        visited: HashSet::new(),
     }
+    .folder_size(path, current_depth, max_depth)
+}
+
+/// A node in a nested directory size tree
+#[derive(Debug, Clone)]
+pub struct DirNode {
+    pub path: PathBuf,
+    /// Total size in bytes, including children that were dropped by the cap
+    pub size: u64,
+    /// Largest entries first; files are leaves with no children
+    pub children: Vec<DirNode>,
+}
+
+/// Maximum number of children kept per node in `build_tree`
+pub const MAX_TREE_CHILDREN: usize = 50;
+
+/// Build a nested size tree rooted at `path`, suitable for a treemap
+/// 
+/// The hierarchy is kept down to `max_depth` levels; below that directories are
+/// still sized in full but have no children. Each node keeps at most
+/// `MAX_TREE_CHILDREN` of its largest entries. Symlinks are not followed and
+/// a directory reachable twice (through a bind mount) is only counted once.
+pub fn build_tree(path: &Path, max_depth: usize) -> Result<DirNode> {
+    // Fail early if the root itself can't be read
+    fs::read_dir(path)?;
+    Ok(build_node(path, 0, max_depth, &mut HashSet::new()))
+}
+
+/// Build one tree node, skipping symlinks and directories already in `visited`
+fn build_node(path: &Path, current_depth: usize, max_depth: usize, visited: &mut HashSet<(u64, u64)>) -> DirNode {
+    if current_depth >= max_depth {
+        let size = calculate_folder_size(path, 0, usize::MAX).size;
+        return DirNode { path: path.to_path_buf(), size, children: Vec::new() };
+    }
+    
+    let mut size = 0u64;
+    let mut children = Vec::new();
     
     if let Ok(entries) = fs::read_dir(path) {
         for entry in entries.flatten() {
             if let Ok(metadata) = entry.metadata() {
                 if metadata.is_file() {
-                    total_size += metadata.len();
-                    file_count += 1;
-                } else if metadata.is_dir() {
-                    dir_count += 1;
-                    let (sub_size, sub_files, sub_dirs) = calculate_folder_size(&entry.path(), current_depth + 1, max_depth);
-                    total_size += sub_size;
-                    file_count += sub_files;
-                    dir_count += sub_dirs;
+                    size += metadata.len();
+                    children.push(DirNode {
+                        path: entry.path(),
+                        size: metadata.len(),
+                        children: Vec::new(),
+                    });
+                } else if metadata.is_dir() && visited.insert((metadata.dev(), metadata.ino())) {
+                    let child = build_node(&entry.path(), current_depth + 1, max_depth, visited);
+                    size += child.size;
+                    children.push(child);
+                }
+            }
+        }
+    }
+    
+    children.sort_by_key(|c| std::cmp::Reverse(c.size));
+    children.truncate(MAX_TREE_CHILDREN);
+    
+    DirNode { path: path.to_path_buf(), size, children }
+}
+
+/// Extensions below this share of the total size are merged into "other"
+const EXTENSION_OTHER_THRESHOLD: f64 = 0.01;
+
+/// Break down a directory's contents by file extension
+/// 
+/// Returns `(extension, total bytes, file count)` sorted by size, largest first.
+/// Extensions are lowercased; files without one are grouped as "no extension".
+/// Extensions holding less than 1% of the total are merged into a final "other"
+/// entry to keep the list short.
+pub fn analyze_by_extension(path: &Path) -> Result<Vec<(String, u64, usize)>> {
+    fn walk(path: &Path, visited: &mut HashSet<(u64, u64)>, totals: &mut HashMap<String, (u64, usize)>) {
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                if let Ok(metadata) = entry.metadata() {
+                    if metadata.is_file() {
+                        let extension = entry.path()
+                            .extension()
+                            .map(|e| e.to_string_lossy().to_lowercase())
+                            .unwrap_or_else(|| "no extension".to_string());
+                        let total = totals.entry(extension).or_default();
+                        total.0 += metadata.len();
+                        total.1 += 1;
+                    } else if metadata.is_dir() && visited.insert((metadata.dev(), metadata.ino())) {
+                        walk(&entry.path(), visited, totals);
+                    }
                 }
             }
         }
     }
     
-    (total_size, file_count, dir_count)
+    fs::read_dir(path)?;
+    let mut totals = HashMap::new();
+    walk(path, &mut HashSet::new(), &mut totals);
+    
+    let total_size: u64 = totals.values().map(|(size, _)| size).sum();
+    let threshold = (total_size as f64 * EXTENSION_OTHER_THRESHOLD) as u64;
+    
+    let mut breakdown = Vec::new();
+    let mut other = (0u64, 0usize);
+    for (extension, (size, count)) in totals {
+        if size < threshold {
+            other.0 += size;
+            other.1 += count;
+        } else {
+            breakdown.push((extension, size, count));
+        }
+    }
+    
+    breakdown.sort_by_key(|(_, size, _)| std::cmp::Reverse(*size));
+    if other.1 > 0 {
+        breakdown.push(("other".to_string(), other.0, other.1));
+    }
+    
+    Ok(breakdown)
+}
+
+/// Files passed to a single sha256sum invocation
+const HASH_BATCH_SIZE: usize = 256;
+
+/// Find groups of files with identical content under `path`
+/// 
+/// Files are first grouped by size; only files sharing a size are hashed, so
+/// most of the tree is never read. Files smaller than `min_size`, symlinks and
+/// extra hard links to an already seen inode are skipped. Groups are sorted by
+/// wasted space (size times extra copies), largest first.
+pub fn find_duplicates(path: &Path, min_size: u64) -> Result<Vec<Vec<PathBuf>>> {
+    fn walk(
+        path: &Path,
+        min_size: u64,
+        seen: &mut HashSet<(u64, u64)>,
+        by_size: &mut HashMap<u64, Vec<PathBuf>>,
+    ) {
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                let Ok(metadata) = entry.path().symlink_metadata() else {
+                    continue;
+                };
+                if metadata.is_dir() {
+                    if seen.insert((metadata.dev(), metadata.ino())) {
+                        walk(&entry.path(), min_size, seen, by_size);
+                    }
+                } else if metadata.is_file()
+                    && metadata.len() >= min_size
+                    && seen.insert((metadata.dev(), metadata.ino()))
+                {
+                    by_size.entry(metadata.len()).or_default().push(entry.path());
+                }
+            }
+        }
+    }
+    
+    fs::read_dir(path)?;
+    let mut by_size = HashMap::new();
+    walk(path, min_size, &mut HashSet::new(), &mut by_size);
+    
+    let mut groups = Vec::new();
+    for (size, paths) in by_size {
+        if paths.len() < 2 {
+            continue;
+        }
+        
+        let mut by_hash: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for chunk in paths.chunks(HASH_BATCH_SIZE) {
+            for (hash, file) in hash_files(chunk)? {
+                by_hash.entry(hash).or_default().push(file);
+            }
+        }
+        
+        groups.extend(by_hash.into_values()
+            .filter(|files| files.len() > 1)
+            .map(|files| (size, files)));
+    }
+    
+    groups.sort_by_key(|(size, files)| std::cmp::Reverse(size * (files.len() as u64 - 1)));
+    
+    Ok(groups.into_iter().map(|(_, files)| files).collect())
+}
+
+/// Hash files with sha256sum, returning `(hash, path)` pairs
+/// 
+/// Unreadable files are left out. Names sha256sum has to escape (newlines or
+/// backslashes) are skipped too, as its output can't be mapped back reliably.
+fn hash_files(files: &[PathBuf]) -> Result<Vec<(String, PathBuf)>> {
+    let output = Command::new("sha256sum")
+        .arg("--")
+        .args(files)
+        .output()?;
+    
+    Ok(output.stdout
+        .split(|&b| b == b'\n')
+        .filter(|line| line.len() > 66 && line[0] != b'\\' && &line[64..66] == b"  ")
+        .map(|line| (
+            String::from_utf8_lossy(&line[..64]).to_string(),
+            PathBuf::from(OsStr::from_bytes(&line[66..])),
+        ))
+        .collect())
 }
 
 /// Get common large directories in home folder
@@ -190,18 +801,13 @@ pub fn get_common_large_folders() -> Result<Vec<FolderInfo>> {
     for path_str in common_paths {
         let path = PathBuf::from(&path_str);
         if path.exists() && path.is_dir() {
-            let (size, file_count, dir_count) = calculate_folder_size(&path, 0, 3);
-            folders.push(FolderInfo {
-                path,
-                size,
-                file_count,
-                dir_count,
-            });
+            let totals = calculate_folder_size(&path, 0, 3);
+            folders.push(FolderInfo::new(path, totals));
         }
     }
     
     // Sort by size (largest first)
-    folders.sort_by(|a, b| b.size.cmp(&a.size));
+    folders.sort_by_key(|f| std::cmp::Reverse(f.size));
     
     Ok(folders)
 }
@@ -217,3 +823,264 @@ pub fn get_cleanup_suggestions() -> Vec<String> {
         "Remove unused Flatpak runtimes: flatpak uninstall --unused".to_string(),
     ]
 }
+
+
+/// Overall SMART assessment of a disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthStatus {
+    Passed,
+    Failed,
+    /// smartctl couldn't read the disk, or it doesn't support SMART
+    Unknown,
+}
+
+/// SMART health of one physical disk
+#[derive(Debug, Clone)]
+pub struct DiskHealth {
+    /// Name in /sys/block, e.g. `sda` or `nvme0n1`
+    pub device: String,
+    pub model: String,
+    pub status: HealthStatus,
+    /// Celsius
+    pub temperature: Option<u32>,
+    pub power_on_hours: Option<u64>,
+    pub reallocated_sectors: Option<u64>,
+}
+
+/// Places smartctl is installed to, which may not be on a user's PATH
+const SMARTCTL_PATHS: [&str; 2] = ["/usr/sbin/smartctl", "/sbin/smartctl"];
+
+/// Check whether smartctl (from smartmontools) is installed
+pub fn smartctl_available() -> bool {
+    let is_executable = |path: &Path| fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false);
+    
+    SMARTCTL_PATHS.iter().any(|path| is_executable(Path::new(path)))
+        || std::env::var_os("PATH")
+            .map(|paths| std::env::split_paths(&paths).any(|dir| is_executable(&dir.join("smartctl"))))
+            .unwrap_or(false)
+}
+
+/// Check whether a /sys/block entry is a physical disk
+/// 
+/// Virtual devices have no `device` link; loop, RAM, mapper, RAID, optical
+/// and floppy devices are skipped by name as well.
+fn is_physical_disk(name: &str) -> bool {
+    const VIRTUAL: [&str; 7] = ["loop", "ram", "zram", "dm-", "md", "sr", "fd"];
+    
+    !VIRTUAL.iter().any(|prefix| name.starts_with(prefix))
+        && Path::new("/sys/block").join(name).join("device").exists()
+}
+
+/// Get the SMART health of each physical disk
+/// 
+/// Runs `smartctl -a` through the privileged helper, once per disk in
+/// /sys/block. Returns an empty list when smartmontools isn't installed; check
+/// `smartctl_available` to tell that apart from a machine without disks.
+/// Disks smartctl can't read are listed with `HealthStatus::Unknown`, as are
+/// the remaining disks once authentication is dismissed.
+pub fn get_disk_health() -> Vec<DiskHealth> {
+    if !smartctl_available() {
+        return Vec::new();
+    }
+    
+    let mut devices: Vec<String> = fs::read_dir("/sys/block")
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| is_physical_disk(name))
+                .collect()
+        })
+        .unwrap_or_default();
+    devices.sort();
+    
+    let mut dismissed = false;
+    devices
+        .into_iter()
+        .map(|device| {
+            let output = if dismissed {
+                None
+            } else {
+                match privileged::run_command_output(&HelperCommand::SmartHealth(device.clone())) {
+                    Ok(output) => Some(output),
+                    Err(e) => {
+                        dismissed = e.is::<AuthenticationDismissed>();
+                        None
+                    }
+                }
+            };
+            
+            match output {
+                Some(output) => parse_smartctl(&device, &output),
+                None => DiskHealth {
+                    model: sysfs_model(&device),
+                    device,
+                    status: HealthStatus::Unknown,
+                    temperature: None,
+                    power_on_hours: None,
+                    reallocated_sectors: None,
+                },
+            }
+        })
+        .collect()
+}
+
+/// Read a disk's model from sysfs, for disks smartctl couldn't read
+fn sysfs_model(device: &str) -> String {
+    fs::read_to_string(Path::new("/sys/block").join(device).join("device/model"))
+        .map(|model| model.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Parse `smartctl -a` for ATA, NVMe and SCSI disks
+/// 
+/// ATA disks report temperature, power-on hours and reallocated sectors as
+/// raw values in the attribute table; NVMe and SCSI disks as `key: value`
+/// lines.
+fn parse_smartctl(device: &str, output: &str) -> DiskHealth {
+    let mut health = DiskHealth {
+        device: device.to_string(),
+        model: String::new(),
+        status: HealthStatus::Unknown,
+        temperature: None,
+        power_on_hours: None,
+        reallocated_sectors: None,
+    };
+    
+    for line in output.lines() {
+        // SCSI: "Accumulated power on time, hours:minutes 1234:56"
+        if let Some(time) = line.trim().strip_prefix("Accumulated power on time, hours:minutes") {
+            health.power_on_hours = leading_number(time.trim());
+            continue;
+        }
+        
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim();
+            match key.trim() {
+                "Device Model" | "Model Number" | "Product" if health.model.is_empty() => {
+                    health.model = value.to_string();
+                }
+                "SMART overall-health self-assessment test result" => {
+                    health.status = match value {
+                        "PASSED" => HealthStatus::Passed,
+                        _ => HealthStatus::Failed,
+                    };
+                }
+                "SMART Health Status" => {
+                    health.status = match value {
+                        "OK" => HealthStatus::Passed,
+                        _ => HealthStatus::Failed,
+                    };
+                }
+                "Temperature" | "Current Drive Temperature" => {
+                    health.temperature = health.temperature.or_else(|| leading_number(value).map(|t| t as u32));
+                }
+                "Power On Hours" => health.power_on_hours = leading_number(value),
+                _ => {}
+            }
+            continue;
+        }
+        
+        // ID# ATTRIBUTE_NAME FLAG VALUE WORST THRESH TYPE UPDATED WHEN_FAILED RAW_VALUE
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+        let Ok(id) = fields[0].parse::<u32>() else {
+            continue;
+        };
+        let raw = leading_number(fields[9]);
+        match id {
+            5 => health.reallocated_sectors = raw,
+            9 => health.power_on_hours = raw,
+            194 => health.temperature = raw.map(|t| t as u32),
+            190 if health.temperature.is_none() => health.temperature = raw.map(|t| t as u32),
+            _ => {}
+        }
+    }
+    
+    health
+}
+
+/// Parse the digits a value starts with, ignoring thousands separators
+/// 
+/// Handles values like `1,234`, `36 Celsius` and `12345h+07m+10s`.
+fn leading_number(value: &str) -> Option<u64> {
+    let digits: String = value
+        .chars()
+        .filter(|c| *c != ',')
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// From an Ubuntu desktop with snaps, a bind mount and a USB stick with a space in its label
+    const PROC_MOUNTS: &str = "\
+sysfs /sys sysfs rw,nosuid,nodev,noexec,relatime 0 0
+proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0
+udev /dev devtmpfs rw,nosuid,relatime,size=8028916k,nr_inodes=2007229,mode=755,inode64 0 0
+tmpfs /run tmpfs rw,nosuid,nodev,noexec,relatime,size=1612824k,mode=755,inode64 0 0
+/dev/nvme0n1p2 / ext4 rw,relatime,errors=remount-ro 0 0
+/dev/loop0 /snap/core22/1122 squashfs ro,nodev,relatime,errors=continue 0 0
+/dev/loop1 /snap/firefox/3836 squashfs ro,nodev,relatime,errors=continue 0 0
+/dev/nvme0n1p1 /boot/efi vfat rw,relatime,fmask=0077,dmask=0077 0 0
+/dev/nvme0n1p2 /var/snap/firefox/common/host-hunspell ext4 ro,noexec,noatime,errors=remount-ro 0 0
+/dev/nvme0n1p2 /var/lib/docker ext4 rw,relatime,errors=remount-ro 0 0
+gvfsd-fuse /run/user/1000/gvfs fuse.gvfsd-fuse rw,nosuid,nodev,relatime,user_id=1000,group_id=1000 0 0
+/dev/sda1 /media/alice/My\\040USB\\040Stick vfat rw,nosuid,nodev,relatime,uid=1000 0 0
+";
+    
+    #[test]
+    fn parse_mounts_deduplicates_and_filters() {
+        let entries = parse_mounts(PROC_MOUNTS, false);
+        let mounts: Vec<(&str, &Path)> = entries.iter()
+            .map(|e| (e.device.as_str(), e.mount_point.as_path()))
+            .collect();
+        
+        // `/` is listed once although its device is mounted three times, snap
+        // loop mounts and virtual filesystems are gone, and the escaped
+        // spaces are decoded
+        assert_eq!(mounts, [
+            ("/dev/nvme0n1p2", Path::new("/")),
+            ("/dev/nvme0n1p1", Path::new("/boot/efi")),
+            ("/dev/sda1", Path::new("/media/alice/My USB Stick")),
+        ]);
+        assert_eq!(entries[0].options, ["rw", "relatime", "errors=remount-ro"]);
+    }
+    
+    #[test]
+    fn parse_mounts_keeps_the_shortest_path_whatever_the_order() {
+        let contents = "\
+/dev/sdb1 /mnt/data/backup ext4 rw 0 0
+/dev/sdb1 /srv/x ext4 rw 0 0
+/dev/sdb1 /mnt/data ext4 rw 0 0
+";
+        let entries = parse_mounts(contents, false);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].mount_point, Path::new("/srv/x"));
+    }
+    
+    #[test]
+    fn parse_mounts_includes_snaps_when_asked() {
+        let entries = parse_mounts(PROC_MOUNTS, true);
+        let snaps: Vec<&Path> = entries.iter()
+            .filter(|e| e.fs_type == "squashfs")
+            .map(|e| e.mount_point.as_path())
+            .collect();
+        assert_eq!(snaps, [Path::new("/snap/core22/1122"), Path::new("/snap/firefox/3836")]);
+    }
+    
+    #[test]
+    fn unescapes_mount_fields() {
+        assert_eq!(unescape_mount_field(r"/a\040b\011c\012d\134e"), b"/a b\tc\nd\\e");
+        assert_eq!(unescape_mount_field(r"/plain"), b"/plain");
+        assert_eq!(unescape_mount_field(r"/bad\09x\4"), br"/bad\09x\4");
+        assert_eq!(unescape_mount_field(r"\+12"), br"\+12");
+    }
+}
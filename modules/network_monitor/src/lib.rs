@@ -0,0 +1,206 @@
+//! Network Monitor Module
+//! 
+//! Lists the sockets listening on this machine and the processes that own them.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// TCP state of a listening socket in /proc/net/tcp
+const TCP_LISTEN: &str = "0A";
+/// State of an unconnected UDP socket, i.e. one waiting for datagrams
+const UDP_UNCONNECTED: &str = "07";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl Protocol {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "TCP",
+            Protocol::Udp => "UDP",
+        }
+    }
+}
+
+/// A socket accepting connections or datagrams
+#[derive(Debug, Clone, PartialEq)]
+pub struct SocketInfo {
+    pub proto: Protocol,
+    /// IPv4-mapped IPv6 addresses are shown as IPv4
+    pub local_addr: IpAddr,
+    pub port: u16,
+    /// `None` when the owner can't be seen, usually a process of another user
+    pub pid: Option<u32>,
+    /// Command name of the owning process
+    pub process: Option<String>,
+}
+
+impl SocketInfo {
+    /// Address and port, e.g. `127.0.0.1:631` or `[::]:22`
+    pub fn address(&self) -> String {
+        SocketAddr::new(self.local_addr, self.port).to_string()
+    }
+    
+    /// Whether the socket only accepts connections from this machine
+    pub fn is_local_only(&self) -> bool {
+        self.local_addr.is_loopback()
+    }
+}
+
+/// A socket line from /proc/net, before its owner is looked up
+#[derive(Debug, Clone, PartialEq)]
+struct RawSocket {
+    local_addr: IpAddr,
+    port: u16,
+    state: String,
+    inode: u64,
+}
+
+/// List the TCP and UDP sockets listening on this machine, IPv4 and IPv6
+/// 
+/// TCP sockets in the LISTEN state and unconnected UDP sockets are included.
+/// Owners are found through the socket links in `/proc/<pid>/fd`, which are
+/// only readable for our own processes unless running as root. Sorted by port,
+/// then protocol and address.
+pub fn get_listening_sockets() -> Result<Vec<SocketInfo>> {
+    let tables = [
+        ("/proc/net/tcp", Protocol::Tcp, TCP_LISTEN),
+        ("/proc/net/tcp6", Protocol::Tcp, TCP_LISTEN),
+        ("/proc/net/udp", Protocol::Udp, UDP_UNCONNECTED),
+        ("/proc/net/udp6", Protocol::Udp, UDP_UNCONNECTED),
+    ];
+    
+    let mut listening = Vec::new();
+    for (path, proto, state) in tables {
+        // The IPv6 tables are missing when IPv6 is disabled
+        let Ok(contents) = fs::read_to_string(path) else {
+            continue;
+        };
+        listening.extend(
+            parse_socket_table(&contents)
+                .into_iter()
+                .filter(|socket| socket.state == state)
+                .map(|socket| (proto, socket)),
+        );
+    }
+    
+    if listening.is_empty() && fs::metadata("/proc/net/tcp").is_err() {
+        anyhow::bail!("/proc/net/tcp is not available");
+    }
+    
+    let owners = socket_owners().context("Failed to read /proc")?;
+    let mut sockets: Vec<SocketInfo> = listening
+        .into_iter()
+        .map(|(proto, socket)| {
+            let pid = owners.get(&socket.inode).copied();
+            SocketInfo {
+                proto,
+                local_addr: socket.local_addr,
+                port: socket.port,
+                pid,
+                process: pid.and_then(process_name),
+            }
+        })
+        .collect();
+    
+    sockets.sort_by(|a, b| {
+        a.port.cmp(&b.port)
+            .then(a.proto.cmp(&b.proto))
+            .then(a.local_addr.cmp(&b.local_addr))
+    });
+    sockets.dedup();
+    Ok(sockets)
+}
+
+/// Parse one of /proc/net/{tcp,tcp6,udp,udp6}
+/// 
+/// After a header line, each line is
+/// `sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode ...`
+/// with addresses as `ADDR:PORT` in hex. Malformed lines are skipped.
+fn parse_socket_table(contents: &str) -> Vec<RawSocket> {
+    contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                return None;
+            }
+            
+            let (addr, port) = fields[1].split_once(':')?;
+            Some(RawSocket {
+                local_addr: parse_hex_addr(addr)?,
+                port: u16::from_str_radix(port, 16).ok()?,
+                state: fields[3].to_string(),
+                inode: fields[9].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Parse an address as the kernel prints it in /proc/net
+/// 
+/// The address bytes are printed as 32-bit words in host byte order: one word
+/// for IPv4, four for IPv6.
+fn parse_hex_addr(hex: &str) -> Option<IpAddr> {
+    let words: Option<Vec<[u8; 4]>> = (0..hex.len())
+        .step_by(8)
+        .map(|start| {
+            let word = hex.get(start..start + 8)?;
+            u32::from_str_radix(word, 16).ok().map(u32::to_ne_bytes)
+        })
+        .collect();
+    let bytes: Vec<u8> = words?.concat();
+    
+    match bytes.len() {
+        4 => Some(IpAddr::V4(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]))),
+        16 => {
+            let octets: [u8; 16] = bytes.try_into().ok()?;
+            Some(IpAddr::V6(Ipv6Addr::from(octets)).to_canonical())
+        }
+        _ => None,
+    }
+}
+
+/// Map socket inodes to the PID of a process holding them
+/// 
+/// Processes whose fd directory can't be read are skipped.
+fn socket_owners() -> Result<HashMap<u64, u32>> {
+    let mut owners = HashMap::new();
+    
+    for entry in fs::read_dir("/proc")?.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        
+        for fd in fds.flatten() {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let inode = target.to_str()
+                .and_then(|t| t.strip_prefix("socket:["))
+                .and_then(|t| t.strip_suffix(']'))
+                .and_then(|t| t.parse::<u64>().ok());
+            if let Some(inode) = inode {
+                owners.entry(inode).or_insert(pid);
+            }
+        }
+    }
+    
+    Ok(owners)
+}
+
+/// Command name of a process, from /proc/<pid>/comm
+fn process_name(pid: u32) -> Option<String> {
+    fs::read_to_string(format!("/proc/{}/comm", pid))
+        .ok()
+        .map(|name| name.trim().to_string())
+}
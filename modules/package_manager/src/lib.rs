@@ -2,14 +2,25 @@
 //! 
 //! Provides information about installed packages across multiple package managers.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use privileged::{AptAction, AptMarkAction, HelperCommand};
+
+pub use privileged::{AuthenticationDismissed, Outcome};
 
 #[derive(Debug, Clone)]
 pub struct PackageStats {
     pub total_installed: usize,
     pub upgradeable: usize,
     pub auto_removable: usize,
+    pub held: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -19,19 +30,97 @@ pub struct PackageInfo {
     pub description: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct UpgradablePackage {
+    pub name: String,
+    pub current_version: String,
+    pub available_version: String,
+}
+
+/// Package management backend available on this system
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageBackend {
+    Apt,
+    Pacman,
+    Dnf,
+}
+
+impl PackageBackend {
+    pub fn name(&self) -> &str {
+        match self {
+            PackageBackend::Apt => "APT (Debian/Ubuntu)",
+            PackageBackend::Pacman => "Pacman (Arch)",
+            PackageBackend::Dnf => "DNF (Fedora/RHEL)",
+        }
+    }
+}
+
+static DETECTED_BACKEND: OnceLock<PackageBackend> = OnceLock::new();
+
+/// Detect the system package backend
+/// 
+/// Probes $PATH for the package manager binaries once and caches the result.
+/// Falls back to APT when nothing recognizable is found.
+pub fn detect_backend() -> PackageBackend {
+    *DETECTED_BACKEND.get_or_init(|| {
+        if binary_in_path("dpkg-query") && binary_in_path("apt-get") {
+            PackageBackend::Apt
+        } else if binary_in_path("pacman") {
+            PackageBackend::Pacman
+        } else if binary_in_path("dnf") {
+            PackageBackend::Dnf
+        } else {
+            PackageBackend::Apt
+        }
+    })
+}
+
+/// Check whether an executable with the given name exists on $PATH
+fn binary_in_path(binary: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(binary).is_file()))
+        .unwrap_or(false)
+}
+
+/// Get package statistics
+/// 
+/// Dispatches to the detected backend for installed, upgradeable, and auto-removable packages.
+pub fn get_package_stats() -> Result<PackageStats> {
+    match detect_backend() {
+        PackageBackend::Apt => get_apt_package_stats(),
+        PackageBackend::Pacman => get_pacman_package_stats(),
+        PackageBackend::Dnf => get_dnf_package_stats(),
+    }
+}
+
+/// Count non-empty lines of a command's stdout, or 0 if it could not be run
+fn count_output_lines(program: &str, args: &[&str]) -> usize {
+    Command::new(program)
+        .args(args)
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .count()
+        })
+        .unwrap_or(0)
+}
+
 /// Get package statistics from APT
 /// 
 /// Queries dpkg and apt for installed, upgradeable, and auto-removable packages.
-pub fn get_package_stats() -> Result<PackageStats> {
+fn get_apt_package_stats() -> Result<PackageStats> {
     let mut stats = PackageStats {
         total_installed: 0,
         upgradeable: 0,
         auto_removable: 0,
+        held: 0,
     };
     
     // Count installed packages
     if let Ok(output) = Command::new("dpkg-query")
-        .args(&["-l"])
+        .args(["-l"])
         .output()
     {
         stats.total_installed = String::from_utf8_lossy(&output.stdout)
@@ -42,7 +131,7 @@ pub fn get_package_stats() -> Result<PackageStats> {
     
     // Count upgradeable packages
     if let Ok(output) = Command::new("apt")
-        .args(&["list", "--upgradable"])
+        .args(["list", "--upgradable"])
         .output()
     {
         stats.upgradeable = String::from_utf8_lossy(&output.stdout)
@@ -53,7 +142,7 @@ pub fn get_package_stats() -> Result<PackageStats> {
     
     // Count auto-removable packages
     if let Ok(output) = Command::new("apt")
-        .args(&["autoremove", "--dry-run"])
+        .args(["autoremove", "--dry-run"])
         .output()
     {
         let output_str = String::from_utf8_lossy(&output.stdout);
@@ -68,180 +157,1829 @@ pub fn get_package_stats() -> Result<PackageStats> {
         }
     }
     
+    // Count held packages
+    stats.held = count_output_lines("apt-mark", &["showhold"]);
+    
     Ok(stats)
 }
 
-/// List recently installed or upgraded packages
+/// Get package statistics from Pacman
 /// 
-/// Parses /var/log/apt/history.log for recent package activity.
-pub fn list_recent_packages(limit: usize) -> Result<Vec<PackageInfo>> {
-    let mut packages = Vec::new();
+/// Uses `checkupdates` (pacman-contrib) for upgrades when available, since it
+/// works on a temporary copy of the sync database; otherwise falls back to `pacman -Qu`.
+fn get_pacman_package_stats() -> Result<PackageStats> {
+    let upgradeable = if binary_in_path("checkupdates") {
+        count_output_lines("checkupdates", &[])
+    } else {
+        count_output_lines("pacman", &["-Qu"])
+    };
     
-    // Get recently installed/upgraded packages from apt history
-    if let Ok(output) = Command::new("grep")
-        .args(&["-E", "^(Install|Upgrade):", "/var/log/apt/history.log"])
+    Ok(PackageStats {
+        total_installed: count_output_lines("pacman", &["-Q"]),
+        upgradeable,
+        // Orphaned dependencies
+        auto_removable: count_output_lines("pacman", &["-Qdtq"]),
+        held: 0,
+    })
+}
+
+/// Get package statistics from DNF
+fn get_dnf_package_stats() -> Result<PackageStats> {
+    // `dnf check-update` also prints blank lines and obsoletes sections
+    let upgradeable = Command::new("dnf")
+        .args(["check-update", "-q"])
         .output()
-    {
-        let output_str = String::from_utf8_lossy(&output.stdout);
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .take_while(|line| !line.starts_with("Obsoleting"))
+                .filter(|line| line.split_whitespace().count() == 3)
+                .count()
+        })
+        .unwrap_or(0);
+    
+    Ok(PackageStats {
+        total_installed: count_output_lines("rpm", &["-qa"]),
+        upgradeable,
+        auto_removable: count_output_lines("dnf", &["repoquery", "--unneeded", "-q"]),
+        held: 0,
+    })
+}
+
+/// List packages that were removed but left their configuration files behind
+/// 
+/// These show up in `dpkg -l` with the `rc` state and serve no purpose until the
+/// package is installed again.
+pub fn list_residual_config_packages() -> Result<Vec<PackageInfo>> {
+    let output = Command::new("dpkg-query")
+        .args(["-l"])
+        .output()
+        .context("Failed to run dpkg-query")?;
+    
+    Ok(parse_residual_configs(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse the `rc` lines of `dpkg -l` output
+/// 
+/// Lines are `rc  name  version  architecture  description`.
+fn parse_residual_configs(output: &str) -> Vec<PackageInfo> {
+    output.lines()
+        .filter(|line| line.starts_with("rc "))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(1);
+            let name = fields.next()?.to_string();
+            let version = fields.next().unwrap_or("").to_string();
+            let _architecture = fields.next();
+            let description = fields.collect::<Vec<_>>().join(" ");
+            Some(PackageInfo { name, version, description })
+        })
+        .collect()
+}
+
+/// Purge the configuration files of all removed packages (requires sudo)
+/// 
+/// With `dry_run` nothing is purged; the packages that would be are returned
+/// as apt-style `Purg` lines.
+pub fn purge_residual_configs(dry_run: bool) -> Result<Outcome<()>> {
+    let packages = list_residual_config_packages()?;
+    if dry_run {
+        return Ok(Outcome::Simulated(
+            packages.iter().map(|p| format!("Purg {} [{}]", p.name, p.version)).collect()
+        ));
+    }
+    if packages.is_empty() {
+        return Ok(Outcome::Done(()));
+    }
+    
+    let names = packages.into_iter().map(|p| p.name).collect();
+    privileged::run_commands(&[HelperCommand::DpkgPurge(names)])?;
+    Ok(Outcome::Done(()))
+}
+
+/// One repository entry from the APT sources
+#[derive(Debug, Clone, PartialEq)]
+pub struct AptSource {
+    pub uri: String,
+    pub suite: String,
+    pub components: Vec<String>,
+    /// False for commented-out lines and `Enabled: no` stanzas
+    pub enabled: bool,
+    /// File the entry was read from
+    pub file: PathBuf,
+}
+
+impl AptSource {
+    /// URL of the suite's Release file, which every working repository serves
+    /// 
+    /// Flat repositories have a suite ending in `/` and keep the file directly
+    /// under it instead of in `dists/`.
+    pub fn release_url(&self) -> String {
+        let uri = self.uri.trim_end_matches('/');
+        if self.suite.ends_with('/') {
+            format!("{}/{}Release", uri, self.suite.trim_start_matches('/'))
+        } else {
+            format!("{}/dists/{}/Release", uri, self.suite)
+        }
+    }
+}
+
+/// List the binary (`deb`) repositories APT is configured with
+/// 
+/// Reads `/etc/apt/sources.list` and the `.list` and deb822 `.sources` files in
+/// `/etc/apt/sources.list.d`. Source-package (`deb-src`) entries are left out.
+pub fn list_apt_sources() -> Result<Vec<AptSource>> {
+    let mut files = vec![PathBuf::from("/etc/apt/sources.list")];
+    if let Ok(entries) = std::fs::read_dir("/etc/apt/sources.list.d") {
+        let mut parts: Vec<PathBuf> = entries.flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == "list" || e == "sources"))
+            .collect();
+        parts.sort();
+        files.extend(parts);
+    }
+    
+    let mut sources = Vec::new();
+    for file in files {
+        let contents = match std::fs::read_to_string(&file) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", file.display())),
+        };
         
-        for line in output_str.lines().take(limit) {
-            if let Some(pkg_part) = line.split(':').nth(1) {
-                for pkg_info in pkg_part.split(',').take(3) {
-                    let parts: Vec<&str> = pkg_info.trim().split_whitespace().collect();
-                    if parts.len() >= 2 {
-                        packages.push(PackageInfo {
-                            name: parts[0].trim_matches(|c| c == '(' || c == ')').to_string(),
-                            version: parts[1].trim_matches(|c| c == '(' || c == ')').to_string(),
-                            description: String::new(),
-                        });
-                    }
-                }
+        if file.extension().is_some_and(|e| e == "sources") {
+            sources.extend(parse_deb822_sources(&contents, &file));
+        } else {
+            sources.extend(parse_one_line_sources(&contents, &file));
+        }
+    }
+    
+    Ok(sources)
+}
+
+/// Parse one-line-style sources, e.g. `deb [arch=amd64] http://archive.ubuntu.com/ubuntu jammy main`
+/// 
+/// A `deb` line that is commented out is returned as disabled; other comments
+/// are skipped.
+fn parse_one_line_sources(contents: &str, file: &Path) -> Vec<AptSource> {
+    contents.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let (enabled, line) = match line.strip_prefix('#') {
+                Some(rest) => (false, rest.trim_start()),
+                None => (true, line),
+            };
+            
+            let rest = line.strip_prefix("deb")?;
+            if !rest.starts_with(char::is_whitespace) {
+                return None;
+            }
+            let mut rest = rest.trim_start();
+            if rest.starts_with('[') {
+                rest = rest[rest.find(']')? + 1..].trim_start();
             }
             
-            if packages.len() >= limit {
-                break;
+            let mut fields = rest.split_whitespace().take_while(|field| !field.starts_with('#'));
+            let uri = fields.next()?.to_string();
+            let suite = fields.next()?.to_string();
+            let components = fields.map(String::from).collect();
+            Some(AptSource { uri, suite, components, enabled, file: file.to_path_buf() })
+        })
+        .collect()
+}
+
+/// Parse deb822-style sources, one stanza per blank-line-separated paragraph
+/// 
+/// A stanza with several URIs or suites yields one entry for each pair.
+fn parse_deb822_sources(contents: &str, file: &Path) -> Vec<AptSource> {
+    let mut stanzas: Vec<Vec<&str>> = vec![Vec::new()];
+    for line in contents.lines() {
+        if line.trim().is_empty() {
+            stanzas.push(Vec::new());
+        } else if let Some(stanza) = stanzas.last_mut() {
+            stanza.push(line);
+        }
+    }
+    
+    let mut sources = Vec::new();
+    for stanza in stanzas {
+        let mut fields: HashMap<String, String> = HashMap::new();
+        let mut last_key = None;
+        for line in stanza {
+            if line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with(char::is_whitespace) {
+                // Continuation of a multi-line field such as Signed-By
+                if let Some(value) = last_key.as_ref().and_then(|key| fields.get_mut(key)) {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+                continue;
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                let key = key.trim().to_ascii_lowercase();
+                fields.insert(key.clone(), value.trim().to_string());
+                last_key = Some(key);
+            }
+        }
+        
+        let field = |key: &str| fields.get(key).map(String::as_str).unwrap_or("");
+        if !field("types").split_whitespace().any(|t| t == "deb") {
+            continue;
+        }
+        let enabled = !field("enabled").eq_ignore_ascii_case("no");
+        let components: Vec<String> = field("components").split_whitespace().map(String::from).collect();
+        
+        for uri in field("uris").split_whitespace() {
+            for suite in field("suites").split_whitespace() {
+                sources.push(AptSource {
+                    uri: uri.to_string(),
+                    suite: suite.to_string(),
+                    components: components.clone(),
+                    enabled,
+                    file: file.to_path_buf(),
+                });
             }
         }
     }
     
-    Ok(packages)
+    sources
 }
 
-/// List upgradeable packages
-pub fn list_upgradeable_packages() -> Result<Vec<PackageInfo>> {
-    let mut packages = Vec::new();
+/// Seconds to wait for a repository to answer in `check_source_reachability`
+const SOURCE_CHECK_TIMEOUT_SECS: u32 = 10;
+
+/// Check that a repository answers for its suite
+/// 
+/// Sends a HEAD request for the suite's Release file with curl, following
+/// redirects. A missing suite (the usual state of a PPA that doesn't support
+/// this release) shows up as an HTTP 404 error. `file:` repositories are
+/// checked on disk; other schemes such as `cdrom:` can't be checked.
+pub fn check_source_reachability(source: &AptSource) -> Result<()> {
+    let url = source.release_url();
     
-    if let Ok(output) = Command::new("apt")
-        .args(&["list", "--upgradable"])
+    if let Some(path) = url.strip_prefix("file:") {
+        if !Path::new(path.trim_start_matches("//")).exists() {
+            anyhow::bail!("{} does not exist", path);
+        }
+        return Ok(());
+    }
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        anyhow::bail!("Cannot check {} sources", url.split(':').next().unwrap_or(&url));
+    }
+    
+    let output = Command::new("curl")
+        .args(["--head", "--silent", "--location", "--output", "/dev/null", "--write-out", "%{http_code}"])
+        .args(["--max-time", &SOURCE_CHECK_TIMEOUT_SECS.to_string()])
+        .arg(&url)
         .output()
-    {
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        
-        for line in output_str.lines() {
-            if line.contains("upgradable") {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 2 {
-                    let name = parts[0].split('/').next().unwrap_or("").to_string();
-                    let version = parts[1].to_string();
-                    
-                    packages.push(PackageInfo {
-                        name,
-                        version,
-                        description: "Available for upgrade".to_string(),
-                    });
+        .context("Failed to run curl")?;
+    
+    let code = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() {
+        anyhow::bail!("{} is unreachable (curl exit {})", source.uri, output.status.code().unwrap_or(-1));
+    }
+    if !code.starts_with('2') {
+        anyhow::bail!("{} returned HTTP {} for {}", source.uri, code, source.suite);
+    }
+    
+    Ok(())
+}
+
+/// Which updates unattended-upgrades installs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpdateCoverage {
+    /// No allowed origins are configured
+    #[default]
+    Nothing,
+    SecurityOnly,
+    /// At least one allowed origin is not a security archive
+    All,
+}
+
+/// Automatic update settings, as configured for unattended-upgrades
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UnattendedStatus {
+    /// Upgrades run periodically (`APT::Periodic::Unattended-Upgrade` is non-zero)
+    pub enabled: bool,
+    /// Package lists are refreshed periodically (`APT::Periodic::Update-Package-Lists`)
+    pub update_lists: bool,
+    pub coverage: UpdateCoverage,
+    /// The `Allowed-Origins` and `Origins-Pattern` entries, unexpanded
+    pub origins: Vec<String>,
+}
+
+/// Configuration files read by `get_unattended_upgrades_status`, in APT's order
+const UNATTENDED_CONFIG_FILES: [&str; 2] = [
+    "/etc/apt/apt.conf.d/20auto-upgrades",
+    "/etc/apt/apt.conf.d/50unattended-upgrades",
+];
+
+/// Report whether automatic updates are on and what they cover
+/// 
+/// Reads the periodic settings from `20auto-upgrades` and the allowed origins
+/// from `50unattended-upgrades`. Missing files leave the defaults: disabled,
+/// covering nothing. Coverage is security-only when every origin that can
+/// receive updates names a security archive.
+pub fn get_unattended_upgrades_status() -> Result<UnattendedStatus> {
+    let mut entries = Vec::new();
+    for file in UNATTENDED_CONFIG_FILES {
+        match std::fs::read_to_string(file) {
+            Ok(contents) => entries.extend(parse_apt_config(&contents)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", file)),
+        }
+    }
+    
+    Ok(unattended_status(&entries))
+}
+
+/// Work out the unattended-upgrades status from parsed APT configuration
+fn unattended_status(entries: &[(String, String)]) -> UnattendedStatus {
+    // Later assignments win, as in APT; keys are case-insensitive
+    let value = |key: &str| entries.iter()
+        .rev()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v.as_str());
+    let is_on = |key: &str| value(key).is_some_and(|v| !matches!(v, "0" | "" | "false" | "no"));
+    
+    let periodic = value("APT::Periodic::Enable") != Some("0");
+    let origins: Vec<String> = entries.iter()
+        .filter(|(k, _)| {
+            k.eq_ignore_ascii_case("Unattended-Upgrade::Allowed-Origins")
+                || k.eq_ignore_ascii_case("Unattended-Upgrade::Origins-Pattern")
+        })
+        .map(|(_, v)| v.clone())
+        .collect();
+    
+    // Ubuntu also allows the release pocket, which never changes after release
+    let updating: Vec<&String> = origins.iter()
+        .filter(|o| o.as_str() != "${distro_id}:${distro_codename}")
+        .collect();
+    let coverage = if updating.is_empty() {
+        UpdateCoverage::Nothing
+    } else if updating.iter().all(|o| o.to_ascii_lowercase().contains("security")) {
+        UpdateCoverage::SecurityOnly
+    } else {
+        UpdateCoverage::All
+    };
+    
+    UnattendedStatus {
+        enabled: periodic && is_on("APT::Periodic::Unattended-Upgrade"),
+        update_lists: periodic && is_on("APT::Periodic::Update-Package-Lists"),
+        coverage,
+        origins,
+    }
+}
+
+/// Parse APT configuration into `(key, value)` pairs
+/// 
+/// Nested scopes are flattened, so `APT { Periodic { Enable "1"; }; };` gives
+/// `("APT::Periodic::Enable", "1")`. Each entry of a list such as
+/// `Allowed-Origins { "a"; "b"; };` is a pair with the list's key. Comments
+/// (`//`, `/* */` and `#` lines) are skipped, as are `#include` and `#clear`.
+fn parse_apt_config(contents: &str) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    let mut scopes: Vec<String> = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let qualify = |scopes: &[String], key: Option<&String>| {
+        scopes.iter().chain(key).cloned().collect::<Vec<_>>().join("::")
+    };
+    
+    for token in tokenize_apt_config(contents) {
+        match token {
+            ConfigToken::Word(word) => words.push(word),
+            ConfigToken::Open => {
+                scopes.push(words.first().cloned().unwrap_or_default());
+                words.clear();
+            }
+            ConfigToken::Close => {
+                scopes.pop();
+                words.clear();
+            }
+            ConfigToken::End => {
+                match words.as_slice() {
+                    [value] => entries.push((qualify(&scopes, None), value.clone())),
+                    [key, value, ..] => entries.push((qualify(&scopes, Some(key)), value.clone())),
+                    [] => {}
                 }
+                words.clear();
             }
         }
     }
     
-    Ok(packages)
+    entries
 }
 
-/// Search for packages by name
-pub fn search_packages(query: &str) -> Result<Vec<PackageInfo>> {
-    let mut packages = Vec::new();
+enum ConfigToken {
+    /// A key or a value, quoted or bare
+    Word(String),
+    Open,
+    Close,
+    End,
+}
+
+/// Split APT configuration into tokens, dropping comments
+fn tokenize_apt_config(contents: &str) -> Vec<ConfigToken> {
+    let mut tokens = Vec::new();
+    let mut chars = contents.chars().peekable();
     
-    if let Ok(output) = Command::new("apt-cache")
-        .args(&["search", query])
-        .output()
-    {
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        
-        for line in output_str.lines().take(50) {
-            if let Some((name, description)) = line.split_once(" - ") {
-                packages.push(PackageInfo {
-                    name: name.trim().to_string(),
-                    version: String::new(),
-                    description: description.trim().to_string(),
-                });
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => tokens.push(ConfigToken::Word(chars.by_ref().take_while(|&c| c != '"').collect())),
+            '{' => tokens.push(ConfigToken::Open),
+            '}' => tokens.push(ConfigToken::Close),
+            ';' => tokens.push(ConfigToken::End),
+            '#' => {
+                chars.by_ref().take_while(|&c| c != '\n').for_each(drop);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                chars.by_ref().take_while(|&c| c != '\n').for_each(drop);
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "\"{};".contains(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(ConfigToken::Word(word));
             }
         }
     }
     
-    Ok(packages)
+    tokens
 }
 
-/// Get detailed package information
-pub fn get_package_info(package: &str) -> Result<PackageInfo> {
-    let output = Command::new("dpkg-query")
-        .args(&["-W", "-f=${Package}\n${Version}\n${Description}", package])
-        .output()?;
+/// Whether updates are waiting for a reboot to take effect
+#[derive(Debug, Clone, PartialEq)]
+pub enum RebootStatus {
+    NotRequired,
+    /// Required, but the packages that asked for it are not known
+    Required,
+    /// Required by these packages
+    RequiredBy(Vec<String>),
+}
+
+/// Check whether installed updates need a reboot
+/// 
+/// Debian and Ubuntu packages touch /var/run/reboot-required when they need one
+/// and append their names to /var/run/reboot-required.pkgs. Other distributions
+/// don't use these files and always report `NotRequired`.
+pub fn reboot_required() -> RebootStatus {
+    if !Path::new("/var/run/reboot-required").exists() {
+        return RebootStatus::NotRequired;
+    }
     
-    let output_str = String::from_utf8_lossy(&output.stdout);
-    let lines: Vec<&str> = output_str.lines().collect();
+    let mut packages: Vec<String> = std::fs::read_to_string("/var/run/reboot-required.pkgs")
+        .unwrap_or_default()
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+    packages.sort();
+    packages.dedup();
     
-    Ok(PackageInfo {
-        name: lines.get(0).unwrap_or(&"").to_string(),
-        version: lines.get(1).unwrap_or(&"").to_string(),
-        description: lines.get(2).unwrap_or(&"").to_string(),
-    })
+    if packages.is_empty() {
+        RebootStatus::Required
+    } else {
+        RebootStatus::RequiredBy(packages)
+    }
 }
 
-/// Upgrade all packages (requires sudo)
-pub fn upgrade_packages() -> Result<()> {
-    Command::new("pkexec")
-        .args(&["apt-get", "update"])
-        .output()?;
+/// Installed packages and their versions at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackageSnapshot {
+    /// When the snapshot was taken, in seconds since the Unix epoch
+    pub taken_at: u64,
+    /// Installed version of each package, by name
+    pub packages: BTreeMap<String, String>,
+}
+
+/// A package whose installed version differs between two snapshots
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageChange {
+    pub name: String,
+    pub old_version: String,
+    pub new_version: String,
+}
+
+/// What changed between two package snapshots, each list sorted by name
+#[derive(Debug, Clone, Default)]
+pub struct PackageDiff {
+    pub added: Vec<PackageInfo>,
+    pub removed: Vec<PackageInfo>,
+    /// Packages installed in both with a different version; downgrades are
+    /// listed here as well
+    pub upgraded: Vec<PackageChange>,
+}
+
+impl PackageDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.upgraded.is_empty()
+    }
+}
+
+/// Record the installed packages and their versions
+pub fn snapshot_installed_packages() -> Result<PackageSnapshot> {
+    let (program, args): (&str, &[&str]) = match detect_backend() {
+        PackageBackend::Apt => ("dpkg-query", &["-W", "-f", "${db:Status-Status} ${binary:Package} ${Version}\n"]),
+        PackageBackend::Pacman => ("pacman", &["-Q"]),
+        PackageBackend::Dnf => ("rpm", &["-qa", "--qf", "%{NAME} %{VERSION}-%{RELEASE}\n"]),
+    };
     
-    Command::new("pkexec")
-        .args(&["apt-get", "upgrade", "-y"])
-        .output()?;
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !output.status.success() {
+        anyhow::bail!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim());
+    }
     
-    Ok(())
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let packages = if detect_backend() == PackageBackend::Apt {
+        // dpkg also lists removed packages that left their configuration behind
+        parse_installed_versions(stdout.lines().filter_map(|line| line.strip_prefix("installed ")))
+    } else {
+        parse_installed_versions(stdout.lines())
+    };
+    
+    let taken_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    
+    Ok(PackageSnapshot { taken_at, packages })
 }
 
-/// Install a package (requires sudo)
-pub fn install_package(package: &str) -> Result<()> {
-    Command::new("pkexec")
-        .args(&["apt-get", "install", "-y", package])
-        .output()?;
+/// Parse `name version` lines
+fn parse_installed_versions<'a>(lines: impl Iterator<Item = &'a str>) -> BTreeMap<String, String> {
+    lines
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some((parts.next()?.to_string(), parts.next()?.to_string()))
+        })
+        .collect()
+}
+
+/// Directory snapshots are saved in, `~/.local/share/sysmate/snapshots`
+fn snapshot_dir() -> Result<PathBuf> {
+    dirs::data_dir()
+        .map(|dir| dir.join("sysmate").join("snapshots"))
+        .context("Could not determine the data directory")
+}
+
+/// Save a snapshot as JSON, named after the time it was taken
+pub fn save_snapshot(snapshot: &PackageSnapshot) -> Result<PathBuf> {
+    let dir = snapshot_dir()?;
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
     
-    Ok(())
+    let path = dir.join(format!("{}.json", snapshot.taken_at));
+    let json = serde_json::to_string(snapshot).context("Failed to serialize snapshot")?;
+    fs::write(&path, json)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
 }
 
-/// Remove a package (requires sudo)
-pub fn remove_package(package: &str) -> Result<()> {
-    Command::new("pkexec")
-        .args(&["apt-get", "remove", "-y", package])
-        .output()?;
+/// Load all saved snapshots, most recent first
+/// 
+/// Files that can't be read or parsed are skipped.
+pub fn list_snapshots() -> Result<Vec<PackageSnapshot>> {
+    let dir = snapshot_dir()?;
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", dir.display())),
+    };
     
-    Ok(())
+    let mut snapshots: Vec<PackageSnapshot> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|contents| serde_json::from_str(&contents).ok())
+        .collect();
+    snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.taken_at));
+    Ok(snapshots)
 }
 
-/// Autoremove unused packages (requires sudo)
-pub fn autoremove_packages() -> Result<()> {
-    Command::new("pkexec")
-        .args(&["apt-get", "autoremove", "-y"])
-        .output()?;
+/// Compare an older snapshot `a` with a newer snapshot `b`
+pub fn diff_snapshots(a: &PackageSnapshot, b: &PackageSnapshot) -> PackageDiff {
+    let mut diff = PackageDiff::default();
     
-    Ok(())
+    for (name, new_version) in &b.packages {
+        match a.packages.get(name) {
+            None => diff.added.push(PackageInfo {
+                name: name.clone(),
+                version: new_version.clone(),
+                description: String::new(),
+            }),
+            Some(old_version) if old_version != new_version => diff.upgraded.push(PackageChange {
+                name: name.clone(),
+                old_version: old_version.clone(),
+                new_version: new_version.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    
+    for (name, version) in &a.packages {
+        if !b.packages.contains_key(name) {
+            diff.removed.push(PackageInfo {
+                name: name.clone(),
+                version: version.clone(),
+                description: String::new(),
+            });
+        }
+    }
+    
+    diff
 }
 
-/// Count installed Snap packages
-pub fn get_snap_count() -> usize {
-    if let Ok(output) = Command::new("snap")
-        .args(&["list"])
-        .output()
-    {
-        String::from_utf8_lossy(&output.stdout)
-            .lines()
-            .skip(1) // Skip header
-            .count()
-    } else {
-        0
+/// APT's log of every install, upgrade and removal
+const APT_HISTORY_LOG: &str = "/var/log/apt/history.log";
+
+/// What an APT run did to a group of packages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryAction {
+    Install,
+    Upgrade,
+    Downgrade,
+    Reinstall,
+    Remove,
+    Purge,
+}
+
+impl HistoryAction {
+    /// The action for a field name in history.log, e.g. `Upgrade`
+    fn from_field(field: &str) -> Option<Self> {
+        match field {
+            "Install" => Some(HistoryAction::Install),
+            "Upgrade" => Some(HistoryAction::Upgrade),
+            "Downgrade" => Some(HistoryAction::Downgrade),
+            "Reinstall" => Some(HistoryAction::Reinstall),
+            "Remove" => Some(HistoryAction::Remove),
+            "Purge" => Some(HistoryAction::Purge),
+            _ => None,
+        }
+    }
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            HistoryAction::Install => "Installed",
+            HistoryAction::Upgrade => "Upgraded",
+            HistoryAction::Downgrade => "Downgraded",
+            HistoryAction::Reinstall => "Reinstalled",
+            HistoryAction::Remove => "Removed",
+            HistoryAction::Purge => "Purged",
+        }
     }
 }
 
-/// Count installed Flatpak applications
-pub fn get_flatpak_count() -> usize {
-    if let Ok(output) = Command::new("flatpak")
-        .args(&["list", "--app"])
-        .output()
-    {
-        String::from_utf8_lossy(&output.stdout)
-            .lines()
-            .count()
-    } else {
-        0
+/// A package changed by an APT run
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryPackage {
+    pub name: String,
+    /// e.g. `amd64`, as logged after the name
+    pub arch: Option<String>,
+    /// Version before the change, `None` for installs
+    pub from_version: Option<String>,
+    /// Version after the change, `None` for removals
+    pub to_version: Option<String>,
+}
+
+/// One action of an APT run, as recorded in /var/log/apt/history.log
+/// 
+/// A run that both installs and upgrades packages gives one transaction per
+/// action, sharing the start time and command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AptTransaction {
+    /// Local time the run started, e.g. `2024-01-15 10:23:45`
+    pub start_time: String,
+    /// Command line of the run, empty if the frontend didn't log one
+    pub command: String,
+    pub action: HistoryAction,
+    pub packages: Vec<HistoryPackage>,
+    /// User who ran APT through sudo or pkexec, e.g. `alice (1000)`
+    pub requested_by: Option<String>,
+}
+
+/// Get the most recent APT transactions, newest first
+/// 
+/// Only the current history.log is read, not the rotated ones. Returns an empty
+/// list on systems without APT.
+pub fn get_apt_history(limit: usize) -> Result<Vec<AptTransaction>> {
+    let contents = match fs::read_to_string(APT_HISTORY_LOG) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).context(format!("Failed to read {}", APT_HISTORY_LOG)),
+    };
+    
+    let mut transactions = parse_apt_history(&contents);
+    transactions.reverse();
+    transactions.truncate(limit);
+    Ok(transactions)
+}
+
+/// Parse history.log, oldest transaction first
+/// 
+/// Each run is a block of `Field: value` lines:
+/// ```text
+/// Start-Date: 2024-01-15  10:23:45
+/// Commandline: apt-get install foo
+/// Requested-By: alice (1000)
+/// Install: foo:amd64 (1.2-3), libfoo:amd64 (1.2-3, automatic)
+/// Upgrade: bar:amd64 (1:1.0-1, 1:1.1-1)
+/// End-Date: 2024-01-15  10:23:50
+/// ```
+/// Unknown fields such as `Error` are ignored.
+fn parse_apt_history(contents: &str) -> Vec<AptTransaction> {
+    let mut transactions = Vec::new();
+    let mut start_time = String::new();
+    let mut command = String::new();
+    let mut requested_by = None;
+    
+    for line in contents.lines() {
+        let Some((field, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        
+        match field {
+            "Start-Date" => {
+                start_time = value.split_whitespace().collect::<Vec<_>>().join(" ");
+                command.clear();
+                requested_by = None;
+            }
+            "Commandline" => command = value.to_string(),
+            "Requested-By" => requested_by = Some(value.to_string()),
+            _ => {
+                if let Some(action) = HistoryAction::from_field(field) {
+                    transactions.push(AptTransaction {
+                        start_time: start_time.clone(),
+                        command: command.clone(),
+                        action,
+                        packages: parse_history_packages(action, value),
+                        requested_by: requested_by.clone(),
+                    });
+                }
+            }
+        }
+    }
+    
+    transactions
+}
+
+/// Parse a history.log package list, e.g. `foo:amd64 (1.0, 1.1), bar:amd64 (2.0, automatic)`
+/// 
+/// Upgrades and downgrades list the old and new version, removals the removed
+/// one and the others the installed one. Versions may contain `:` (epochs).
+fn parse_history_packages(action: HistoryAction, list: &str) -> Vec<HistoryPackage> {
+    let mut packages = Vec::new();
+    let mut rest = list.trim();
+    
+    while let Some((package, after)) = rest.split_once(" (") {
+        let Some((versions, after)) = after.split_once(')') else {
+            break;
+        };
+        rest = after.trim_start_matches(',').trim_start();
+        
+        let versions: Vec<String> = versions.split(',')
+            .map(|version| version.trim().to_string())
+            .filter(|version| !version.is_empty() && version != "automatic")
+            .collect();
+        let (from_version, to_version) = match (action, versions.as_slice()) {
+            (HistoryAction::Upgrade | HistoryAction::Downgrade, [from, to, ..]) => (Some(from.clone()), Some(to.clone())),
+            (HistoryAction::Reinstall, [version, ..]) => (Some(version.clone()), Some(version.clone())),
+            (HistoryAction::Install, [version, ..]) => (None, Some(version.clone())),
+            (HistoryAction::Remove | HistoryAction::Purge, [version, ..]) => (Some(version.clone()), None),
+            _ => (None, None),
+        };
+        
+        let package = package.trim();
+        let (name, arch) = match package.split_once(':') {
+            Some((name, arch)) => (name, Some(arch.to_string())),
+            None => (package, None),
+        };
+        packages.push(HistoryPackage {
+            name: name.to_string(),
+            arch,
+            from_version,
+            to_version,
+        });
+    }
+    
+    packages
+}
+
+/// List recently installed or upgraded packages, most recent first
+/// 
+/// Taken from the APT history. Each package is listed once, with the version it
+/// was last installed or upgraded to.
+pub fn list_recent_packages(limit: usize) -> Result<Vec<PackageInfo>> {
+    let mut packages: Vec<PackageInfo> = Vec::new();
+    
+    for transaction in get_apt_history(usize::MAX)? {
+        if !matches!(transaction.action, HistoryAction::Install | HistoryAction::Upgrade) {
+            continue;
+        }
+        
+        for package in transaction.packages {
+            if packages.len() >= limit {
+                return Ok(packages);
+            }
+            if packages.iter().any(|p| p.name == package.name) {
+                continue;
+            }
+            packages.push(PackageInfo {
+                name: package.name,
+                version: package.to_version.unwrap_or_default(),
+                description: String::new(),
+            });
+        }
+    }
+    
+    Ok(packages)
+}
+
+/// List upgradeable packages
+pub fn list_upgradeable_packages() -> Result<Vec<UpgradablePackage>> {
+    let mut packages = Vec::new();
+    
+    if let Ok(output) = Command::new("apt")
+        .args(["list", "--upgradable"])
+        .output()
+    {
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        packages.extend(output_str.lines().filter_map(parse_upgradable_line));
+    }
+    
+    Ok(packages)
+}
+
+/// Parse one line of `apt list --upgradable`
+/// 
+/// Lines look like:
+/// `firefox/jammy-updates 120.0+build2 amd64 [upgradable from: 119.0+build2]`
+fn parse_upgradable_line(line: &str) -> Option<UpgradablePackage> {
+    let (fields, from) = line.split_once("[upgradable from:")?;
+    let parts: Vec<&str> = fields.split_whitespace().collect();
+    if parts.len() < 2 {
+        return None;
+    }
+    
+    Some(UpgradablePackage {
+        name: parts[0].split('/').next().unwrap_or("").to_string(),
+        current_version: from.trim().trim_end_matches(']').trim().to_string(),
+        available_version: parts[1].to_string(),
+    })
+}
+
+/// List packages on hold
+/// 
+/// Held packages are skipped by `apt-get upgrade`, which is why they can show up
+/// as upgradeable without ever being upgraded.
+pub fn list_held_packages() -> Result<Vec<PackageInfo>> {
+    let output = Command::new("apt-mark")
+        .args(["showhold"])
+        .output()
+        .context("Failed to run apt-mark")?;
+    
+    let output_str = String::from_utf8_lossy(&output.stdout);
+    let names: Vec<&str> = output_str.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    // Look up all installed versions in one call
+    let mut packages = Vec::new();
+    let output = Command::new("dpkg-query")
+        .arg("-W")
+        .arg("-f=${Package}\t${Version}\n")
+        .args(&names)
+        .output()?;
+    
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some((name, version)) = line.split_once('\t') {
+            packages.push(PackageInfo {
+                name: name.to_string(),
+                version: version.to_string(),
+                description: "Held at current version".to_string(),
+            });
+        }
+    }
+    
+    Ok(packages)
+}
+
+/// Hold a package at its installed version, so upgrades skip it (requires sudo)
+/// 
+/// With `dry_run` nothing changes; the command that would run is returned instead.
+pub fn hold_package(package: &str, dry_run: bool) -> Result<Outcome<()>> {
+    run_apt_mark(AptMarkAction::Hold, package, dry_run)
+}
+
+/// Let upgrades include a held package again (requires sudo)
+/// 
+/// With `dry_run` nothing changes; the command that would run is returned instead.
+pub fn unhold_package(package: &str, dry_run: bool) -> Result<Outcome<()>> {
+    run_apt_mark(AptMarkAction::Unhold, package, dry_run)
+}
+
+fn run_apt_mark(action: AptMarkAction, package: &str, dry_run: bool) -> Result<Outcome<()>> {
+    let command = HelperCommand::AptMark { action, packages: vec![package.to_string()] };
+    if dry_run {
+        return Ok(Outcome::Simulated(vec![format!("Run as root: {}", command.argv().join(" "))]));
+    }
+    
+    privileged::run_commands(&[command])?;
+    Ok(Outcome::Done(()))
+}
+
+/// Maximum number of distinct queries kept by the search cache
+const SEARCH_CACHE_CAPACITY: usize = 32;
+
+/// Recently used search results, most recent first
+static SEARCH_CACHE: Mutex<VecDeque<(String, Vec<PackageInfo>)>> = Mutex::new(VecDeque::new());
+
+/// Search for packages by name
+/// 
+/// Results are kept in a small LRU cache keyed by the exact query, so retyping
+/// a query does not spawn `apt-cache` again.
+pub fn search_packages(query: &str) -> Result<Vec<PackageInfo>> {
+    if let Ok(mut cache) = SEARCH_CACHE.lock() {
+        if let Some(index) = cache.iter().position(|(q, _)| q == query) {
+            if let Some(entry) = cache.remove(index) {
+                let results = entry.1.clone();
+                cache.push_front(entry);
+                return Ok(results);
+            }
+        }
+    }
+    
+    let mut packages = Vec::new();
+    
+    if let Ok(output) = Command::new("apt-cache")
+        .args(["search", query])
+        .output()
+    {
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        
+        for line in output_str.lines().take(50) {
+            if let Some((name, description)) = line.split_once(" - ") {
+                packages.push(PackageInfo {
+                    name: name.trim().to_string(),
+                    version: String::new(),
+                    description: description.trim().to_string(),
+                });
+            }
+        }
+    }
+    
+    if let Ok(mut cache) = SEARCH_CACHE.lock() {
+        cache.push_front((query.to_string(), packages.clone()));
+        cache.truncate(SEARCH_CACHE_CAPACITY);
+    }
+    
+    Ok(packages)
+}
+
+/// Drop all cached search results
+/// 
+/// Call this after the package database changes, e.g. after an install.
+pub fn clear_search_cache() {
+    if let Ok(mut cache) = SEARCH_CACHE.lock() {
+        cache.clear();
+    }
+}
+
+/// Get detailed package information
+pub fn get_package_info(package: &str) -> Result<PackageInfo> {
+    let output = Command::new("dpkg-query")
+        .args(["-W", "-f=${Package}\n${Version}\n${Description}", package])
+        .output()?;
+    
+    let output_str = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = output_str.lines().collect();
+    
+    Ok(PackageInfo {
+        name: lines.first().unwrap_or(&"").to_string(),
+        version: lines.get(1).unwrap_or(&"").to_string(),
+        description: lines.get(2).unwrap_or(&"").to_string(),
+    })
+}
+
+/// List the paths a package installed
+/// 
+/// Parses `dpkg -L`, skipping its diversion notes and the `/.` root entry.
+/// dpkg also lists every directory a package ships files into, shared ones
+/// like /usr included; with `files_only` those are left out. Symlinks are kept,
+/// even when they point at a directory.
+pub fn get_package_files(package: &str, files_only: bool) -> Result<Vec<PathBuf>> {
+    let output = Command::new("dpkg")
+        .args(["-L", package])
+        .output()
+        .context("Failed to run dpkg")?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("is not installed") {
+            anyhow::bail!("Package '{}' is not installed", package);
+        }
+        anyhow::bail!("dpkg -L {} failed ({}): {}", package, output.status, stderr.trim());
+    }
+    
+    let files = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.starts_with('/') && *line != "/.")
+        .map(PathBuf::from)
+        .filter(|path| {
+            !files_only || !path.symlink_metadata().map(|m| m.is_dir()).unwrap_or(false)
+        })
+        .collect();
+    
+    Ok(files)
+}
+
+/// How an installed file differs from what its package shipped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileIssueKind {
+    /// The contents no longer match the package's checksum
+    Modified,
+    Missing,
+}
+
+/// A file that failed verification
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileIssue {
+    pub path: PathBuf,
+    pub kind: FileIssueKind,
+}
+
+/// Number of files hashed per `md5sum` call
+const MD5SUM_BATCH: usize = 200;
+
+/// Check the files of an installed package against the checksums it shipped
+/// 
+/// Uses `debsums` when installed, otherwise compares the package's md5sums
+/// control file with `md5sum` directly. Configuration files have no recorded
+/// checksum and aren't checked. An empty list means every file is intact.
+pub fn verify_package(package: &str) -> Result<Vec<FileIssue>> {
+    if binary_in_path("debsums") {
+        return verify_with_debsums(package);
+    }
+    
+    let output = Command::new("dpkg-query")
+        .args(["--control-path", package, "md5sums"])
+        .output()
+        .context("Failed to run dpkg-query")?;
+    let control_path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || control_path.is_empty() {
+        anyhow::bail!("No checksums recorded for '{}'", package);
+    }
+    
+    let md5sums = fs::read_to_string(&control_path)
+        .with_context(|| format!("Failed to read {}", control_path))?;
+    verify_md5sums(&md5sums)
+}
+
+/// Parse the `debsums -s` complaints on stderr
+fn verify_with_debsums(package: &str) -> Result<Vec<FileIssue>> {
+    let output = Command::new("debsums")
+        .args(["-s", package])
+        .output()
+        .context("Failed to run debsums")?;
+    
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let issues: Vec<FileIssue> = stderr.lines()
+        .filter_map(|line| {
+            let rest = line.strip_prefix("debsums: ")?;
+            let (kind, rest) = if let Some(rest) = rest.strip_prefix("changed file ") {
+                (FileIssueKind::Modified, rest)
+            } else {
+                (FileIssueKind::Missing, rest.strip_prefix("missing file ")?)
+            };
+            // "changed file /usr/bin/x (from pkg package)"
+            let path = rest.rsplit_once(" (from ").map_or(rest, |(path, _)| path);
+            Some(FileIssue { path: PathBuf::from(path), kind })
+        })
+        .collect();
+    
+    if !output.status.success() && issues.is_empty() {
+        anyhow::bail!("debsums {} failed ({}): {}", package, output.status, stderr.trim());
+    }
+    Ok(issues)
+}
+
+/// Compare files with the `<md5>  <path relative to />` lines of a md5sums file
+fn verify_md5sums(md5sums: &str) -> Result<Vec<FileIssue>> {
+    let mut issues = Vec::new();
+    let mut expected: HashMap<PathBuf, &str> = HashMap::new();
+    
+    for line in md5sums.lines() {
+        let Some((hash, path)) = line.split_once("  ") else {
+            continue;
+        };
+        let path = Path::new("/").join(path);
+        if path.exists() {
+            expected.insert(path, hash);
+        } else {
+            issues.push(FileIssue { path, kind: FileIssueKind::Missing });
+        }
+    }
+    
+    let paths: Vec<&PathBuf> = expected.keys().collect();
+    for batch in paths.chunks(MD5SUM_BATCH) {
+        let output = Command::new("md5sum")
+            .arg("--")
+            .args(batch)
+            .output()
+            .context("Failed to run md5sum")?;
+        
+        // Unreadable files are left out of stdout, so they are not reported
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let Some((hash, path)) = line.split_once("  ") else {
+                continue;
+            };
+            let path = PathBuf::from(path);
+            if expected.get(&path).is_some_and(|expected| *expected != hash) {
+                issues.push(FileIssue { path, kind: FileIssueKind::Modified });
+            }
+        }
+    }
+    
+    issues.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(issues)
+}
+
+/// Find the package that installed a file
+/// 
+/// The path is made absolute but symlinks are not resolved at first, since dpkg
+/// records paths as shipped. If that finds nothing, the fully resolved path is
+/// tried, and on merged-/usr systems also its alias outside /usr (dpkg knows
+/// /usr/bin/bash as /bin/bash). Returns `None` for files no package owns. When
+/// several packages share a directory, the first is returned.
+pub fn find_owning_package(path: &Path) -> Result<Option<String>> {
+    let absolute = std::path::absolute(path)
+        .with_context(|| format!("Failed to resolve {}", path.display()))?;
+    let mut candidates = vec![absolute];
+    
+    if let Ok(resolved) = path.canonicalize() {
+        if let Ok(rest) = resolved.strip_prefix("/usr") {
+            let alias = Path::new("/").join(rest);
+            let top_level = rest.components().next().map(|c| Path::new("/").join(c));
+            if top_level.is_some_and(|dir| dir.is_symlink()) {
+                candidates.push(alias);
+            }
+        }
+        candidates.insert(1, resolved);
+    }
+    candidates.dedup();
+    
+    for candidate in candidates {
+        if let Some(package) = query_owner(&candidate)? {
+            return Ok(Some(package));
+        }
+    }
+    
+    Ok(None)
+}
+
+/// Ask `dpkg -S` which package owns exactly this path
+fn query_owner(path: &Path) -> Result<Option<String>> {
+    let output = Command::new("dpkg")
+        .arg("-S")
+        .arg(path)
+        .output()
+        .context("Failed to run dpkg")?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("no path found") {
+            return Ok(None);
+        }
+        anyhow::bail!("dpkg -S {} failed ({}): {}", path.display(), output.status, stderr.trim());
+    }
+    
+    // Lines look like "pkg1, pkg2: /path"; diversion notes and pattern matches are skipped
+    let wanted = path.to_string_lossy();
+    let owner = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.starts_with("diversion by"))
+        .filter_map(|line| line.split_once(": "))
+        .find(|(_, owned)| *owned == wanted)
+        .and_then(|(packages, _)| packages.split(", ").next())
+        .map(|package| package.to_string());
+    
+    Ok(owner)
+}
+
+/// Get the packages a package depends on
+/// 
+/// Parses the `Depends`/`PreDepends` entries of `apt-cache depends`. Alternatives
+/// (`|`) are listed as plain names and virtual packages (`<...>`) are skipped.
+pub fn get_package_dependencies(package: &str) -> Result<Vec<String>> {
+    let output = Command::new("apt-cache")
+        .args(["depends", package])
+        .output()
+        .context("Failed to run apt-cache")?;
+    
+    Ok(parse_depends(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Get the installed packages that depend on a package
+pub fn get_reverse_dependencies(package: &str) -> Result<Vec<String>> {
+    let output = Command::new("apt-cache")
+        .args(["rdepends", "--installed", package])
+        .output()
+        .context("Failed to run apt-cache")?;
+    
+    Ok(parse_rdepends(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `apt-cache depends` output into clean package names
+fn parse_depends(output: &str) -> Vec<String> {
+    let mut deps: Vec<String> = Vec::new();
+    
+    for line in output.lines() {
+        let entry = line.trim().trim_start_matches('|');
+        let Some((kind, name)) = entry.split_once(':') else {
+            continue;
+        };
+        
+        if kind != "Depends" && kind != "PreDepends" {
+            continue;
+        }
+        
+        let name = name.trim();
+        if name.is_empty() || name.starts_with('<') {
+            continue;
+        }
+        
+        if !deps.iter().any(|d| d == name) {
+            deps.push(name.to_string());
+        }
+    }
+    
+    deps
+}
+
+/// Parse `apt-cache rdepends` output into clean package names
+fn parse_rdepends(output: &str) -> Vec<String> {
+    let mut rdeps: Vec<String> = Vec::new();
+    
+    for line in output.lines().skip_while(|l| !l.starts_with("Reverse Depends:")).skip(1) {
+        let name = line.trim().trim_start_matches('|');
+        if name.is_empty() || name.starts_with('<') {
+            continue;
+        }
+        
+        if !rdeps.iter().any(|d| d == name) {
+            rdeps.push(name.to_string());
+        }
+    }
+    
+    rdeps
+}
+
+/// Get the changelog of an APT package
+/// 
+/// Tries `apt-get changelog` first, which fetches from the network, then falls back to
+/// the compressed Debian changelog shipped in /usr/share/doc. Returns an empty string
+/// when no changelog is available.
+pub fn get_package_changelog(package: &str) -> Result<String> {
+    if let Ok(output) = Command::new("apt-get")
+        .args(["changelog", package])
+        .output()
+    {
+        let changelog = String::from_utf8_lossy(&output.stdout);
+        if output.status.success() && !changelog.trim().is_empty() {
+            return Ok(changelog.to_string());
+        }
+    }
+    
+    // Avoid escaping /usr/share/doc with a crafted name
+    if package.is_empty() || package.contains('/') {
+        return Ok(String::new());
+    }
+    
+    let local = PathBuf::from(format!("/usr/share/doc/{}/changelog.Debian.gz", package));
+    if !local.exists() {
+        return Ok(String::new());
+    }
+    
+    let output = Command::new("gzip")
+        .arg("-dc")
+        .arg(&local)
+        .output()
+        .context("Failed to run gzip")?;
+    
+    if !output.status.success() {
+        return Ok(String::new());
+    }
+    
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Get the number of bytes `apt-get upgrade` would download
+/// 
+/// `apt-get -s` does not print the download summary, so this uses `--print-uris`,
+/// which also resolves the upgrade without taking the dpkg lock or installing anything.
+pub fn get_upgrade_download_size() -> Result<u64> {
+    let output = Command::new("apt-get")
+        .args(["--print-uris", "upgrade"])
+        .env("LC_ALL", "C")
+        .output()
+        .context("Failed to run apt-get")?;
+    
+    Ok(parse_download_size(&String::from_utf8_lossy(&output.stdout)).unwrap_or(0))
+}
+
+/// Parse the "Need to get X MB of archives." line of apt-get output
+/// 
+/// When some archives are already cached apt prints "Need to get 1 MB/3 MB",
+/// where the first figure is what is still left to download.
+fn parse_download_size(output: &str) -> Option<u64> {
+    let line = output.lines().find(|l| l.starts_with("Need to get "))?;
+    let amount = line.strip_prefix("Need to get ")?.split(" of archives").next()?;
+    let needed = amount.split('/').next()?.trim();
+    
+    let (number, unit) = needed.split_once(' ')?;
+    let value: f64 = number.replace(',', "").parse().ok()?;
+    let multiplier = match unit.trim() {
+        "B" => 1.0,
+        "kB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        _ => return None,
+    };
+    
+    Some((value * multiplier) as u64)
+}
+
+/// Prefix of the final line passed to `upgrade_packages_streaming` callbacks
+pub const EXIT_STATUS_PREFIX: &str = "Exit status: ";
+
+/// Upgrade all packages (requires sudo)
+/// 
+/// Runs `apt-get update` followed by `apt-get upgrade -y` in the privileged
+/// helper, so no new prompt is needed if it is already running.
+pub fn upgrade_packages() -> Result<()> {
+    privileged::run_commands(&[
+        HelperCommand::Apt { action: AptAction::Update, packages: Vec::new() },
+        HelperCommand::Apt { action: AptAction::Upgrade, packages: Vec::new() },
+    ])?;
+    clear_search_cache();
+    Ok(())
+}
+
+/// Upgrade all packages, reporting progress line by line (requires sudo)
+/// 
+/// Runs `apt-get update` followed by `apt-get upgrade -y` in the privileged
+/// helper, like `upgrade_packages`, invoking `on_line` for every stdout/stderr
+/// line as it is emitted. The last line always starts with `EXIT_STATUS_PREFIX`
+/// followed by 0 on success or 1 on failure, after a line saying what failed.
+/// This blocks until the upgrade finishes, so call it off the main thread.
+pub fn upgrade_packages_streaming(mut on_line: impl FnMut(String)) -> Result<()> {
+    let result = privileged::run_commands_streaming(
+        &[
+            HelperCommand::Apt { action: AptAction::Update, packages: Vec::new() },
+            HelperCommand::Apt { action: AptAction::Upgrade, packages: Vec::new() },
+        ],
+        &mut on_line,
+    );
+    
+    match result {
+        Ok(()) => {
+            clear_search_cache();
+            on_line(format!("{}0", EXIT_STATUS_PREFIX));
+            Ok(())
+        }
+        Err(e) => {
+            on_line(format!("Error: {:#}", e));
+            on_line(format!("{}1", EXIT_STATUS_PREFIX));
+            Err(e.context("Upgrade failed"))
+        }
+    }
+}
+
+/// Install a package (requires sudo)
+/// 
+/// With `dry_run` nothing is installed; the planned changes are returned instead.
+pub fn install_package(package: &str, dry_run: bool) -> Result<Outcome<()>> {
+    run_apt(AptAction::Install, &[package.to_string()], dry_run)
+}
+
+/// List the versions of a package APT can install, as `apt-cache madison` orders them
+/// 
+/// Only versions offered by a configured source are listed, so the installed
+/// version is missing if no source carries it anymore.
+pub fn list_package_versions(package: &str) -> Result<Vec<String>> {
+    if package.is_empty() || package.starts_with('-') {
+        anyhow::bail!("Invalid package name '{}'", package);
+    }
+    
+    let output = Command::new("apt-cache")
+        .args(["madison", package])
+        .output()
+        .context("Failed to run apt-cache")?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("apt-cache madison {} failed ({}): {}", package, output.status, stderr.trim());
+    }
+    
+    Ok(parse_madison(package, &String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `apt-cache madison` output
+/// 
+/// Lines look like
+/// `  firefox | 120.0+build2 | http://archive.ubuntu.com/ubuntu jammy-updates/main amd64 Packages`.
+/// Source package lines (ending in `Sources`) are skipped, and a version offered
+/// by several sources is listed once.
+fn parse_madison(package: &str, output: &str) -> Vec<String> {
+    let mut versions: Vec<String> = Vec::new();
+    
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+        let [name, version, source] = fields.as_slice() else {
+            continue;
+        };
+        if *name != package || version.is_empty() || source.ends_with("Sources") {
+            continue;
+        }
+        if !versions.iter().any(|v| v == version) {
+            versions.push(version.to_string());
+        }
+    }
+    
+    versions
+}
+
+/// Install a specific version of a package (requires sudo)
+/// 
+/// Used to go back to a known-good version after a bad upgrade, so downgrades
+/// are allowed. The version must be one `list_package_versions` reports, which
+/// keeps anything but a real version off the apt-get command line. With
+/// `dry_run` the planned changes are returned instead.
+pub fn install_package_version(package: &str, version: &str, dry_run: bool) -> Result<Outcome<()>> {
+    let versions = list_package_versions(package)?;
+    if !versions.iter().any(|v| v == version) {
+        anyhow::bail!("Version {} of {} is not available from any configured source", version, package);
+    }
+    
+    run_apt(AptAction::InstallVersion, &[format!("{}={}", package, version)], dry_run)
+}
+
+/// Upgrade only the given packages (requires sudo)
+/// 
+/// Runs `apt-get install --only-upgrade`, so none of them is newly installed.
+/// apt refuses to change held packages, so callers should leave those out.
+/// With `dry_run` the planned changes are returned instead.
+pub fn upgrade_selected(packages: &[String], dry_run: bool) -> Result<Outcome<()>> {
+    if packages.is_empty() {
+        return Ok(Outcome::Done(()));
+    }
+    
+    run_apt(AptAction::OnlyUpgrade, packages, dry_run)
+}
+
+/// Remove a package (requires sudo)
+/// 
+/// With `dry_run` nothing is removed; the planned changes are returned instead.
+pub fn remove_package(package: &str, dry_run: bool) -> Result<Outcome<()>> {
+    run_apt(AptAction::Remove, &[package.to_string()], dry_run)
+}
+
+/// List the packages `apt-get autoremove` would remove
+/// 
+/// Runs the autoremove as a dry run, which needs no root. Descriptions are
+/// left empty.
+pub fn list_autoremovable_packages() -> Result<Vec<PackageInfo>> {
+    let output = Command::new("apt-get")
+        .env("LC_ALL", "C")
+        .args(["autoremove", "--dry-run"])
+        .output()
+        .context("Failed to run apt-get")?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("apt-get autoremove --dry-run failed ({}): {}", output.status, stderr.trim());
+    }
+    
+    Ok(parse_autoremove_dry_run(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse the packages out of `apt-get autoremove --dry-run` output
+/// 
+/// Names come from the indented lists under the "no longer required" and
+/// "will be REMOVED" headings (a trailing `*` marks a purge), versions from
+/// the `Remv name [version]` lines that follow.
+fn parse_autoremove_dry_run(output: &str) -> Vec<PackageInfo> {
+    let mut packages: Vec<PackageInfo> = Vec::new();
+    let mut in_list = false;
+    
+    for line in output.lines() {
+        if line.ends_with(':') && (line.contains("will be REMOVED") || line.contains("no longer required")) {
+            in_list = true;
+            continue;
+        }
+        
+        if in_list && line.starts_with(' ') {
+            for name in line.split_whitespace().map(|name| name.trim_end_matches('*')) {
+                if !packages.iter().any(|p| p.name == name) {
+                    packages.push(PackageInfo {
+                        name: name.to_string(),
+                        version: String::new(),
+                        description: String::new(),
+                    });
+                }
+            }
+            continue;
+        }
+        in_list = false;
+        
+        if let Some(rest) = line.strip_prefix("Remv ") {
+            let Some(name) = rest.split_whitespace().next() else {
+                continue;
+            };
+            let version = rest.split_once('[')
+                .and_then(|(_, v)| v.split_once(']'))
+                .map(|(v, _)| v.to_string())
+                .unwrap_or_default();
+            
+            match packages.iter_mut().find(|p| p.name == name) {
+                Some(package) => package.version = version,
+                None => packages.push(PackageInfo {
+                    name: name.to_string(),
+                    version,
+                    description: String::new(),
+                }),
+            }
+        }
+    }
+    
+    packages
+}
+
+/// Autoremove unused packages (requires sudo)
+/// 
+/// With `dry_run` nothing is removed; the planned changes are returned instead.
+pub fn autoremove_packages(dry_run: bool) -> Result<Outcome<()>> {
+    run_apt(AptAction::Autoremove, &[], dry_run)
+}
+
+/// Run an apt-get action as root, or simulate it
+/// 
+/// `apt-get -s` needs no root and prints one `Inst`, `Remv` or `Purg` line per
+/// package it would touch; those lines are the simulated outcome.
+fn run_apt(action: AptAction, packages: &[String], dry_run: bool) -> Result<Outcome<()>> {
+    if !dry_run {
+        privileged::run_commands(&[HelperCommand::Apt { action, packages: packages.to_vec() }])?;
+        clear_search_cache();
+        return Ok(Outcome::Done(()));
+    }
+    
+    let mut args: Vec<&str> = action.apt_args().to_vec();
+    args.extend(packages.iter().map(String::as_str));
+    let output = Command::new("apt-get")
+        .arg("-s")
+        .args(&args)
+        .output()
+        .context("Failed to run apt-get")?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("apt-get -s {} failed ({}): {}", args.join(" "), output.status, stderr.trim());
+    }
+    
+    let changes = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| ["Inst ", "Remv ", "Purg "].iter().any(|prefix| line.starts_with(prefix)))
+        .map(|line| line.to_string())
+        .collect();
+    
+    Ok(Outcome::Simulated(changes))
+}
+
+/// Count installed Snap packages
+pub fn get_snap_count() -> usize {
+    if let Ok(output) = Command::new("snap")
+        .args(["list"])
+        .output()
+    {
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .skip(1) // Skip header
+            .count()
+    } else {
+        0
+    }
+}
+
+/// Count installed Flatpak applications
+pub fn get_flatpak_count() -> usize {
+    if let Ok(output) = Command::new("flatpak")
+        .args(["list", "--app"])
+        .output()
+    {
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .count()
+    } else {
+        0
+    }
+}
+
+/// List Flatpak applications and runtimes with pending updates
+pub fn list_flatpak_updates() -> Result<Vec<PackageInfo>> {
+    let output = Command::new("flatpak")
+        .args(["remote-ls", "--updates", "--columns=application,version,name"])
+        .output()
+        .context("Failed to run flatpak")?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("flatpak remote-ls failed ({}): {}", output.status, stderr.trim());
+    }
+    
+    let mut packages = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if let Some(application) = fields.first().filter(|a| !a.trim().is_empty()) {
+            packages.push(PackageInfo {
+                name: application.trim().to_string(),
+                version: fields.get(1).unwrap_or(&"").trim().to_string(),
+                description: fields.get(2).unwrap_or(&"").trim().to_string(),
+            });
+        }
+    }
+    
+    Ok(packages)
+}
+
+/// Refresh all Snap packages (requires sudo)
+pub fn update_snaps() -> Result<()> {
+    if !binary_in_path("snap") {
+        anyhow::bail!("Snap is not installed");
+    }
+    privileged::run_commands(&[HelperCommand::SnapRefresh])
+}
+
+/// Update all Flatpak applications and runtimes
+/// 
+/// Flatpak asks polkit for system installations itself, so no pkexec is needed.
+pub fn update_flatpaks() -> Result<()> {
+    if !binary_in_path("flatpak") {
+        anyhow::bail!("Flatpak is not installed");
+    }
+    
+    let output = Command::new("flatpak")
+        .args(["update", "-y", "--noninteractive"])
+        .output()
+        .context("Failed to run flatpak")?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("flatpak update failed ({}): {}", output.status, stderr.trim());
+    }
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Three runs as logged by apt, unattended-upgrades (no Commandline) and apt again
+    const HISTORY_LOG: &str = "
+Start-Date: 2024-01-15  10:23:45
+Commandline: apt install htop
+Requested-By: alice (1000)
+Install: libnl-genl-3-200:amd64 (3.5.0-0.1, automatic), htop:amd64 (3.0.5-7build2)
+End-Date: 2024-01-15  10:23:50
+
+Start-Date: 2024-01-16  06:12:01
+Upgrade: libssl3:amd64 (3.0.2-0ubuntu1.12, 3.0.2-0ubuntu1.14), openssl:amd64 (3.0.2-0ubuntu1.12, 3.0.2-0ubuntu1.14)
+End-Date: 2024-01-16  06:12:09
+
+Start-Date: 2024-01-17  18:40:02
+Commandline: apt-get install --allow-downgrades firefox=1:119.0+build2-0ubuntu1
+Requested-By: alice (1000)
+Install: libfoo:amd64 (1.0, automatic)
+Downgrade: firefox:amd64 (1:120.0+build2-0ubuntu1, 1:119.0+build2-0ubuntu1)
+Remove: oldpkg:amd64 (2.1-1)
+End-Date: 2024-01-17  18:40:30
+";
+    
+    #[test]
+    fn parses_multi_entry_history_log() {
+        let transactions = parse_apt_history(HISTORY_LOG);
+        let summary: Vec<(&str, HistoryAction, usize)> = transactions.iter()
+            .map(|t| (t.start_time.as_str(), t.action, t.packages.len()))
+            .collect();
+        assert_eq!(summary, [
+            ("2024-01-15 10:23:45", HistoryAction::Install, 2),
+            ("2024-01-16 06:12:01", HistoryAction::Upgrade, 2),
+            ("2024-01-17 18:40:02", HistoryAction::Install, 1),
+            ("2024-01-17 18:40:02", HistoryAction::Downgrade, 1),
+            ("2024-01-17 18:40:02", HistoryAction::Remove, 1),
+        ]);
+        
+        let install = &transactions[0];
+        assert_eq!(install.command, "apt install htop");
+        assert_eq!(install.requested_by.as_deref(), Some("alice (1000)"));
+        assert_eq!(install.packages[0], HistoryPackage {
+            name: "libnl-genl-3-200".to_string(),
+            arch: Some("amd64".to_string()),
+            from_version: None,
+            to_version: Some("3.5.0-0.1".to_string()),
+        });
+        assert_eq!(install.packages[1].name, "htop");
+        assert_eq!(install.packages[1].to_version.as_deref(), Some("3.0.5-7build2"));
+        
+        // Fields of the previous run don't leak into one without a Commandline
+        let upgrade = &transactions[1];
+        assert_eq!(upgrade.command, "");
+        assert_eq!(upgrade.requested_by, None);
+        assert_eq!(upgrade.packages[1], HistoryPackage {
+            name: "openssl".to_string(),
+            arch: Some("amd64".to_string()),
+            from_version: Some("3.0.2-0ubuntu1.12".to_string()),
+            to_version: Some("3.0.2-0ubuntu1.14".to_string()),
+        });
+        
+        // Versions with epochs keep their colon
+        let downgrade = &transactions[3];
+        assert_eq!(downgrade.packages[0].from_version.as_deref(), Some("1:120.0+build2-0ubuntu1"));
+        assert_eq!(downgrade.packages[0].to_version.as_deref(), Some("1:119.0+build2-0ubuntu1"));
+        
+        let remove = &transactions[4];
+        assert_eq!(remove.packages[0].from_version.as_deref(), Some("2.1-1"));
+        assert_eq!(remove.packages[0].to_version, None);
+    }
+    
+    #[test]
+    fn parses_apt_list_upgradable() {
+        let output = "\
+Listing... Done
+firefox/jammy-updates 120.0+build2-0ubuntu0.22.04.1 amd64 [upgradable from: 119.0+build2-0ubuntu0.22.04.1]
+libc6:i386/jammy-updates,jammy-security 2.35-0ubuntu3.6 i386 [upgradable from: 2.35-0ubuntu3.5]
+tzdata/jammy-updates 2024a-0ubuntu0.22.04 all [upgradable from: 2023c-0ubuntu0.22.04.2]
+";
+        let packages: Vec<(String, String, String)> = output.lines()
+            .filter_map(parse_upgradable_line)
+            .map(|p| (p.name, p.current_version, p.available_version))
+            .collect();
+        
+        let expected = [
+            ("firefox", "119.0+build2-0ubuntu0.22.04.1", "120.0+build2-0ubuntu0.22.04.1"),
+            ("libc6:i386", "2.35-0ubuntu3.5", "2.35-0ubuntu3.6"),
+            ("tzdata", "2023c-0ubuntu0.22.04.2", "2024a-0ubuntu0.22.04"),
+        ];
+        assert_eq!(packages.len(), expected.len());
+        for (package, (name, current, available)) in packages.iter().zip(expected) {
+            assert_eq!((package.0.as_str(), package.1.as_str(), package.2.as_str()), (name, current, available));
+        }
+    }
+    
+    #[test]
+    fn parses_residual_configs_from_dpkg_list() {
+        let output = "\
+Desired=Unknown/Install/Remove/Purge/Hold
+| Status=Not/Inst/Conf-files/Unpacked/halF-conf/Half-inst/trig-aWait/Trig-pend
+|/ Err?=(none)/Reinst-required (Status,Err: uppercase=bad)
+||/ Name                 Version           Architecture Description
++++-====================-=================-============-=================================
+ii  adduser              3.118ubuntu5      all          add and remove users and groups
+rc  libfoo1:amd64        1.2-3             amd64        foo runtime library
+ii  htop                 3.0.5-7build2     amd64        interactive processes viewer
+rc  linux-image-6.2.0-39-generic 6.2.0-39.40~22.04.1 amd64 Signed kernel image generic
+iU  half-installed       1.0               amd64        not configured yet
+";
+        let packages = parse_residual_configs(output);
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["libfoo1:amd64", "linux-image-6.2.0-39-generic"]);
+        assert_eq!(packages[0].version, "1.2-3");
+        assert_eq!(packages[0].description, "foo runtime library");
+        assert_eq!(packages[1].version, "6.2.0-39.40~22.04.1");
     }
 }
@@ -0,0 +1,50 @@
+//! SysMate privileged helper
+//! 
+//! Started by SysMate through pkexec and run as root for the rest of the
+//! session. Reads one command per line on stdin and answers each on stdout
+//! with `ok` or `error <message>`; commands that report output answer
+//! `ok <output>`, encoded as one word. Commands prefixed with `stream ` first
+//! send each line they print as `line <text>`. Only the operations in
+//! `HelperCommand` are carried out; every other line is refused. Exits when
+//! stdin is closed.
+
+use privileged::{HelperCommand, HELPER_READY, STREAM_PREFIX};
+use std::io::{self, BufRead, Write};
+
+fn main() -> anyhow::Result<()> {
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{}", HELPER_READY)?;
+    stdout.flush()?;
+    
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        
+        let (streamed, line) = match line.strip_prefix(STREAM_PREFIX) {
+            Some(rest) => (true, rest),
+            None => (false, line.as_str()),
+        };
+        
+        let result = line.parse::<HelperCommand>().and_then(|command| {
+            if streamed {
+                command.execute_streaming(|output| {
+                    let _ = writeln!(stdout, "line {}", privileged::encode_word(&output))
+                        .and_then(|_| stdout.flush());
+                })?;
+                return Ok(None);
+            }
+            let output = command.execute_with_output()?;
+            Ok(command.returns_output().then_some(output))
+        });
+        match result {
+            Ok(None) => writeln!(stdout, "ok")?,
+            Ok(Some(output)) => writeln!(stdout, "ok {}", privileged::encode_word(&output))?,
+            Err(e) => writeln!(stdout, "error {}", privileged::encode_word(&format!("{:#}", e)))?,
+        }
+        stdout.flush()?;
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,1060 @@
+//! Privileged Operations
+//! 
+//! Runs commands as root through pkexec, batching several commands into a
+//! single authentication prompt.
+//! 
+//! The operations SysMate may run as root are listed in `HelperCommand`. They
+//! are carried out by `sysmate-helper`, started once through pkexec and kept
+//! running for the rest of the session, so later commands need no new prompt.
+//! The helper reads one command per line on stdin and answers each with `ok`
+//! (followed by the output, for commands that report one) or `error <message>`;
+//! anything it can't parse into a `HelperCommand` is refused. A command sent
+//! as `stream <command>` is first answered with one `line <text>` per line it
+//! prints. When the helper is not installed, commands fall back to one batched
+//! pkexec call each.
+
+use anyhow::{Context, Result};
+use std::fmt;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, ExitStatus, Output, Stdio};
+use std::str::FromStr;
+use std::sync::{mpsc, Mutex, TryLockError};
+use std::thread;
+
+/// Error returned when the pkexec authentication dialog is dismissed or fails
+#[derive(Debug, thiserror::Error)]
+#[error("Authentication was cancelled or not authorized")]
+pub struct AuthenticationDismissed;
+
+/// Result of a destructive operation that may only have been simulated
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome<T> {
+    /// The operation ran
+    Done(T),
+    /// Dry run: the changes the operation would make, one per line
+    Simulated(Vec<String>),
+}
+
+/// Run a sequence of commands as root, asking for authentication once
+/// 
+/// Each command is a program followed by its arguments. They are joined into
+/// one `pkexec sh -c` script with every argument single-quoted, so nothing in
+/// them is interpreted by the shell. The script stops at the first command
+/// that fails; its stderr is returned in the error. A dismissed or refused
+/// prompt is reported as `AuthenticationDismissed`.
+pub fn run_privileged(commands: &[Vec<String>]) -> Result<()> {
+    let commands: Vec<&Vec<String>> = commands.iter()
+        .filter(|command| !command.is_empty())
+        .collect();
+    if commands.is_empty() {
+        return Ok(());
+    }
+    
+    let script = build_script(&commands);
+    let output = Command::new("pkexec")
+        .args(["/bin/sh", "-c", &script])
+        .output()
+        .context("Failed to run pkexec")?;
+    
+    if output.status.success() {
+        return Ok(());
+    }
+    
+    // Failing commands exit the script with 1, so 126/127 can only come from pkexec
+    match output.status.code() {
+        Some(126) | Some(127) => Err(AuthenticationDismissed.into()),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Privileged command failed ({}): {}", output.status, stderr.trim())
+        }
+    }
+}
+
+/// Like `run_privileged`, passing every line the commands print to `on_line`
+/// 
+/// stdout and stderr lines are forwarded as they arrive, so this blocks until
+/// the last command finishes.
+fn run_privileged_streaming(commands: &[Vec<String>], on_line: &mut dyn FnMut(String)) -> Result<()> {
+    let commands: Vec<&Vec<String>> = commands.iter()
+        .filter(|command| !command.is_empty())
+        .collect();
+    if commands.is_empty() {
+        return Ok(());
+    }
+    
+    let script = build_script(&commands);
+    let mut child = Command::new("pkexec")
+        .args(["/bin/sh", "-c", &script])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run pkexec")?;
+    let status = stream_child(&mut child, on_line)?;
+    
+    if status.success() {
+        return Ok(());
+    }
+    
+    // Failing commands exit the script with 1, so 126/127 can only come from pkexec
+    match status.code() {
+        Some(126) | Some(127) => Err(AuthenticationDismissed.into()),
+        _ => anyhow::bail!("Privileged command failed ({})", status),
+    }
+}
+
+/// Wait for a child with piped stdout and stderr, passing each line to `on_line`
+fn stream_child(child: &mut Child, on_line: &mut dyn FnMut(String)) -> Result<ExitStatus> {
+    let stdout = child.stdout.take().context("Missing stdout pipe")?;
+    let stderr = child.stderr.take().context("Missing stderr pipe")?;
+    
+    // Read both pipes concurrently so neither can fill up and stall the child
+    let (tx, rx) = mpsc::channel();
+    let tx_err = tx.clone();
+    let stdout_reader = thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(|l| l.ok()) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let stderr_reader = thread::spawn(move || {
+        for line in BufReader::new(stderr).lines().map_while(|l| l.ok()) {
+            if tx_err.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    
+    // Ends once both readers have hit EOF and dropped their senders
+    for line in rx {
+        on_line(line);
+    }
+    
+    let _ = stdout_reader.join();
+    let _ = stderr_reader.join();
+    
+    Ok(child.wait()?)
+}
+
+/// Join commands into a shell script that stops at the first failure
+fn build_script(commands: &[&Vec<String>]) -> String {
+    commands.iter()
+        .map(|command| {
+            let quoted: Vec<String> = command.iter().map(|arg| shell_quote(arg)).collect();
+            format!("{} || exit 1\n", quoted.join(" "))
+        })
+        .collect()
+}
+
+/// Quote an argument for the shell
+/// 
+/// Inside single quotes nothing is special except the quote itself, which is
+/// written as `'\''`.
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// An apt-get action the helper may run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AptAction {
+    Update,
+    Upgrade,
+    Install,
+    /// Install `name=version` packages, allowing downgrades
+    InstallVersion,
+    /// Upgrade the given packages only, never installing new ones
+    OnlyUpgrade,
+    Remove,
+    Autoremove,
+    /// Remove packages along with their configuration (used for old kernels)
+    Purge,
+    Clean,
+}
+
+impl AptAction {
+    const ALL: [AptAction; 9] = [
+        AptAction::Update,
+        AptAction::Upgrade,
+        AptAction::Install,
+        AptAction::InstallVersion,
+        AptAction::OnlyUpgrade,
+        AptAction::Remove,
+        AptAction::Autoremove,
+        AptAction::Purge,
+        AptAction::Clean,
+    ];
+    
+    /// Name used in the helper protocol
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AptAction::Update => "update",
+            AptAction::Upgrade => "upgrade",
+            AptAction::Install => "install",
+            AptAction::InstallVersion => "install-version",
+            AptAction::OnlyUpgrade => "only-upgrade",
+            AptAction::Remove => "remove",
+            AptAction::Autoremove => "autoremove",
+            AptAction::Purge => "purge",
+            AptAction::Clean => "clean",
+        }
+    }
+    
+    /// apt-get arguments for this action, before the package names
+    pub fn apt_args(&self) -> &'static [&'static str] {
+        match self {
+            AptAction::Update => &["update"],
+            AptAction::Upgrade => &["upgrade"],
+            AptAction::Install => &["install"],
+            AptAction::InstallVersion => &["install", "--allow-downgrades"],
+            AptAction::OnlyUpgrade => &["install", "--only-upgrade"],
+            AptAction::Remove => &["remove"],
+            AptAction::Autoremove => &["autoremove"],
+            AptAction::Purge => &["purge"],
+            AptAction::Clean => &["clean"],
+        }
+    }
+    
+    /// Whether the action needs at least one package name
+    fn takes_packages(&self) -> bool {
+        matches!(self, AptAction::Install | AptAction::InstallVersion | AptAction::OnlyUpgrade | AptAction::Remove | AptAction::Purge)
+    }
+}
+
+/// A systemctl action the helper may run on a unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemctlAction {
+    Start,
+    Stop,
+    Restart,
+    Enable,
+    Disable,
+    Unmask,
+    /// Clear the failed state so the unit can be started cleanly
+    ResetFailed,
+}
+
+impl SystemctlAction {
+    const ALL: [SystemctlAction; 7] = [
+        SystemctlAction::Start,
+        SystemctlAction::Stop,
+        SystemctlAction::Restart,
+        SystemctlAction::Enable,
+        SystemctlAction::Disable,
+        SystemctlAction::Unmask,
+        SystemctlAction::ResetFailed,
+    ];
+    
+    /// The systemctl verb, also used in the helper protocol
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SystemctlAction::Start => "start",
+            SystemctlAction::Stop => "stop",
+            SystemctlAction::Restart => "restart",
+            SystemctlAction::Enable => "enable",
+            SystemctlAction::Disable => "disable",
+            SystemctlAction::Unmask => "unmask",
+            SystemctlAction::ResetFailed => "reset-failed",
+        }
+    }
+}
+
+/// An apt-mark action the helper may run on packages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AptMarkAction {
+    /// Keep the package at its installed version
+    Hold,
+    Unhold,
+}
+
+impl AptMarkAction {
+    const ALL: [AptMarkAction; 2] = [AptMarkAction::Hold, AptMarkAction::Unhold];
+    
+    /// The apt-mark verb, also used in the helper protocol
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AptMarkAction::Hold => "hold",
+            AptMarkAction::Unhold => "unhold",
+        }
+    }
+}
+
+/// How far to shrink the systemd journal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalLimit {
+    /// Remove archived entries older than this many days
+    Days(u32),
+    /// Remove archived entries until the journal fits in this many bytes
+    Bytes(u64),
+}
+
+/// Directories under which `HelperCommand::Delete` may remove files
+pub const DELETE_ROOTS: &[&str] = &["/tmp", "/var/tmp", "/var/log"];
+
+/// Where the kernel lists backlight devices
+pub const BACKLIGHT_DIR: &str = "/sys/class/backlight";
+
+/// An operation `sysmate-helper` carries out as root
+/// 
+/// This is the full list of what the helper will do. On the wire each command
+/// is one line of space-separated words, e.g. `apt install htop`,
+/// `service restart cups.service` or `clean journal-days 7`; see `FromStr`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HelperCommand {
+    /// `apt-get -y <action> <packages>`
+    Apt { action: AptAction, packages: Vec<String> },
+    /// `dpkg --purge <packages>`
+    DpkgPurge(Vec<String>),
+    /// `apt-mark <action> <packages>`
+    AptMark { action: AptMarkAction, packages: Vec<String> },
+    /// `snap refresh`
+    SnapRefresh,
+    /// `systemctl <action> <unit>` on the system manager
+    Service { action: SystemctlAction, unit: String },
+    /// `journalctl --vacuum-time` or `--vacuum-size`
+    VacuumJournal(JournalLimit),
+    /// `docker system prune -f`
+    PruneDocker,
+    /// `rm -rf` on paths below one of `DELETE_ROOTS`
+    Delete(Vec<PathBuf>),
+    /// Write `brightness` to a device in `BACKLIGHT_DIR`
+    SetBacklight { device: String, brightness: u32 },
+    /// `smartctl -a /dev/<device>`, reporting its output
+    SmartHealth(String),
+}
+
+impl HelperCommand {
+    /// The program and arguments that carry out this command
+    pub fn argv(&self) -> Vec<String> {
+        let words: Vec<&str> = match self {
+            HelperCommand::Apt { action, packages } => {
+                let mut words = vec!["apt-get", "-y"];
+                words.extend_from_slice(action.apt_args());
+                words.extend(packages.iter().map(String::as_str));
+                words
+            }
+            HelperCommand::DpkgPurge(packages) => {
+                let mut words = vec!["dpkg", "--purge"];
+                words.extend(packages.iter().map(String::as_str));
+                words
+            }
+            HelperCommand::AptMark { action, packages } => {
+                let mut words = vec!["apt-mark", action.as_str()];
+                words.extend(packages.iter().map(String::as_str));
+                words
+            }
+            HelperCommand::SnapRefresh => vec!["snap", "refresh"],
+            HelperCommand::Service { action, unit } => vec!["systemctl", action.as_str(), unit],
+            HelperCommand::VacuumJournal(limit) => {
+                let arg = match limit {
+                    JournalLimit::Days(days) => format!("--vacuum-time={}d", days),
+                    JournalLimit::Bytes(bytes) => format!("--vacuum-size={}", bytes),
+                };
+                return vec!["journalctl".to_string(), arg];
+            }
+            HelperCommand::PruneDocker => vec!["docker", "system", "prune", "-f"],
+            HelperCommand::Delete(paths) => {
+                let mut argv = vec!["rm".to_string(), "-rf".to_string(), "--".to_string()];
+                argv.extend(paths.iter().map(|path| path.to_string_lossy().to_string()));
+                return argv;
+            }
+            HelperCommand::SetBacklight { device, brightness } => {
+                // Value and path are passed as arguments, never spliced into the script
+                return vec![
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    r#"echo "$1" > "$2""#.to_string(),
+                    "sh".to_string(),
+                    brightness.to_string(),
+                    format!("{}/{}/brightness", BACKLIGHT_DIR, device),
+                ];
+            }
+            HelperCommand::SmartHealth(device) => {
+                return vec!["smartctl".to_string(), "-a".to_string(), format!("/dev/{}", device)];
+            }
+        };
+        words.into_iter().map(String::from).collect()
+    }
+    
+    /// Whether the helper sends the command's output back
+    pub fn returns_output(&self) -> bool {
+        matches!(self, HelperCommand::SmartHealth(_))
+    }
+    
+    /// Run the command in this process, which is expected to be root
+    /// 
+    /// Used by `sysmate-helper`. A non-zero exit becomes an error carrying the
+    /// command's stderr.
+    pub fn execute(&self) -> Result<()> {
+        self.execute_with_output().map(|_| ())
+    }
+    
+    /// Like `execute`, returning what the command printed on stdout
+    pub fn execute_with_output(&self) -> Result<String> {
+        self.check_filesystem()?;
+        
+        let argv = self.argv();
+        let output = Command::new(&argv[0])
+            .args(&argv[1..])
+            .output()
+            .with_context(|| format!("Failed to run {}", argv[0]))?;
+        
+        self.check_status(&output)?;
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+    
+    /// Like `execute`, passing every stdout and stderr line to `on_line` as it is printed
+    pub fn execute_streaming(&self, mut on_line: impl FnMut(String)) -> Result<()> {
+        self.check_filesystem()?;
+        
+        let argv = self.argv();
+        let mut child = Command::new(&argv[0])
+            .args(&argv[1..])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to run {}", argv[0]))?;
+        let status = stream_child(&mut child, &mut on_line)?;
+        
+        if self.failed(status) {
+            anyhow::bail!("{} failed ({})", argv.join(" "), status);
+        }
+        Ok(())
+    }
+    
+    /// Checks that can only be made as root, right before running
+    fn check_filesystem(&self) -> Result<()> {
+        if let HelperCommand::Delete(paths) = self {
+            for path in paths {
+                ensure_safe_to_delete(path)?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Whether the command's exit status means it failed
+    /// 
+    /// smartctl's exit status is a bit mask: only the lowest two bits mean the
+    /// device couldn't be read, the others report problems it found on the disk.
+    fn failed(&self, status: ExitStatus) -> bool {
+        match self {
+            HelperCommand::SmartHealth(_) => status.code().is_none_or(|code| code & 0b11 != 0),
+            _ => !status.success(),
+        }
+    }
+    
+    /// Turn a failed exit into an error carrying stderr
+    fn check_status(&self, output: &Output) -> Result<()> {
+        if self.failed(output.status) {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            // smartctl explains its failures on stdout
+            let message = if stderr.trim().is_empty() { stdout } else { stderr };
+            anyhow::bail!("{} failed ({}): {}", self.argv().join(" "), output.status, message.trim());
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for HelperCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut words: Vec<String> = match self {
+            HelperCommand::Apt { action, packages } => {
+                let mut words = vec!["apt".to_string(), action.as_str().to_string()];
+                words.extend(packages.iter().cloned());
+                words
+            }
+            HelperCommand::DpkgPurge(packages) => {
+                let mut words = vec!["dpkg".to_string(), "purge".to_string()];
+                words.extend(packages.iter().cloned());
+                words
+            }
+            HelperCommand::AptMark { action, packages } => {
+                let mut words = vec!["apt-mark".to_string(), action.as_str().to_string()];
+                words.extend(packages.iter().cloned());
+                words
+            }
+            HelperCommand::SnapRefresh => vec!["snap".to_string(), "refresh".to_string()],
+            HelperCommand::Service { action, unit } => {
+                vec!["service".to_string(), action.as_str().to_string(), unit.clone()]
+            }
+            HelperCommand::VacuumJournal(JournalLimit::Days(days)) => {
+                vec!["clean".to_string(), "journal-days".to_string(), days.to_string()]
+            }
+            HelperCommand::VacuumJournal(JournalLimit::Bytes(bytes)) => {
+                vec!["clean".to_string(), "journal-size".to_string(), bytes.to_string()]
+            }
+            HelperCommand::PruneDocker => vec!["clean".to_string(), "docker".to_string()],
+            HelperCommand::Delete(paths) => {
+                let mut words = vec!["delete".to_string()];
+                words.extend(paths.iter().map(|path| path.to_string_lossy().to_string()));
+                words
+            }
+            HelperCommand::SetBacklight { device, brightness } => {
+                vec!["backlight".to_string(), device.clone(), brightness.to_string()]
+            }
+            HelperCommand::SmartHealth(device) => vec!["smart".to_string(), device.clone()],
+        };
+        
+        for word in &mut words {
+            *word = encode_word(word);
+        }
+        write!(f, "{}", words.join(" "))
+    }
+}
+
+/// Parse one line of the helper protocol, refusing anything not allowed
+/// 
+/// Package and unit names must use the characters Debian and systemd allow in
+/// names, so they can't be taken for options or paths, and deleted paths must
+/// be absolute, free of `..` and strictly below one of `DELETE_ROOTS`.
+impl FromStr for HelperCommand {
+    type Err = anyhow::Error;
+    
+    fn from_str(line: &str) -> Result<Self> {
+        let words: Vec<String> = line.split_whitespace()
+            .map(decode_word)
+            .collect::<Result<_>>()?;
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        
+        let command = match words.as_slice() {
+            ["apt", action, packages @ ..] => {
+                let action = AptAction::ALL.into_iter()
+                    .find(|a| a.as_str() == *action)
+                    .with_context(|| format!("Unknown apt action '{}'", action))?;
+                if action.takes_packages() == packages.is_empty() {
+                    anyhow::bail!("Wrong arguments for apt {}", action.as_str());
+                }
+                let packages = if action == AptAction::InstallVersion {
+                    packages.iter().map(|word| versioned_package(word)).collect::<Result<_>>()?
+                } else {
+                    packages.iter().map(|word| package(word)).collect::<Result<_>>()?
+                };
+                HelperCommand::Apt { action, packages }
+            }
+            ["dpkg", "purge", packages @ ..] if !packages.is_empty() => {
+                HelperCommand::DpkgPurge(packages.iter().map(|word| package(word)).collect::<Result<_>>()?)
+            }
+            ["apt-mark", action, packages @ ..] if !packages.is_empty() => {
+                let action = AptMarkAction::ALL.into_iter()
+                    .find(|a| a.as_str() == *action)
+                    .with_context(|| format!("Unknown apt-mark action '{}'", action))?;
+                HelperCommand::AptMark { action, packages: packages.iter().map(|word| package(word)).collect::<Result<_>>()? }
+            }
+            ["snap", "refresh"] => HelperCommand::SnapRefresh,
+            ["service", action, unit] => {
+                let action = SystemctlAction::ALL.into_iter()
+                    .find(|a| a.as_str() == *action)
+                    .with_context(|| format!("Unknown service action '{}'", action))?;
+                HelperCommand::Service { action, unit: unit_name(unit)? }
+            }
+            ["clean", "journal-days", days] => {
+                HelperCommand::VacuumJournal(JournalLimit::Days(days.parse()?))
+            }
+            ["clean", "journal-size", bytes] => {
+                HelperCommand::VacuumJournal(JournalLimit::Bytes(bytes.parse()?))
+            }
+            ["clean", "docker"] => HelperCommand::PruneDocker,
+            ["delete", paths @ ..] if !paths.is_empty() => {
+                HelperCommand::Delete(paths.iter().map(|path| deletable(path)).collect::<Result<_>>()?)
+            }
+            ["backlight", device, brightness] => {
+                HelperCommand::SetBacklight { device: backlight_device(device)?, brightness: brightness.parse()? }
+            }
+            ["smart", device] => HelperCommand::SmartHealth(block_device(device)?),
+            _ => anyhow::bail!("Unknown command '{}'", line.trim()),
+        };
+        
+        Ok(command)
+    }
+}
+
+/// Check a Debian package name, optionally qualified with `:arch`
+/// 
+/// Names start with a lowercase letter or digit and contain only lowercase
+/// letters, digits, `+`, `-` and `.`, so they can't be taken for an option or
+/// a path to a local .deb.
+fn package(word: &str) -> Result<String> {
+    let (name, arch) = match word.split_once(':') {
+        Some((name, arch)) => (name, Some(arch)),
+        None => (word, None),
+    };
+    let valid_name = name.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "+-.".contains(c));
+    let valid_arch = arch.is_none_or(|arch| {
+        !arch.is_empty() && arch.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    });
+    if !valid_name || !valid_arch {
+        anyhow::bail!("Invalid package name '{}'", word);
+    }
+    Ok(word.to_string())
+}
+
+/// Check a `name=version` package, validating both parts
+/// 
+/// Debian versions start with a digit and contain only letters, digits and
+/// `.+~:-`.
+fn versioned_package(word: &str) -> Result<String> {
+    let (name, version) = word.split_once('=')
+        .with_context(|| format!("Expected name=version, got '{}'", word))?;
+    package(name)?;
+    let valid_version = version.starts_with(|c: char| c.is_ascii_digit())
+        && version.chars().all(|c| c.is_ascii_alphanumeric() || ".+~:-".contains(c));
+    if !valid_version {
+        anyhow::bail!("Invalid package version '{}'", version);
+    }
+    Ok(word.to_string())
+}
+
+/// Check a systemd unit name such as `cups.service` or `getty@tty1.service`
+/// 
+/// Allows the characters systemd uses in unit names except the `\` of its
+/// escapes, and refuses a leading `-` or `.`.
+fn unit_name(word: &str) -> Result<String> {
+    let valid = word.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && word.chars().all(|c| c.is_ascii_alphanumeric() || ":-_.@".contains(c));
+    if !valid {
+        anyhow::bail!("Invalid unit name '{}'", word);
+    }
+    Ok(word.to_string())
+}
+
+/// Check a backlight device name, so it can't point outside `BACKLIGHT_DIR`
+fn backlight_device(word: &str) -> Result<String> {
+    let valid = !word.is_empty()
+        && !word.starts_with('.')
+        && word.chars().all(|c| c.is_ascii_alphanumeric() || "-_:.".contains(c));
+    if !valid {
+        anyhow::bail!("Invalid backlight device '{}'", word);
+    }
+    Ok(word.to_string())
+}
+
+/// Check a block device name such as `sda` or `nvme0n1`, so it stays in /dev
+fn block_device(word: &str) -> Result<String> {
+    if word.is_empty() || !word.chars().all(|c| c.is_ascii_alphanumeric()) {
+        anyhow::bail!("Invalid block device '{}'", word);
+    }
+    Ok(word.to_string())
+}
+
+/// Check that a path may be deleted by the helper
+/// 
+/// Only looks at the path itself; `ensure_safe_to_delete` checks the
+/// filesystem right before deleting. The path is rebuilt from its components,
+/// dropping a trailing `/` that would make `rm` follow a symlink.
+fn deletable(word: &str) -> Result<PathBuf> {
+    let path = Path::new(word);
+    let normal = path.is_absolute()
+        && path.components().all(|c| matches!(c, Component::RootDir | Component::Normal(_)));
+    let allowed = DELETE_ROOTS.iter()
+        .any(|root| path.starts_with(root) && path != Path::new(root));
+    
+    if !normal || !allowed {
+        anyhow::bail!("Refusing to delete {}", path.display());
+    }
+    Ok(path.components().collect())
+}
+
+/// Check, as root and just before deleting, that `path` really lies below its delete root
+/// 
+/// Anyone can create `/tmp/x/link -> /etc`, which makes `/tmp/x/link/shadow`
+/// pass `deletable`. So the parent must resolve to itself, with no symlink on
+/// the way, and every directory between the root and the parent must belong to
+/// root, so its owner can't swap it for a symlink afterwards. The path itself
+/// may be a symlink: `rm` removes the link, not what it points to.
+fn ensure_safe_to_delete(path: &Path) -> Result<()> {
+    let refuse = || anyhow::anyhow!("Refusing to delete {}", path.display());
+    
+    let root = DELETE_ROOTS.iter()
+        .map(Path::new)
+        .find(|root| path.starts_with(root) && path != *root)
+        .ok_or_else(refuse)?;
+    let parent = path.parent().ok_or_else(refuse)?;
+    
+    let resolved = fs::canonicalize(parent)
+        .with_context(|| format!("Failed to resolve {}", parent.display()))?;
+    if resolved != parent {
+        return Err(refuse());
+    }
+    
+    for dir in parent.ancestors().take_while(|dir| *dir != root) {
+        let metadata = fs::symlink_metadata(dir)
+            .with_context(|| format!("Failed to read {}", dir.display()))?;
+        if metadata.uid() != 0 {
+            return Err(refuse());
+        }
+    }
+    
+    Ok(())
+}
+
+/// Check whether the helper would delete `path`
+/// 
+/// Callers use this to leave out paths the helper would refuse, such as logs in
+/// /var/log directories owned by a service user, since one refused path fails
+/// the whole `HelperCommand::Delete`.
+pub fn helper_may_delete(path: &Path) -> bool {
+    deletable(&path.to_string_lossy())
+        .and_then(|path| ensure_safe_to_delete(&path))
+        .is_ok()
+}
+
+/// Escape the characters that would split a protocol word or line
+pub fn encode_word(word: &str) -> String {
+    let mut encoded = String::with_capacity(word.len());
+    for c in word.chars() {
+        match c {
+            '%' | ' ' | '\t' | '\n' | '\r' => encoded.push_str(&format!("%{:02X}", c as u32)),
+            _ => encoded.push(c),
+        }
+    }
+    encoded
+}
+
+/// Undo `encode_word`
+fn decode_word(word: &str) -> Result<String> {
+    let mut bytes = Vec::with_capacity(word.len());
+    let mut rest = word.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail.get(..2)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .with_context(|| format!("Bad escape in '{}'", word))?;
+            bytes.push(hex);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).with_context(|| format!("Invalid UTF-8 in '{}'", word))
+}
+
+/// Reply the helper sends once it has started
+pub const HELPER_READY: &str = "ready";
+
+/// Prefix of a command whose output the helper should stream back
+pub const STREAM_PREFIX: &str = "stream ";
+
+/// Name of the helper binary
+const HELPER_NAME: &str = "sysmate-helper";
+
+/// Find the helper: next to the running executable, then the install location
+fn helper_path() -> Option<PathBuf> {
+    let beside_exe = std::env::current_exe()
+        .ok()
+        .map(|exe| exe.with_file_name(HELPER_NAME));
+    
+    beside_exe.into_iter()
+        .chain([PathBuf::from("/usr/libexec/sysmate").join(HELPER_NAME)])
+        .find(|path| path.is_file())
+}
+
+/// A running `sysmate-helper`, authenticated once through pkexec
+struct HelperSession {
+    /// Keeps its stdin; closing it tells the helper to exit
+    child: Child,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl HelperSession {
+    /// Start the helper and wait for it to be ready
+    /// 
+    /// This is when the authentication prompt appears. If pkexec exits before
+    /// the helper answers, a dismissed or refused prompt is reported as
+    /// `AuthenticationDismissed`.
+    fn spawn(path: &Path) -> Result<Self> {
+        let mut child = Command::new("pkexec")
+            .arg(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("Failed to run pkexec")?;
+        
+        let stdout = child.stdout.take().context("Helper has no stdout")?;
+        let mut session = Self { child, stdout: BufReader::new(stdout) };
+        
+        match session.read_reply()? {
+            Some(reply) if reply == HELPER_READY => Ok(session),
+            Some(reply) => anyhow::bail!("Unexpected reply from {}: {}", HELPER_NAME, reply),
+            None => {
+                let status = session.child.wait()?;
+                match status.code() {
+                    Some(126) | Some(127) => Err(AuthenticationDismissed.into()),
+                    _ => anyhow::bail!("{} exited before it was ready ({})", HELPER_NAME, status),
+                }
+            }
+        }
+    }
+    
+    /// Send one command and wait for its reply, returning the output it carries
+    /// 
+    /// With `on_line` the command is sent as `stream <command>`, and each line
+    /// it prints is passed on while waiting.
+    fn send(&mut self, command: &HelperCommand, mut on_line: Option<&mut (dyn FnMut(String) + '_)>) -> Result<String> {
+        let stdin: &mut ChildStdin = self.child.stdin.as_mut()
+            .with_context(|| format!("{} has no stdin", HELPER_NAME))?;
+        let prefix = if on_line.is_some() { STREAM_PREFIX } else { "" };
+        writeln!(stdin, "{}{}", prefix, command)
+            .and_then(|_| stdin.flush())
+            .with_context(|| format!("{} is not running", HELPER_NAME))?;
+        
+        let mut reply = self.read_reply()?;
+        while let Some(line) = reply.as_deref().and_then(|reply| reply.strip_prefix("line ")) {
+            if let Some(on_line) = on_line.as_mut() {
+                on_line(decode_word(line)?);
+            }
+            reply = self.read_reply()?;
+        }
+        
+        match reply {
+            Some(reply) if reply == "ok" => Ok(String::new()),
+            Some(reply) if reply.starts_with("ok ") => decode_word(&reply[3..]),
+            Some(reply) => match reply.strip_prefix("error ") {
+                Some(message) => anyhow::bail!("{}", decode_word(message)?),
+                None => anyhow::bail!("Unexpected reply from {}: {}", HELPER_NAME, reply),
+            },
+            None => anyhow::bail!("{} exited unexpectedly", HELPER_NAME),
+        }
+    }
+    
+    /// Read one reply line, or `None` once the helper has exited
+    fn read_reply(&mut self) -> Result<Option<String>> {
+        let mut line = String::new();
+        if self.stdout.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line.trim_end().to_string()))
+    }
+    
+    fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+}
+
+impl Drop for HelperSession {
+    /// Closing stdin ends the helper; reap it so no zombie is left behind
+    fn drop(&mut self) {
+        drop(self.child.stdin.take());
+        let _ = self.child.wait();
+    }
+}
+
+/// The helper started for this session, if any
+static SESSION: Mutex<Option<HelperSession>> = Mutex::new(None);
+
+/// Run commands as root, asking for authentication at most once per session
+/// 
+/// The first call starts `sysmate-helper` through pkexec; later calls reuse it
+/// without a new prompt. Commands run in order and stop at the first failure.
+/// Without an installed helper this falls back to `run_privileged`, after the
+/// same checks the helper would make.
+pub fn run_commands(commands: &[HelperCommand]) -> Result<()> {
+    if commands.is_empty() {
+        return Ok(());
+    }
+    
+    let Some(path) = helper_path() else {
+        let argvs = fallback_argvs(commands)?;
+        return run_privileged(&argvs);
+    };
+    
+    send_to_helper(&path, commands, None).map(|_| ())
+}
+
+/// Run commands as root like `run_commands`, passing every line they print to `on_line`
+/// 
+/// stdout and stderr lines arrive as they are printed, so this blocks until the
+/// last command finishes; call it off the main thread. Without an installed
+/// helper all commands run in one pkexec call.
+pub fn run_commands_streaming(commands: &[HelperCommand], mut on_line: impl FnMut(String)) -> Result<()> {
+    let Some(path) = helper_path() else {
+        let argvs = fallback_argvs(commands)?;
+        return run_privileged_streaming(&argvs, &mut on_line);
+    };
+    
+    send_to_helper(&path, commands, Some(&mut on_line)).map(|_| ())
+}
+
+/// Run one command as root and return what it printed
+/// 
+/// Goes through the helper like `run_commands`. Without an installed helper
+/// the command runs through pkexec directly, asking for authentication each time.
+pub fn run_command_output(command: &HelperCommand) -> Result<String> {
+    let Some(path) = helper_path() else {
+        let argv = fallback_argvs(std::slice::from_ref(command))?.remove(0);
+        let output = Command::new("pkexec")
+            .args(&argv)
+            .output()
+            .context("Failed to run pkexec")?;
+        if matches!(output.status.code(), Some(126) | Some(127)) {
+            return Err(AuthenticationDismissed.into());
+        }
+        command.check_status(&output)?;
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    };
+    
+    let mut outputs = send_to_helper(&path, std::slice::from_ref(command), None)?;
+    Ok(outputs.pop().unwrap_or_default())
+}
+
+/// Check commands as the helper would, then return what pkexec should run
+/// 
+/// Without the helper nothing stands between a command and root, so each one
+/// goes through the same protocol parsing and filesystem checks first.
+fn fallback_argvs(commands: &[HelperCommand]) -> Result<Vec<Vec<String>>> {
+    commands.iter()
+        .map(|command| {
+            let checked: HelperCommand = command.to_string().parse()?;
+            checked.check_filesystem()?;
+            Ok(checked.argv())
+        })
+        .collect()
+}
+
+/// Send commands to this session's helper, starting it first if needed
+/// 
+/// Fails straight away while another thread is using the helper.
+/// Returns the output of each command, in order. With `on_line`, the lines the
+/// commands print are streamed to it instead.
+fn send_to_helper(
+    path: &Path,
+    commands: &[HelperCommand],
+    mut on_line: Option<&mut dyn FnMut(String)>,
+) -> Result<Vec<String>> {
+    // A long command such as a streamed upgrade holds the session; callers on
+    // the main thread must get an error rather than freeze the window
+    let mut session = match SESSION.try_lock() {
+        Ok(session) => session,
+        Err(TryLockError::Poisoned(e)) => e.into_inner(),
+        Err(TryLockError::WouldBlock) => anyhow::bail!("Another privileged operation is still running"),
+    };
+    if !session.as_mut().is_some_and(HelperSession::is_running) {
+        *session = Some(HelperSession::spawn(path)?);
+    }
+    
+    let mut outputs = Vec::new();
+    for command in commands {
+        let Some(helper) = session.as_mut() else {
+            break;
+        };
+        match helper.send(command, on_line.as_deref_mut()) {
+            Ok(output) => outputs.push(output),
+            Err(e) => {
+                if !helper.is_running() {
+                    *session = None;
+                }
+                return Err(e);
+            }
+        }
+    }
+    
+    Ok(outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn parse(line: &str) -> Result<HelperCommand> {
+        line.parse()
+    }
+    
+    #[test]
+    fn accepts_package_and_unit_names() {
+        assert!(parse("apt install libstdc++6 python3.12 g++-13").is_ok());
+        assert!(parse("apt remove libc6:i386").is_ok());
+        assert!(parse("dpkg purge linux-image-6.5.0-14-generic").is_ok());
+        assert!(parse("apt-mark hold firefox").is_ok());
+        assert!(parse("service restart getty@tty1.service").is_ok());
+        assert!(parse("service stop systemd-journald.socket").is_ok());
+    }
+    
+    #[test]
+    fn refuses_paths_and_options_as_names() {
+        for line in [
+            "apt install ./evil.deb",
+            "apt install /home/u/x.deb",
+            "apt install ..%2Fevil.deb",
+            "apt install .hidden",
+            "apt install -oDebug=1",
+            "apt install Firefox",
+            "apt install htop=1.0",
+            "apt install libc6:",
+            "dpkg purge a\\b",
+            "apt-mark hold ../x",
+            "service start ../../etc/x.service",
+            "service start .hidden.service",
+            "service start -x",
+            "service start dev-disk-by\\x2duuid.device",
+        ] {
+            assert!(parse(line).is_err(), "accepted {}", line);
+        }
+    }
+    
+    #[test]
+    fn deletions_stay_below_their_root() {
+        assert_eq!(deletable("/tmp/a/b/").unwrap(), Path::new("/tmp/a/b"));
+        assert!(deletable("/tmp").is_err());
+        assert!(deletable("/tmp/../etc/shadow").is_err());
+        assert!(deletable("tmp/x").is_err());
+        assert!(deletable("/etc/shadow").is_err());
+        
+        // A user-made link inside /tmp leading elsewhere
+        let dir = std::env::temp_dir().join(format!("sysmate-delete-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let link = dir.join("link");
+        let _ = fs::remove_file(&link);
+        std::os::unix::fs::symlink("/etc", &link).unwrap();
+        
+        let result = ensure_safe_to_delete(&link.join("shadow"));
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(result.is_err());
+    }
+    
+    #[test]
+    fn refuses_deletions_below_directories_not_owned_by_root() {
+        let dir = std::env::temp_dir().join(format!("sysmate-owner-{}", std::process::id()));
+        let service_dir = dir.join("mysql");
+        fs::create_dir_all(&service_dir).unwrap();
+        // As root, hand the subdirectory to `nobody`; otherwise we own both already
+        let _ = std::os::unix::fs::chown(&service_dir, Some(65534), Some(65534));
+        
+        let below_service = helper_may_delete(&service_dir.join("error.log.1.gz"));
+        let dir_owned_by_root = fs::metadata(&dir).unwrap().uid() == 0;
+        let below_root_dir = helper_may_delete(&dir.join("old.log.1.gz"));
+        fs::remove_dir_all(&dir).unwrap();
+        
+        assert!(!below_service);
+        if dir_owned_by_root && dir.starts_with("/tmp") {
+            assert!(below_root_dir);
+        }
+    }
+    
+    #[test]
+    fn fallback_applies_the_helper_checks() {
+        let smuggled = HelperCommand::Apt { action: AptAction::Install, packages: vec!["./evil.deb".to_string()] };
+        assert!(fallback_argvs(&[smuggled]).is_err());
+        assert!(fallback_argvs(&[HelperCommand::Delete(vec![PathBuf::from("/etc/shadow")])]).is_err());
+        
+        let update = HelperCommand::Apt { action: AptAction::Update, packages: Vec::new() };
+        assert_eq!(fallback_argvs(&[update]).unwrap(), [["apt-get", "-y", "update"]]);
+    }
+    
+    #[test]
+    fn install_version_checks_name_and_version() {
+        assert_eq!(
+            parse("apt install-version firefox=1:120.0+build2-0ubuntu1~22.04").unwrap().argv(),
+            ["apt-get", "-y", "install", "--allow-downgrades", "firefox=1:120.0+build2-0ubuntu1~22.04"],
+        );
+        assert!(parse("apt install-version firefox").is_err());
+        assert!(parse("apt install-version ./x.deb=1.0").is_err());
+        assert!(parse("apt install-version firefox=/tmp/x").is_err());
+        assert!(parse("apt install-version firefox=-1").is_err());
+    }
+}
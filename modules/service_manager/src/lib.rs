@@ -2,8 +2,17 @@
 //! 
 //! Provides systemd service information and management capabilities.
 
-use anyhow::Result;
-use std::process::Command;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use privileged::{HelperCommand, SystemctlAction};
+
+pub use privileged::AuthenticationDismissed;
 
 #[derive(Debug, Clone)]
 pub struct ServiceInfo {
@@ -11,6 +20,36 @@ pub struct ServiceInfo {
     pub description: String,
     pub state: ServiceState,
     pub enabled: bool,
+    pub scope: Scope,
+}
+
+/// Which systemd instance a unit belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scope {
+    /// System-wide units, managed through the privileged helper
+    #[default]
+    System,
+    /// Per-user units (`systemctl --user`), managed without privilege escalation
+    User,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Scope::System => "System",
+            Scope::User => "User",
+        }
+    }
+    
+    /// A `systemctl` command talking to this scope's manager
+    fn systemctl(&self) -> Command {
+        let mut command = Command::new("systemctl");
+        if *self == Scope::User {
+            command.arg("--user");
+        }
+        command
+    }
+    
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -18,6 +57,12 @@ pub enum ServiceState {
     Active,
     Inactive,
     Failed,
+    /// Starting up; transitional
+    Activating,
+    /// Shutting down; transitional
+    Deactivating,
+    /// Linked to /dev/null, so it can't be started or enabled until unmasked
+    Masked,
     Unknown,
 }
 
@@ -27,15 +72,44 @@ impl ServiceState {
             ServiceState::Active => "Active",
             ServiceState::Inactive => "Inactive",
             ServiceState::Failed => "Failed",
+            ServiceState::Activating => "Activating",
+            ServiceState::Deactivating => "Deactivating",
+            ServiceState::Masked => "Masked",
             ServiceState::Unknown => "Unknown",
         }
     }
     
+    /// Whether the unit is between states and will settle on its own
+    pub fn is_transitional(&self) -> bool {
+        matches!(self, ServiceState::Activating | ServiceState::Deactivating)
+    }
+    
+    /// Map systemd's ActiveState and UnitFileState to a service state
+    /// 
+    /// A masked unit file wins over the active state, since it decides what
+    /// the user can do with the unit.
+    fn from_systemd(active_state: Option<&str>, unit_file_state: Option<&str>) -> Self {
+        if unit_file_state.is_some_and(|s| s.starts_with("masked")) {
+            return ServiceState::Masked;
+        }
+        
+        match active_state {
+            Some("active") | Some("reloading") => ServiceState::Active,
+            Some("inactive") => ServiceState::Inactive,
+            Some("failed") => ServiceState::Failed,
+            Some("activating") => ServiceState::Activating,
+            Some("deactivating") => ServiceState::Deactivating,
+            _ => ServiceState::Unknown,
+        }
+    }
+    
     pub fn icon(&self) -> &str {
         match self {
             ServiceState::Active => "emblem-ok-symbolic",
             ServiceState::Inactive => "media-playback-pause-symbolic",
             ServiceState::Failed => "dialog-error-symbolic",
+            ServiceState::Activating | ServiceState::Deactivating => "emblem-synchronizing-symbolic",
+            ServiceState::Masked => "action-unavailable-symbolic",
             ServiceState::Unknown => "dialog-question-symbolic",
         }
     }
@@ -43,92 +117,264 @@ impl ServiceState {
 
 /// List systemd services with their current status
 /// 
-/// Queries systemctl for service information.
+/// Queries systemctl for system service information.
 pub fn list_services() -> Result<Vec<ServiceInfo>> {
-    list_services_with_limit(None)
+    list_services_with_limit(None, false)
 }
 
 /// List systemd services with optional limit
-pub fn list_services_with_limit(limit: Option<usize>) -> Result<Vec<ServiceInfo>> {
-    let mut services = Vec::new();
+/// 
+/// With `include_user` set, the current user's units are listed after the
+/// system ones; the limit applies to each scope separately.
+pub fn list_services_with_limit(limit: Option<usize>, include_user: bool) -> Result<Vec<ServiceInfo>> {
+    let mut services = list_scope_services(Scope::System, limit)?;
+    if include_user {
+        services.extend(list_scope_services(Scope::User, limit)?);
+    }
+    
+    // Sort by state (active first) then by name
+    services.sort_by(|a, b| {
+        if a.state == b.state {
+            a.name.cmp(&b.name)
+        } else if a.state == ServiceState::Active {
+            std::cmp::Ordering::Less
+        } else if b.state == ServiceState::Active {
+            std::cmp::Ordering::Greater
+        } else {
+            a.name.cmp(&b.name)
+        }
+    });
     
+    Ok(services)
+}
+
+/// List the services of a single systemd instance
+/// 
+/// Uses two systemctl calls regardless of the number of units: one to list the
+/// unit files, and one `systemctl show` for all of them at once.
+fn list_scope_services(scope: Scope, limit: Option<usize>) -> Result<Vec<ServiceInfo>> {
     // Get list of services using systemctl
-    let output = Command::new("systemctl")
-        .args(&["list-unit-files", "--type=service", "--no-pager", "--no-legend"])
+    let output = scope.systemctl()
+        .args(["list-unit-files", "--type=service", "--no-pager", "--no-legend"])
         .output()?;
     
     if !output.status.success() {
-        return Ok(services);
+        return Ok(Vec::new());
     }
     
     let output_str = String::from_utf8_lossy(&output.stdout);
-    let lines: Vec<&str> = if let Some(lim) = limit {
-        output_str.lines().take(lim).collect()
-    } else {
-        output_str.lines().collect()
+    let mut unit_files: Vec<(&str, &str)> = output_str.lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 2 {
+                return None;
+            }
+            Some((parts[0], parts[1]))
+        })
+        // Templates like getty@.service can't be queried without an instance
+        .filter(|(unit, _)| !unit.ends_with("@.service"))
+        .collect();
+    if let Some(lim) = limit {
+        unit_files.truncate(lim);
+    }
+    
+    describe_units(scope, unit_files)
+}
+
+/// List the failed services of both the system and the current user
+/// 
+/// Asks systemd for failed units directly instead of listing every unit file,
+/// so it is cheap and never misses failures the way a limited listing can.
+pub fn get_failed_services() -> Result<Vec<ServiceInfo>> {
+    let mut services = failed_scope_services(Scope::System)?;
+    services.extend(failed_scope_services(Scope::User)?);
+    services.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(services)
+}
+
+fn failed_scope_services(scope: Scope) -> Result<Vec<ServiceInfo>> {
+    let output = scope.systemctl()
+        .args(["--failed", "--type=service", "--no-legend", "--plain", "--no-pager"])
+        .output()?;
+    
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+    
+    let output_str = String::from_utf8_lossy(&output.stdout);
+    let units: Vec<(&str, &str)> = output_str.lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|unit| (unit, ""))
+        .collect();
+    
+    describe_units(scope, units)
+}
+
+/// Look up description and state of the given units with one `systemctl show`
+/// 
+/// Each unit comes with its state from `list-unit-files`, used when `show`
+/// reports none.
+fn describe_units(scope: Scope, unit_files: Vec<(&str, &str)>) -> Result<Vec<ServiceInfo>> {
+    if unit_files.is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    let show_output = scope.systemctl()
+        .arg("show")
+        .args(unit_files.iter().map(|(unit, _)| unit))
+        .args(["-p", "Id,Description,ActiveState,UnitFileState", "--no-pager"])
+        .output()?;
+    let details = parse_show_output(&String::from_utf8_lossy(&show_output.stdout));
+    
+    let services = unit_files.into_iter()
+        .map(|(unit, file_state)| {
+            let properties = details.get(unit);
+            let property = |key: &str| properties.and_then(|p| p.get(key)).map(String::as_str);
+            let unit_file_state = property("UnitFileState").unwrap_or(file_state);
+            
+            ServiceInfo {
+                name: unit.trim_end_matches(".service").to_string(),
+                description: property("Description").unwrap_or_default().to_string(),
+                state: ServiceState::from_systemd(property("ActiveState"), Some(unit_file_state)),
+                enabled: unit_file_state == "enabled",
+                scope,
+            }
+        })
+        .collect();
+    
+    Ok(services)
+}
+
+/// Parse `systemctl show` output for several units, keyed by unit `Id`
+/// 
+/// Each unit is a block of `Key=Value` lines; blocks are separated by blank lines.
+fn parse_show_output(output: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut units = HashMap::new();
+    
+    for block in output.split("\n\n") {
+        let properties: HashMap<String, String> = block.lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        
+        if let Some(id) = properties.get("Id") {
+            units.insert(id.clone(), properties);
+        }
+    }
+    
+    units
+}
+
+/// A systemd timer and the unit it activates
+#[derive(Debug, Clone)]
+pub struct TimerInfo {
+    /// Timer name without the `.timer` suffix
+    pub name: String,
+    /// Next scheduled run as `YYYY-MM-DD HH:MM:SS TZ`, `None` if not scheduled
+    pub next_elapse: Option<String>,
+    /// Last run as `YYYY-MM-DD HH:MM:SS TZ`, `None` if it never ran
+    pub last_trigger: Option<String>,
+    /// Unit activated by the timer, e.g. `apt-daily.service`
+    pub unit: String,
+}
+
+/// List all systemd timers, including inactive ones
+/// 
+/// Parses `systemctl list-timers --all --no-pager`.
+pub fn list_timers() -> Result<Vec<TimerInfo>> {
+    let output = Command::new("systemctl")
+        .args(["list-timers", "--all", "--no-pager"])
+        .env("LC_ALL", "C")
+        .output()?;
+    
+    if !output.status.success() {
+        anyhow::bail!(
+            "systemctl list-timers failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    
+    Ok(parse_timers(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse the `systemctl list-timers` table
+/// 
+/// Columns are padded to fixed widths, and timestamps contain spaces, so each
+/// row is sliced at the offsets of the header's column names.
+fn parse_timers(output: &str) -> Vec<TimerInfo> {
+    let mut lines = output.lines();
+    let Some(header) = lines.next() else {
+        return Vec::new();
     };
     
+    let column = |name: &str| header.find(name);
+    let (Some(next), Some(left), Some(last), Some(passed), Some(unit), Some(activates)) = (
+        column("NEXT"),
+        column("LEFT"),
+        column("LAST"),
+        column("PASSED"),
+        column("UNIT"),
+        column("ACTIVATES"),
+    ) else {
+        return Vec::new();
+    };
+    
+    let mut timers = Vec::new();
     for line in lines {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 2 {
-            continue;
+        // The table ends with a blank line and an "N timers listed." footer
+        if line.trim().is_empty() {
+            break;
         }
         
-        let name = parts[0].trim_end_matches(".service").to_string();
-        let enabled = parts[1] == "enabled";
-        
-        // Get service status
-        let status_output = Command::new("systemctl")
-            .args(&["is-active", &format!("{}.service", name)])
-            .output()
-            .ok();
-        
-        let state = if let Some(output) = status_output {
-            let status = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            match status.as_str() {
-                "active" => ServiceState::Active,
-                "inactive" => ServiceState::Inactive,
-                "failed" => ServiceState::Failed,
-                _ => ServiceState::Unknown,
-            }
-        } else {
-            ServiceState::Unknown
+        let chars: Vec<char> = line.chars().collect();
+        let field = |start: usize, end: usize| -> String {
+            chars.get(start..end.min(chars.len()))
+                .map(|c| c.iter().collect::<String>().trim().to_string())
+                .unwrap_or_default()
         };
         
-        // Get service description
-        let desc_output = Command::new("systemctl")
-            .args(&["show", &format!("{}.service", name), "-p", "Description", "--value"])
-            .output()
-            .ok();
-        
-        let description = if let Some(output) = desc_output {
-            String::from_utf8_lossy(&output.stdout).trim().to_string()
-        } else {
-            String::new()
-        };
+        let timer_unit = field(unit, activates);
+        if timer_unit.is_empty() {
+            continue;
+        }
         
-        services.push(ServiceInfo {
-            name,
-            description,
-            state,
-            enabled,
+        timers.push(TimerInfo {
+            name: timer_unit.trim_end_matches(".timer").to_string(),
+            next_elapse: normalize_timestamp(&field(next, left)),
+            last_trigger: normalize_timestamp(&field(last, passed)),
+            unit: field(activates, chars.len()),
         });
     }
     
-    // Sort by state (active first) then by name
-    services.sort_by(|a, b| {
-        if a.state == b.state {
-            a.name.cmp(&b.name)
-        } else if a.state == ServiceState::Active {
-            std::cmp::Ordering::Less
-        } else if b.state == ServiceState::Active {
-            std::cmp::Ordering::Greater
-        } else {
-            a.name.cmp(&b.name)
-        }
-    });
+    timers
+}
+
+/// Turn `Thu 2023-10-12 00:00:00 UTC` into `2023-10-12 00:00:00 UTC`
+/// 
+/// `n/a` and `-` mean no timestamp. Anything not in the expected shape is kept
+/// as-is rather than dropped.
+fn normalize_timestamp(raw: &str) -> Option<String> {
+    if raw.is_empty() || raw == "n/a" || raw == "-" {
+        return None;
+    }
     
-    Ok(services)
+    let parts: Vec<&str> = raw.split_whitespace().collect();
+    let is_date = |s: &str| s.len() == 10 && s.chars().enumerate()
+        .all(|(i, c)| if i == 4 || i == 7 { c == '-' } else { c.is_ascii_digit() });
+    let is_time = |s: &str| s.len() == 8 && s.chars().enumerate()
+        .all(|(i, c)| if i == 2 || i == 5 { c == ':' } else { c.is_ascii_digit() });
+    
+    match parts.as_slice() {
+        [_, date, time, rest @ ..] if is_date(date) && is_time(time) => {
+            let mut stable = format!("{} {}", date, time);
+            for part in rest {
+                stable.push(' ');
+                stable.push_str(part);
+            }
+            Some(stable)
+        }
+        _ => Some(raw.to_string()),
+    }
 }
 
 /// Filter services by state
@@ -147,66 +393,452 @@ pub fn search_services(query: &str) -> Result<Vec<ServiceInfo>> {
         .collect())
 }
 
-/// Start a service (requires sudo)
-pub fn start_service(service: &str) -> Result<()> {
-    Command::new("pkexec")
-        .args(&["systemctl", "start", &format!("{}.service", service)])
-        .output()?;
-    Ok(())
+/// Start a service (system scope requires sudo)
+pub fn start_service(service: &str, scope: Scope) -> Result<()> {
+    run_systemctl_action(scope, SystemctlAction::Start, service)
 }
 
-/// Stop a service (requires sudo)
-pub fn stop_service(service: &str) -> Result<()> {
-    Command::new("pkexec")
-        .args(&["systemctl", "stop", &format!("{}.service", service)])
-        .output()?;
-    Ok(())
+/// Stop a service (system scope requires sudo)
+pub fn stop_service(service: &str, scope: Scope) -> Result<()> {
+    run_systemctl_action(scope, SystemctlAction::Stop, service)
 }
 
-/// Restart a service (requires sudo)
-pub fn restart_service(service: &str) -> Result<()> {
-    Command::new("pkexec")
-        .args(&["systemctl", "restart", &format!("{}.service", service)])
-        .output()?;
-    Ok(())
+/// Restart a service (system scope requires sudo)
+pub fn restart_service(service: &str, scope: Scope) -> Result<()> {
+    run_systemctl_action(scope, SystemctlAction::Restart, service)
 }
 
-/// Enable a service (requires sudo)
-pub fn enable_service(service: &str) -> Result<()> {
-    Command::new("pkexec")
-        .args(&["systemctl", "enable", &format!("{}.service", service)])
-        .output()?;
+/// Enable a service (system scope requires sudo)
+pub fn enable_service(service: &str, scope: Scope) -> Result<()> {
+    run_systemctl_action(scope, SystemctlAction::Enable, service)
+}
+
+/// Disable a service (system scope requires sudo)
+pub fn disable_service(service: &str, scope: Scope) -> Result<()> {
+    run_systemctl_action(scope, SystemctlAction::Disable, service)
+}
+
+/// Unmask a service so it can be started and enabled again (system scope requires sudo)
+pub fn unmask_service(service: &str, scope: Scope) -> Result<()> {
+    run_systemctl_action(scope, SystemctlAction::Unmask, service)
+}
+
+/// Clear a service's failed state so it can be restarted cleanly (system scope requires sudo)
+pub fn reset_failed_service(service: &str, scope: Scope) -> Result<()> {
+    run_systemctl_action(scope, SystemctlAction::ResetFailed, service)
+}
+
+/// Clear the failed state of every failed service in both scopes
+/// 
+/// The system services are reset in one batch, so there is a single
+/// authentication prompt.
+pub fn reset_all_failed() -> Result<()> {
+    let commands: Vec<HelperCommand> = failed_scope_services(Scope::System)?
+        .into_iter()
+        .map(|service| HelperCommand::Service {
+            action: SystemctlAction::ResetFailed,
+            unit: format!("{}.service", service.name),
+        })
+        .collect();
+    if !commands.is_empty() {
+        privileged::run_commands(&commands)?;
+    }
+    
+    for service in failed_scope_services(Scope::User)? {
+        reset_failed_service(&service.name, Scope::User)?;
+    }
     Ok(())
 }
 
-/// Disable a service (requires sudo)
-pub fn disable_service(service: &str) -> Result<()> {
-    Command::new("pkexec")
-        .args(&["systemctl", "disable", &format!("{}.service", service)])
-        .output()?;
+/// Run a state-changing systemctl action on a service
+/// 
+/// A non-zero exit becomes an error carrying systemctl's stderr, so a restart
+/// that fails because of a broken unit is reported instead of looking
+/// successful. System units go through the privileged helper; a dismissed
+/// prompt is reported as `AuthenticationDismissed`.
+fn run_systemctl_action(scope: Scope, action: SystemctlAction, service: &str) -> Result<()> {
+    let unit = format!("{}.service", service);
+    if scope == Scope::System {
+        return privileged::run_commands(&[HelperCommand::Service { action, unit }]);
+    }
+    
+    let output = scope.systemctl()
+        .args([action.as_str(), &unit])
+        .output()
+        .context("Failed to run systemctl")?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("systemctl {} {} failed ({}): {}", action.as_str(), unit, output.status, stderr.trim());
+    }
     Ok(())
 }
 
+/// Minimum journal priority to show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogPriority {
+    #[default]
+    All,
+    Error,
+    Warning,
+    Info,
+}
+
+impl LogPriority {
+    pub const ALL: [LogPriority; 4] = [LogPriority::All, LogPriority::Error, LogPriority::Warning, LogPriority::Info];
+    
+    pub fn label(&self) -> &str {
+        match self {
+            LogPriority::All => "All Priorities",
+            LogPriority::Error => "Errors",
+            LogPriority::Warning => "Warnings",
+            LogPriority::Info => "Info",
+        }
+    }
+    
+    /// Value for `journalctl -p`, which also includes every higher priority
+    fn journal_level(&self) -> Option<&str> {
+        match self {
+            LogPriority::All => None,
+            LogPriority::Error => Some("err"),
+            LogPriority::Warning => Some("warning"),
+            LogPriority::Info => Some("info"),
+        }
+    }
+}
+
+/// A `journalctl` command for a service's log
+/// 
+/// `lines` limits the output to the most recent entries; 0 shows all of them.
+fn journalctl(service: &str, lines: usize, priority: LogPriority, scope: Scope) -> Command {
+    let mut command = Command::new("journalctl");
+    if scope == Scope::User {
+        command.arg("--user");
+    }
+    command.args(["-u", &format!("{}.service", service), "--no-pager"]);
+    if lines > 0 {
+        command.args(["-n", &lines.to_string()]);
+    }
+    if let Some(level) = priority.journal_level() {
+        command.args(["-p", level]);
+    }
+    command
+}
+
 /// Get service logs
-pub fn get_service_logs(service: &str, lines: usize) -> Result<String> {
-    let output = Command::new("journalctl")
-        .args(&[
-            "-u",
-            &format!("{}.service", service),
-            "-n",
-            &lines.to_string(),
-            "--no-pager"
-        ])
-        .output()?;
+/// 
+/// `lines` limits the output to the most recent entries; 0 returns all of them.
+pub fn get_service_logs(service: &str, lines: usize, priority: LogPriority, scope: Scope) -> Result<String> {
+    let output = journalctl(service, lines, priority, scope)
+        .output()
+        .context("Failed to run journalctl")?;
     
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// Handle to a running `journalctl -f`
+/// 
+/// Dropping it kills and reaps journalctl and waits for the reader thread,
+/// after which the line callback is never called again.
+pub struct JournalFollower {
+    child: Child,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl Drop for JournalFollower {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
+
+/// Live-tail a service's journal
+/// 
+/// Starts with the last `lines` entries (all of them for 0), then calls
+/// `on_line` from a background thread for every new line until the returned
+/// handle is dropped.
+pub fn follow_service_logs(
+    service: &str,
+    lines: usize,
+    priority: LogPriority,
+    scope: Scope,
+    mut on_line: impl FnMut(String) + Send + 'static,
+) -> Result<JournalFollower> {
+    let mut command = journalctl(service, lines, priority, scope);
+    // Following would otherwise start with just the last 10 entries
+    if lines == 0 {
+        command.args(["-n", "all"]);
+    }
+    let mut child = command
+        .arg("-f")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to run journalctl")?;
+    
+    let Some(stdout) = child.stdout.take() else {
+        let _ = child.kill();
+        let _ = child.wait();
+        anyhow::bail!("journalctl produced no output stream");
+    };
+    
+    let reader = thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            match line {
+                Ok(line) => on_line(line),
+                Err(_) => break,
+            }
+        }
+    });
+    
+    Ok(JournalFollower { child, reader: Some(reader) })
+}
+
+/// Units a service pulls in, recursively
+/// 
+/// Parsed from `systemctl list-dependencies <unit> --plain`.
+pub fn get_service_dependencies(service: &str, scope: Scope) -> Result<Vec<String>> {
+    list_dependencies(service, scope, false)
+}
+
+/// Units that pull in a service, recursively
+/// 
+/// Useful for judging what else goes down when the service is stopped.
+pub fn get_service_reverse_dependencies(service: &str, scope: Scope) -> Result<Vec<String>> {
+    list_dependencies(service, scope, true)
+}
+
+fn list_dependencies(service: &str, scope: Scope, reverse: bool) -> Result<Vec<String>> {
+    let mut command = scope.systemctl();
+    command.args(["list-dependencies", &format!("{}.service", service), "--plain", "--no-pager"]);
+    if reverse {
+        command.arg("--reverse");
+    }
+    
+    let output = command.output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "systemctl list-dependencies failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    
+    Ok(parse_dependencies(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Extract unit names from `list-dependencies` output
+/// 
+/// The first line is the queried unit itself. The rest are indented and may
+/// carry tree glyphs or a state bullet, which are stripped. Duplicates from
+/// units reached along several paths are dropped.
+fn parse_dependencies(output: &str) -> Vec<String> {
+    let mut units: Vec<String> = Vec::new();
+    
+    for line in output.lines().skip(1) {
+        let unit = line.trim_start_matches(|c: char| c.is_whitespace() || "●○×*├└│─".contains(c)).trim();
+        if !unit.is_empty() && !units.iter().any(|u| u == unit) {
+            units.push(unit.to_string());
+        }
+    }
+    
+    units
+}
+
 /// Get detailed service status
-pub fn get_service_status(service: &str) -> Result<String> {
-    let output = Command::new("systemctl")
-        .args(&["status", &format!("{}.service", service), "--no-pager"])
+pub fn get_service_status(service: &str, scope: Scope) -> Result<String> {
+    let output = scope.systemctl()
+        .args(["status", &format!("{}.service", service), "--no-pager"])
         .output()?;
     
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
+
+/// Path of the unit file a service was loaded from
+/// 
+/// `None` for units without one, such as transient units.
+pub fn get_service_fragment_path(service: &str, scope: Scope) -> Result<Option<PathBuf>> {
+    let value = show_property(service, scope, "FragmentPath")?;
+    Ok((!value.is_empty()).then(|| PathBuf::from(value)))
+}
+
+/// Drop-in override files applied on top of a service's unit file, with their contents
+/// 
+/// Uses the `DropInPaths` systemd itself resolved, so drop-ins in /etc, /run
+/// and the vendor directories are all included, in the order they apply.
+pub fn get_service_drop_ins(service: &str, scope: Scope) -> Result<Vec<(PathBuf, String)>> {
+    show_property(service, scope, "DropInPaths")?
+        .split_whitespace()
+        .map(|path| {
+            let contents = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path))?;
+            Ok((PathBuf::from(path), contents))
+        })
+        .collect()
+}
+
+/// Value of a single unit property from `systemctl show`
+fn show_property(service: &str, scope: Scope, property: &str) -> Result<String> {
+    let output = scope.systemctl()
+        .args(["show", &format!("{}.service", service), "-p", property, "--value"])
+        .output()
+        .context("Failed to run systemctl")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "systemctl show failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Time spent in each boot phase, from `systemd-analyze time`
+/// 
+/// Firmware and loader times are only known on EFI systems; the initrd phase
+/// only exists when one was used.
+#[derive(Debug, Clone)]
+pub struct BootAnalysis {
+    pub firmware: Option<Duration>,
+    pub loader: Option<Duration>,
+    pub kernel: Duration,
+    pub initrd: Option<Duration>,
+    pub userspace: Duration,
+    pub total: Duration,
+}
+
+/// Get how long the last boot took, per phase
+/// 
+/// Fails with systemd-analyze's own message when boot times can't be measured,
+/// e.g. inside containers or while boot is still in progress.
+pub fn get_boot_analysis() -> Result<BootAnalysis> {
+    let stdout = run_systemd_analyze("time")?;
+    parse_boot_analysis(&stdout)
+        .with_context(|| format!("Unexpected systemd-analyze output: {}", stdout.trim()))
+}
+
+/// Get units ordered by how long they took to start, slowest first
+pub fn get_boot_blame() -> Result<Vec<(String, Duration)>> {
+    let stdout = run_systemd_analyze("blame")?;
+    Ok(stdout.lines().filter_map(parse_blame_line).collect())
+}
+
+fn run_systemd_analyze(command: &str) -> Result<String> {
+    let output = Command::new("systemd-analyze")
+        .args([command, "--no-pager"])
+        .env("LC_ALL", "C")
+        .output()
+        .context("Failed to run systemd-analyze")?;
+    
+    if !output.status.success() {
+        anyhow::bail!(
+            "Boot time can't be measured on this system: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parse `Startup finished in 3.1s (firmware) + ... + 8.4s (userspace) = 16.8s`
+fn parse_boot_analysis(output: &str) -> Option<BootAnalysis> {
+    let line = output.lines().find(|l| l.starts_with("Startup finished in "))?;
+    let (phases, total) = line.strip_prefix("Startup finished in ")?.split_once(" = ")?;
+    
+    let mut phase_times = HashMap::new();
+    for phase in phases.split(" + ") {
+        let (time, name) = phase.rsplit_once(" (")?;
+        phase_times.insert(name.trim_end_matches(')'), parse_systemd_duration(time)?);
+    }
+    
+    Some(BootAnalysis {
+        firmware: phase_times.get("firmware").copied(),
+        loader: phase_times.get("loader").copied(),
+        kernel: phase_times.get("kernel").copied()?,
+        initrd: phase_times.get("initrd").copied(),
+        userspace: phase_times.get("userspace").copied()?,
+        total: parse_systemd_duration(total)?,
+    })
+}
+
+/// Parse a blame line such as `1min 2.345s NetworkManager-wait-online.service`
+fn parse_blame_line(line: &str) -> Option<(String, Duration)> {
+    let (time, unit) = line.trim().rsplit_once(' ')?;
+    Some((unit.to_string(), parse_systemd_duration(time)?))
+}
+
+/// Parse a systemd timespan such as `1min 2.345s`, `850ms` or `1h 3min`
+fn parse_systemd_duration(text: &str) -> Option<Duration> {
+    let mut total = 0.0f64;
+    
+    for part in text.split_whitespace() {
+        let unit_start = part.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+        let (number, unit) = part.split_at(unit_start);
+        let number: f64 = number.parse().ok()?;
+        
+        let seconds = match unit {
+            "us" | "μs" => 1e-6,
+            "ms" => 1e-3,
+            "s" => 1.0,
+            "min" => 60.0,
+            "h" => 3600.0,
+            "d" => 86400.0,
+            _ => return None,
+        };
+        total += number * seconds;
+    }
+    
+    if text.trim().is_empty() {
+        None
+    } else {
+        Some(Duration::from_micros((total * 1e6).round() as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parses_multi_unit_show_output() {
+        let output = "\
+Id=cups.service
+Description=CUPS Scheduler
+ActiveState=active
+UnitFileState=enabled
+ExecMainStartTimestamp=Mon 2024-01-15 10:23:45 CET
+
+Id=custom.service
+Description=
+ActiveState=inactive
+UnitFileState=disabled
+
+Id=ssh.service
+Description=OpenBSD Secure Shell server
+Environment=SSHD_OPTS=-D
+ActiveState=failed
+UnitFileState=enabled
+";
+        let units = parse_show_output(output);
+        assert_eq!(units.len(), 3);
+        
+        let cups = &units["cups.service"];
+        assert_eq!(cups["Description"], "CUPS Scheduler");
+        assert_eq!(cups["ActiveState"], "active");
+        assert_eq!(cups["ExecMainStartTimestamp"], "Mon 2024-01-15 10:23:45 CET");
+        
+        // An empty Description is kept as empty, and doesn't end the record
+        let custom = &units["custom.service"];
+        assert_eq!(custom["Description"], "");
+        assert_eq!(custom["ActiveState"], "inactive");
+        assert_eq!(custom["UnitFileState"], "disabled");
+        
+        // Only the first `=` separates key and value
+        let ssh = &units["ssh.service"];
+        assert_eq!(ssh["Environment"], "SSHD_OPTS=-D");
+        assert_eq!(ssh["ActiveState"], "failed");
+    }
+}
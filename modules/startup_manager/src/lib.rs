@@ -3,17 +3,57 @@
 //! Manages autostart applications on system login.
 
 use anyhow::{Result, Context};
+use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use sysinfo::{Pid, Process};
 
 #[derive(Debug, Clone)]
 pub struct AutostartApp {
     pub name: String,
+    /// `Exec` as written in the desktop file
     pub exec: String,
+    /// `Exec` without field codes, see `clean_exec`
+    pub clean_exec: String,
     pub comment: String,
     pub enabled: bool,
     pub path: PathBuf,
     pub hidden: bool,
+    /// Seconds to wait after login before starting (`X-GNOME-Autostart-Delay`)
+    pub delay: Option<u32>,
+    /// Desktops the entry is restricted to (`OnlyShowIn`), empty for all
+    pub only_show_in: Vec<String>,
+    /// Desktops the entry is excluded from (`NotShowIn`)
+    pub not_show_in: Vec<String>,
+    /// The program named by `TryExec` (or `Exec` without one) isn't installed
+    pub executable_missing: bool,
+}
+
+impl AutostartApp {
+    /// Whether the entry runs in the current session according to
+    /// `OnlyShowIn`/`NotShowIn` and `$XDG_CURRENT_DESKTOP`
+    pub fn applies_to_current_desktop(&self) -> bool {
+        let current = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+        let desktops: Vec<&str> = current.split(':').filter(|d| !d.is_empty()).collect();
+        let listed = |list: &[String]| list.iter()
+            .any(|l| desktops.iter().any(|d| d.eq_ignore_ascii_case(l)));
+        
+        if !self.only_show_in.is_empty() && !listed(&self.only_show_in) {
+            return false;
+        }
+        !listed(&self.not_show_in)
+    }
+}
+
+/// The user's autostart directory, `~/.config/autostart`
+fn user_autostart_dir() -> PathBuf {
+    dirs::config_dir()
+        .map(|p| p.join("autostart"))
+        .unwrap_or_else(|| PathBuf::from(format!("{}/.config/autostart", 
+            std::env::var("HOME").unwrap_or_default())))
 }
 
 /// Get list of autostart applications from XDG autostart directories
@@ -25,10 +65,7 @@ pub fn list_autostart_apps() -> Result<Vec<AutostartApp>> {
     let mut apps = Vec::new();
     
     // User autostart directory
-    let user_dir = dirs::config_dir()
-        .map(|p| p.join("autostart"))
-        .unwrap_or_else(|| PathBuf::from(format!("{}/.config/autostart", 
-            std::env::var("HOME").unwrap_or_default())));
+    let user_dir = user_autostart_dir();
     
     // System autostart directory
     let system_dir = PathBuf::from("/etc/xdg/autostart");
@@ -79,24 +116,48 @@ fn parse_desktop_file(path: &Path) -> Result<AutostartApp> {
     let contents = fs::read_to_string(path)
         .context(format!("Failed to read {:?}", path))?;
     
-    let mut name = String::new();
-    let mut exec = String::new();
-    let mut comment = String::new();
-    let mut hidden = false;
-    
+    Ok(parse_desktop_entry(path, &contents, &locale_variants()))
+}
+
+/// Parse the contents of a .desktop file read from `path`
+/// 
+/// Localized keys are looked up in the order of `locales`, as returned by
+/// `locale_variants`, before falling back to the unlocalized key.
+fn parse_desktop_entry(path: &Path, contents: &str, locales: &[String]) -> AutostartApp {
+    // Only keys in the [Desktop Entry] group describe the entry itself;
+    // [Desktop Action ...] groups have their own Name and Exec
+    let mut entry: HashMap<&str, &str> = HashMap::new();
+    let mut in_entry = false;
     for line in contents.lines() {
         let line = line.trim();
-        if line.starts_with("Name=") {
-            name = line.strip_prefix("Name=").unwrap_or("").to_string();
-        } else if line.starts_with("Exec=") {
-            exec = line.strip_prefix("Exec=").unwrap_or("").to_string();
-        } else if line.starts_with("Comment=") {
-            comment = line.strip_prefix("Comment=").unwrap_or("").to_string();
-        } else if line.starts_with("Hidden=") {
-            hidden = line.strip_prefix("Hidden=").unwrap_or("false") == "true";
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+        } else if in_entry && !line.starts_with('#') {
+            if let Some((key, value)) = line.split_once('=') {
+                entry.insert(key.trim(), value.trim());
+            }
         }
     }
     
+    let localized = |key: &str| -> String {
+        locales.iter()
+            .find_map(|locale| entry.get(format!("{}[{}]", key, locale).as_str()))
+            .or_else(|| entry.get(key))
+            .map(|v| v.to_string())
+            .unwrap_or_default()
+    };
+    
+    let mut name = localized("Name");
+    let exec = entry.get("Exec").map(|v| v.to_string()).unwrap_or_default();
+    let comment = localized("Comment");
+    let hidden = entry.get("Hidden") == Some(&"true");
+    let delay = entry.get("X-GNOME-Autostart-Delay")
+        .and_then(|v| v.parse().ok())
+        .filter(|&d| d > 0);
+    let only_show_in = entry.get("OnlyShowIn").map(|v| parse_string_list(v)).unwrap_or_default();
+    let not_show_in = entry.get("NotShowIn").map(|v| parse_string_list(v)).unwrap_or_default();
+    let try_exec = entry.get("TryExec").map(|v| v.to_string()).unwrap_or_default();
+    
     if name.is_empty() {
         name = path.file_stem()
             .and_then(|s| s.to_str())
@@ -104,25 +165,323 @@ fn parse_desktop_file(path: &Path) -> Result<AutostartApp> {
             .to_string();
     }
     
-    Ok(AutostartApp {
+    // TryExec names the binary to check; otherwise use the program in Exec
+    let program = if !try_exec.is_empty() {
+        try_exec
+    } else {
+        exec_program(&exec).unwrap_or_default()
+    };
+    let executable_missing = !program.is_empty() && !executable_exists(&program);
+    
+    AutostartApp {
         name,
+        clean_exec: clean_exec(&exec),
         exec,
         comment,
         enabled: !hidden,
         path: path.to_path_buf(),
         hidden,
-    })
+        delay,
+        only_show_in,
+        not_show_in,
+        executable_missing,
+    }
 }
 
-/// Enable an autostart application
+/// Locale suffixes to try for localized keys, most specific first
 /// 
-/// For system apps, creates an override file in ~/.config/autostart/
-/// For user apps, removes the Hidden=true line
-pub fn enable_autostart(app: &AutostartApp) -> Result<()> {
-    let user_dir = dirs::config_dir()
-        .map(|p| p.join("autostart"))
-        .unwrap_or_else(|| PathBuf::from(format!("{}/.config/autostart", 
-            std::env::var("HOME").unwrap_or_default())));
+/// For `LANG=de_DE.UTF-8@euro` this is `de_DE@euro`, `de_DE`, `de@euro`, `de`,
+/// following the desktop entry spec. `LC_ALL` and `LC_MESSAGES` take
+/// precedence over `LANG`.
+fn locale_variants() -> Vec<String> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.is_empty())
+        .unwrap_or_default();
+    
+    variants_of_locale(&locale)
+}
+
+/// Locale suffixes for one locale name such as `de_DE.UTF-8@euro`
+fn variants_of_locale(locale: &str) -> Vec<String> {
+    let (locale, modifier) = match locale.split_once('@') {
+        Some((locale, modifier)) => (locale, Some(modifier)),
+        None => (locale, None),
+    };
+    let locale = locale.split('.').next().unwrap_or("");
+    if locale.is_empty() || locale == "C" || locale == "POSIX" {
+        return Vec::new();
+    }
+    
+    let (lang, country) = match locale.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (locale, None),
+    };
+    
+    let mut variants = Vec::new();
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        variants.push(format!("{}_{}@{}", lang, country, modifier));
+    }
+    if let Some(country) = country {
+        variants.push(format!("{}_{}", lang, country));
+    }
+    if let Some(modifier) = modifier {
+        variants.push(format!("{}@{}", lang, modifier));
+    }
+    variants.push(lang.to_string());
+    variants
+}
+
+/// The program part of an `Exec` value, without arguments, quotes or escapes
+/// 
+/// A leading `env VAR=value` is skipped, so
+/// `env GDK_BACKEND=x11 "/opt/My App/app" --tray` gives `/opt/My App/app`.
+fn exec_program(exec: &str) -> Option<String> {
+    let mut words = split_exec(exec).into_iter().map(unquote_word);
+    
+    let program = words.next()?;
+    if Path::new(&program).file_name()? == "env" {
+        return words.find(|word| !word.contains('=') && !word.starts_with('-'));
+    }
+    Some(program)
+}
+
+/// Check whether a program is an executable file, searching $PATH for bare names
+fn executable_exists(program: &str) -> bool {
+    let is_executable = |path: &Path| fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false);
+    
+    if program.contains('/') {
+        return is_executable(Path::new(program));
+    }
+    
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| is_executable(&dir.join(program))))
+        .unwrap_or(false)
+}
+
+/// Field codes an `Exec` value may contain, besides `%%`
+const FIELD_CODES: &str = "fFuUdDnNickvm";
+
+/// An `Exec` value without its field codes, for display or running
+/// 
+/// Following the desktop entry spec, `%%` becomes `%` and the other codes
+/// (`%f %F %u %U %d %D %n %N %i %c %k %v %m`) are dropped; an argument that
+/// was nothing but a field code disappears. Quoted arguments are kept as written.
+pub fn clean_exec(exec: &str) -> String {
+    split_exec(exec).into_iter()
+        .map(strip_field_codes)
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Split an `Exec` value at unquoted whitespace, keeping quotes and escapes
+fn split_exec(exec: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+    let mut escaped = false;
+    
+    for (index, c) in exec.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            quoted = !quoted;
+        } else if c.is_whitespace() && !quoted {
+            if let Some(start) = start.take() {
+                words.push(&exec[start..index]);
+            }
+            continue;
+        }
+        start.get_or_insert(index);
+    }
+    if let Some(start) = start {
+        words.push(&exec[start..]);
+    }
+    
+    words
+}
+
+/// A word from `split_exec` as the program receives it, without quotes or escapes
+fn unquote_word(word: &str) -> String {
+    let mut unquoted = String::with_capacity(word.len());
+    let mut chars = word.chars();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {}
+            '\\' => unquoted.extend(chars.next()),
+            c => unquoted.push(c),
+        }
+    }
+    
+    unquoted
+}
+
+fn strip_field_codes(word: &str) -> String {
+    let mut stripped = String::with_capacity(word.len());
+    let mut chars = word.chars();
+    
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            stripped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => stripped.push('%'),
+            Some(code) if FIELD_CODES.contains(code) => {}
+            Some(other) => {
+                stripped.push('%');
+                stripped.push(other);
+            }
+            None => stripped.push('%'),
+        }
+    }
+    
+    stripped
+}
+
+/// Length the kernel truncates process names (`comm`) to
+const PROCESS_NAME_MAX: usize = 15;
+
+/// What an enabled autostart entry costs while it runs
+#[derive(Debug, Clone)]
+pub struct StartupImpact {
+    pub app: AutostartApp,
+    /// The running process the entry was matched with, `None` if not running
+    pub matched_pid: Option<Pid>,
+    /// CPU usage in percent, as of the last process refresh
+    pub cpu: f32,
+    /// Resident memory in bytes
+    pub memory: u64,
+}
+
+/// Match enabled autostart entries that run in this session with running processes
+/// 
+/// The program file name from `Exec` is compared with each process's
+/// executable, first argument and name. An entry running several times is
+/// matched with its lowest PID. Sorted by memory usage, largest first, with
+/// entries that aren't running last.
+pub fn analyze_startup_impact(processes: &HashMap<Pid, Process>) -> Result<Vec<StartupImpact>> {
+    let mut impacts: Vec<StartupImpact> = list_autostart_apps()?
+        .into_iter()
+        .filter(|app| app.enabled && app.applies_to_current_desktop())
+        .map(|app| {
+            let process = exec_binary_name(&app.clean_exec).and_then(|binary| {
+                processes.values()
+                    .filter(|p| p.thread_kind().is_none() && process_runs(p, &binary))
+                    .min_by_key(|p| p.pid())
+            });
+            StartupImpact {
+                matched_pid: process.map(|p| p.pid()),
+                cpu: process.map_or(0.0, |p| p.cpu_usage()),
+                memory: process.map_or(0, |p| p.memory()),
+                app,
+            }
+        })
+        .collect();
+    
+    impacts.sort_by_key(|impact| (impact.matched_pid.is_none(), std::cmp::Reverse(impact.memory)));
+    Ok(impacts)
+}
+
+/// File name of the program a cleaned `Exec` value runs, see `exec_program`
+fn exec_binary_name(exec: &str) -> Option<String> {
+    let program = exec_program(exec)?;
+    Path::new(&program)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+/// Whether a process runs the program with the given file name
+/// 
+/// Process names are cut to 15 bytes by the kernel, so a longer binary name
+/// matches on its prefix.
+fn process_runs(process: &Process, binary: &str) -> bool {
+    let is_binary = |path: &Path| path.file_name().is_some_and(|name| name == binary);
+    let name = process.name();
+    
+    process.exe().is_some_and(is_binary)
+        || process.cmd().first().is_some_and(|arg| is_binary(Path::new(arg)))
+        || name == binary
+        || (name.len() == PROCESS_NAME_MAX && binary.starts_with(name))
+}
+
+/// Split a `;`-separated desktop entry list such as `GNOME;Unity;`
+fn parse_string_list(value: &str) -> Vec<String> {
+    value.split(';')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Create a new autostart entry in ~/.config/autostart/
+/// 
+/// The file name is derived from `name` (lowercased, anything other than
+/// letters and digits replaced by `-`). Fails if that file already exists.
+pub fn create_autostart_entry(name: &str, exec: &str, comment: &str) -> Result<AutostartApp> {
+    let name = name.trim();
+    let exec = exec.trim();
+    let comment = comment.trim();
+    
+    if exec.is_empty() {
+        anyhow::bail!("Command must not be empty");
+    }
+    if [name, exec, comment].iter().any(|v| v.contains('\n') || v.contains('\r')) {
+        anyhow::bail!("Values must fit on a single line");
+    }
+    
+    let file_stem = sanitize_file_stem(name);
+    if file_stem.is_empty() {
+        anyhow::bail!("Name must contain at least one letter or digit");
+    }
+    
+    let user_dir = user_autostart_dir();
+    fs::create_dir_all(&user_dir)?;
+    let path = user_dir.join(format!("{}.desktop", file_stem));
+    
+    let mut contents = format!(
+        "[Desktop Entry]\nType=Application\nName={}\nExec={}\n",
+        name, exec
+    );
+    if !comment.is_empty() {
+        contents.push_str(&format!("Comment={}\n", comment));
+    }
+    
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .context(format!("Failed to create {:?}", path))?;
+    file.write_all(contents.as_bytes())?;
+    
+    parse_desktop_file(&path)
+}
+
+/// Turn a display name into a safe desktop file name stem
+fn sanitize_file_stem(name: &str) -> String {
+    let mut stem = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            stem.extend(c.to_lowercase());
+        } else if !stem.is_empty() && !stem.ends_with('-') {
+            stem.push('-');
+        }
+    }
+    stem.trim_end_matches('-').to_string()
+}
+
+/// Copy an entry into the user autostart directory if it isn't there yet
+/// 
+/// Returns the path of the user-dir copy, which overrides the system file. An
+/// existing copy is kept as it is, since it may hold changes made earlier.
+fn ensure_user_copy(app: &AutostartApp) -> Result<PathBuf> {
+    let user_dir = user_autostart_dir();
     
     fs::create_dir_all(&user_dir)?;
     
@@ -131,10 +490,143 @@ pub fn enable_autostart(app: &AutostartApp) -> Result<()> {
     let target_path = user_dir.join(file_name);
     
     // If it's a system file, copy it to user directory
-    if !app.path.starts_with(&user_dir) {
+    if !app.path.starts_with(&user_dir) && !target_path.exists() {
         fs::copy(&app.path, &target_path)?;
     }
     
+    Ok(target_path)
+}
+
+/// Set or remove a key in the `[Desktop Entry]` group of a desktop file
+/// 
+/// An existing key is replaced in place; a new one goes right after the group
+/// header. Every other line is left untouched.
+fn set_entry_key(path: &Path, key: &str, value: Option<&str>) -> Result<()> {
+    let contents = fs::read_to_string(path)?;
+    let prefix = format!("{}=", key);
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_entry = false;
+    let mut header_index = None;
+    let mut replaced = false;
+    
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_entry = trimmed == "[Desktop Entry]";
+            if in_entry {
+                header_index = Some(lines.len());
+            }
+        } else if in_entry && trimmed.starts_with(&prefix) {
+            if let (Some(value), false) = (value, replaced) {
+                lines.push(format!("{}{}", prefix, value));
+                replaced = true;
+            }
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+    
+    if let (Some(value), false) = (value, replaced) {
+        let new_line = format!("{}{}", prefix, value);
+        match header_index {
+            Some(i) => lines.insert(i + 1, new_line),
+            None => lines.push(new_line),
+        }
+    }
+    
+    let mut new_contents = lines.join("\n");
+    if contents.ends_with('\n') {
+        new_contents.push('\n');
+    }
+    fs::write(path, new_contents)?;
+    
+    Ok(())
+}
+
+/// Remove all `key[locale]` variants from the `[Desktop Entry]` group
+fn remove_localized_keys(path: &Path, key: &str) -> Result<()> {
+    let contents = fs::read_to_string(path)?;
+    let prefix = format!("{}[", key);
+    let mut in_entry = false;
+    
+    let lines: Vec<&str> = contents.lines()
+        .filter(|line| {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') {
+                in_entry = trimmed == "[Desktop Entry]";
+                return true;
+            }
+            !(in_entry && trimmed.starts_with(&prefix))
+        })
+        .collect();
+    
+    let mut new_contents = lines.join("\n");
+    if contents.ends_with('\n') {
+        new_contents.push('\n');
+    }
+    fs::write(path, new_contents)?;
+    
+    Ok(())
+}
+
+/// Delay an autostart application by the given number of seconds after login
+/// 
+/// Writes `X-GNOME-Autostart-Delay` to the user-dir copy, copying a system entry
+/// there first. A delay of 0 removes the key.
+pub fn set_autostart_delay(app: &AutostartApp, seconds: u32) -> Result<()> {
+    let target_path = ensure_user_copy(app)?;
+    let value = seconds.to_string();
+    set_entry_key(&target_path, "X-GNOME-Autostart-Delay", (seconds > 0).then_some(value.as_str()))
+}
+
+/// Change the name, command or comment of an autostart entry
+/// 
+/// Only the given keys are rewritten, in the user-dir copy (a system entry is
+/// copied there first). An empty comment removes the `Comment` key.
+pub fn update_autostart_entry(
+    app: &AutostartApp,
+    name: Option<&str>,
+    exec: Option<&str>,
+    comment: Option<&str>,
+) -> Result<()> {
+    let name = name.map(str::trim);
+    let exec = exec.map(str::trim);
+    let comment = comment.map(str::trim);
+    
+    if name == Some("") {
+        anyhow::bail!("Name must not be empty");
+    }
+    if exec == Some("") {
+        anyhow::bail!("Command must not be empty");
+    }
+    if [name, exec, comment].iter().flatten().any(|v| v.contains('\n') || v.contains('\r')) {
+        anyhow::bail!("Values must fit on a single line");
+    }
+    
+    let target_path = ensure_user_copy(app)?;
+    if let Some(name) = name {
+        set_entry_key(&target_path, "Name", Some(name))?;
+        // Translations would otherwise keep showing the old name
+        remove_localized_keys(&target_path, "Name")?;
+    }
+    if let Some(exec) = exec {
+        set_entry_key(&target_path, "Exec", Some(exec))?;
+    }
+    if let Some(comment) = comment {
+        set_entry_key(&target_path, "Comment", Some(comment).filter(|c| !c.is_empty()))?;
+        remove_localized_keys(&target_path, "Comment")?;
+    }
+    
+    Ok(())
+}
+
+/// Enable an autostart application
+/// 
+/// For system apps, creates an override file in ~/.config/autostart/
+/// For user apps, removes the Hidden=true line
+pub fn enable_autostart(app: &AutostartApp) -> Result<()> {
+    let target_path = ensure_user_copy(app)?;
+    
     // Remove Hidden=true line
     let contents = fs::read_to_string(&target_path)?;
     let new_contents: Vec<String> = contents
@@ -152,21 +644,7 @@ pub fn enable_autostart(app: &AutostartApp) -> Result<()> {
 /// 
 /// Adds Hidden=true to the desktop file
 pub fn disable_autostart(app: &AutostartApp) -> Result<()> {
-    let user_dir = dirs::config_dir()
-        .map(|p| p.join("autostart"))
-        .unwrap_or_else(|| PathBuf::from(format!("{}/.config/autostart", 
-            std::env::var("HOME").unwrap_or_default())));
-    
-    fs::create_dir_all(&user_dir)?;
-    
-    let file_name = app.path.file_name()
-        .context("Invalid path")?;
-    let target_path = user_dir.join(file_name);
-    
-    // If it's a system file, copy it to user directory
-    if !app.path.starts_with(&user_dir) {
-        fs::copy(&app.path, &target_path)?;
-    }
+    let target_path = ensure_user_copy(app)?;
     
     // Add Hidden=true line
     let contents = fs::read_to_string(&target_path)?;
@@ -199,10 +677,7 @@ pub fn disable_autostart(app: &AutostartApp) -> Result<()> {
 /// 
 /// Deletes the .desktop file from user autostart directory
 pub fn remove_autostart(app: &AutostartApp) -> Result<()> {
-    let user_dir = dirs::config_dir()
-        .map(|p| p.join("autostart"))
-        .unwrap_or_else(|| PathBuf::from(format!("{}/.config/autostart", 
-            std::env::var("HOME").unwrap_or_default())));
+    let user_dir = user_autostart_dir();
     
     // Only allow removing from user directory
     if app.path.starts_with(&user_dir) {
@@ -214,3 +689,205 @@ pub fn remove_autostart(app: &AutostartApp) -> Result<()> {
     
     Ok(())
 }
+
+/// A scheduled job from a crontab
+#[derive(Debug, Clone)]
+pub struct CronJob {
+    /// The five time fields, or a shortcut like `@reboot`
+    pub schedule: String,
+    pub command: String,
+    /// Where the job is defined: `crontab` for the user's own, else a file path
+    pub source: String,
+    /// User the job runs as; only system crontabs name one
+    pub user: Option<String>,
+}
+
+/// List cron jobs from the user's crontab and /etc/cron.d/
+/// 
+/// A user without a crontab, a system without cron, or a failing `crontab -l`
+/// (which is logged) yields no user jobs rather than an error.
+pub fn list_cron_jobs() -> Result<Vec<CronJob>> {
+    let mut jobs = Vec::new();
+    
+    if let Ok(output) = Command::new("crontab").arg("-l").env("LC_ALL", "C").output() {
+        if output.status.success() {
+            jobs.extend(parse_crontab(&String::from_utf8_lossy(&output.stdout), "crontab", false));
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if !stderr.contains("no crontab for") {
+                tracing::warn!("crontab -l failed: {}", stderr.trim());
+            }
+        }
+    }
+    
+    if let Ok(entries) = fs::read_dir("/etc/cron.d") {
+        let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        paths.sort();
+        for path in paths {
+            // cron ignores files with dots in the name, e.g. dpkg leftovers
+            let ignored = path.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.contains('.'))
+                .unwrap_or(true);
+            if ignored || !path.is_file() {
+                continue;
+            }
+            if let Ok(contents) = fs::read_to_string(&path) {
+                jobs.extend(parse_crontab(&contents, &path.display().to_string(), true));
+            }
+        }
+    }
+    
+    Ok(jobs)
+}
+
+/// Parse crontab lines, skipping comments and variable assignments
+/// 
+/// System crontabs (`has_user`) have a user column between the schedule and
+/// the command.
+fn parse_crontab(contents: &str, source: &str, has_user: bool) -> Vec<CronJob> {
+    let mut jobs = Vec::new();
+    
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        
+        let first = line.split_whitespace().next().unwrap_or("");
+        if first.contains('=') {
+            continue;
+        }
+        
+        let schedule_fields = if first.starts_with('@') { 1 } else { 5 };
+        let fields = schedule_fields + usize::from(has_user);
+        
+        // Take the leading fields one by one so the command keeps its spacing
+        let mut rest = line;
+        let mut taken = Vec::new();
+        for _ in 0..fields {
+            let trimmed = rest.trim_start();
+            let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+            taken.push(&trimmed[..end]);
+            rest = &trimmed[end..];
+        }
+        let command = rest.trim();
+        if command.is_empty() {
+            continue;
+        }
+        
+        jobs.push(CronJob {
+            schedule: taken[..schedule_fields].join(" "),
+            command: command.to_string(),
+            source: source.to_string(),
+            user: has_user.then(|| taken[schedule_fields].to_string()),
+        });
+    }
+    
+    jobs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const DESKTOP_FILE: &str = "\
+[Desktop Entry]
+Type=Application
+Name=Files
+Name[de]=Dateien
+Name[de_DE]=Dateien (Deutschland)
+Comment=Browse files
+Comment[de]=Dateien durchsuchen
+Exec=nautilus --gapplication-service
+X-GNOME-Autostart-Delay=5
+Actions=new-window;
+
+[Desktop Action new-window]
+Name=New Window
+Name[de_DE]=Neues Fenster
+Comment=Opens a window
+Exec=nautilus --new-window
+Hidden=true
+";
+    
+    fn parse(locale: &str) -> AutostartApp {
+        parse_desktop_entry(Path::new("/tmp/org.gnome.Nautilus.desktop"), DESKTOP_FILE, &variants_of_locale(locale))
+    }
+    
+    #[test]
+    fn locale_variants_follow_the_spec_order() {
+        assert_eq!(variants_of_locale("de_DE.UTF-8@euro"), ["de_DE@euro", "de_DE", "de@euro", "de"]);
+        assert_eq!(variants_of_locale("de_DE.UTF-8"), ["de_DE", "de"]);
+        assert_eq!(variants_of_locale("de"), ["de"]);
+        assert!(variants_of_locale("C.UTF-8").is_empty());
+        assert!(variants_of_locale("POSIX").is_empty());
+        assert!(variants_of_locale("").is_empty());
+    }
+    
+    #[test]
+    fn localized_keys_fall_back_from_country_to_language_to_plain() {
+        let app = parse("de_DE.UTF-8");
+        assert_eq!(app.name, "Dateien (Deutschland)");
+        assert_eq!(app.comment, "Dateien durchsuchen");
+        
+        let app = parse("de_AT.UTF-8");
+        assert_eq!(app.name, "Dateien");
+        
+        let app = parse("fr_FR.UTF-8");
+        assert_eq!(app.name, "Files");
+        assert_eq!(app.comment, "Browse files");
+    }
+    
+    #[test]
+    fn desktop_action_groups_are_ignored() {
+        let app = parse("de_DE.UTF-8");
+        assert_eq!(app.exec, "nautilus --gapplication-service");
+        assert_eq!(app.delay, Some(5));
+        assert!(!app.hidden);
+        assert!(app.enabled);
+    }
+    
+    #[test]
+    fn clean_exec_drops_each_field_code() {
+        for code in ["%f", "%F", "%u", "%U", "%i", "%c", "%k", "%d", "%D", "%n", "%N", "%v", "%m"] {
+            assert_eq!(clean_exec(&format!("app {} --flag", code)), "app --flag", "{}", code);
+        }
+        assert_eq!(clean_exec("app --file=%f --uri=%u"), "app --file= --uri=");
+        assert_eq!(clean_exec("app %i %c %k %U"), "app");
+    }
+    
+    #[test]
+    fn clean_exec_unescapes_percent_and_keeps_unknown_codes() {
+        assert_eq!(clean_exec("printf 100%% %F"), "printf 100%");
+        assert_eq!(clean_exec("date +%%Y-%%m"), "date +%Y-%m");
+        assert_eq!(clean_exec("app %x 50%"), "app %x 50%");
+    }
+    
+    #[test]
+    fn quoted_arguments_stay_whole() {
+        assert_eq!(
+            split_exec(r#""/opt/My App/app" --title "Hello World" %U"#),
+            [r#""/opt/My App/app""#, "--title", r#""Hello World""#, "%U"],
+        );
+        assert_eq!(split_exec(r#"sh -c "echo \"a b\"" x"#), ["sh", "-c", r#""echo \"a b\"""#, "x"]);
+        assert_eq!(
+            clean_exec(r#""/opt/My App/app"   --name "A %% B" %f"#),
+            r#""/opt/My App/app" --name "A % B""#,
+        );
+        assert_eq!(strip_field_codes("%F"), "");
+        assert_eq!(exec_program(r#""/opt/My App/app" %U"#).as_deref(), Some("/opt/My App/app"));
+    }
+    
+    #[test]
+    fn program_skips_env_and_unescapes() {
+        assert_eq!(exec_program(r"/opt/My\ App/app --tray").as_deref(), Some("/opt/My App/app"));
+        assert_eq!(
+            exec_program(r#"env GDK_BACKEND=x11 "/opt/My App/app" --tray"#).as_deref(),
+            Some("/opt/My App/app"),
+        );
+        assert_eq!(exec_program("/usr/bin/env -i FOO=1 redshift").as_deref(), Some("redshift"));
+        assert_eq!(exec_binary_name(r#"env A=1 "/opt/My App/app""#).as_deref(), Some("app"));
+        assert_eq!(exec_program("env FOO=1"), None);
+    }
+}
@@ -3,9 +3,20 @@
 //! Provides system cleaning capabilities for freeing up disk space.
 
 use anyhow::Result;
+use nix::unistd::getuid;
+use std::collections::HashSet;
 use std::fs;
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+use privileged::{AptAction, HelperCommand, JournalLimit};
+
+pub use privileged::{AuthenticationDismissed, Outcome};
+
+/// Journal files older than this many days are removed by the Logs category
+const LOG_MAX_AGE_DAYS: u32 = 7;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum CleanupCategory {
@@ -13,9 +24,14 @@ pub enum CleanupCategory {
     Thumbnails,
     Trash,
     Logs,
+    RotatedLogs,
     OldKernels,
     BrowserCache,
     TempFiles,
+    /// Dangling images and build cache of Docker and Podman
+    ContainerCache,
+    /// A user-chosen directory whose contents should be cleaned
+    Custom(PathBuf),
 }
 
 impl CleanupCategory {
@@ -25,9 +41,14 @@ impl CleanupCategory {
             CleanupCategory::Thumbnails => "Thumbnail Cache",
             CleanupCategory::Trash => "Trash",
             CleanupCategory::Logs => "Old System Logs",
+            CleanupCategory::RotatedLogs => "Rotated Logs",
             CleanupCategory::OldKernels => "Old Kernels",
             CleanupCategory::BrowserCache => "Browser Cache",
             CleanupCategory::TempFiles => "Temporary Files",
+            CleanupCategory::ContainerCache => "Container Cache",
+            CleanupCategory::Custom(path) => path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("Custom Path"),
         }
     }
 
@@ -36,10 +57,32 @@ impl CleanupCategory {
             CleanupCategory::PackageCache => "APT package cache and downloaded .deb files",
             CleanupCategory::Thumbnails => "Cached thumbnail images",
             CleanupCategory::Trash => "Files in trash bin",
-            CleanupCategory::Logs => "Rotated and old system log files",
-            CleanupCategory::OldKernels => "Old kernel versions (keeps current and one previous)",
+            CleanupCategory::Logs => "Systemd journal files older than a week",
+            CleanupCategory::RotatedLogs => "Compressed rotated log files in /var/log",
+            CleanupCategory::OldKernels => "Old kernel versions (keeps the running and the newest)",
             CleanupCategory::BrowserCache => "Firefox and Chrome cache files",
             CleanupCategory::TempFiles => "Temporary files in /tmp and /var/tmp",
+            CleanupCategory::ContainerCache => "Unused Docker and Podman images, containers and build cache",
+            CleanupCategory::Custom(path) => path.to_str().unwrap_or("Custom directory"),
+        }
+    }
+    
+    /// Whether cleaning may run commands as root, asking for authentication
+    /// 
+    /// Temporary files only need root for entries owned by other users, but
+    /// that can't be known before scanning /tmp.
+    pub fn requires_root(&self) -> bool {
+        match self {
+            CleanupCategory::Thumbnails
+            | CleanupCategory::Trash
+            | CleanupCategory::BrowserCache
+            | CleanupCategory::Custom(_) => false,
+            CleanupCategory::PackageCache
+            | CleanupCategory::Logs
+            | CleanupCategory::RotatedLogs
+            | CleanupCategory::OldKernels
+            | CleanupCategory::TempFiles
+            | CleanupCategory::ContainerCache => true,
         }
     }
 }
@@ -50,48 +93,219 @@ pub struct CleanupItem {
     pub size: u64,
     pub count: usize,
     pub paths: Vec<PathBuf>,
+    /// Bytes kept because they match an exclusion, not part of `size`
+    pub excluded: u64,
 }
 
 /// Calculate the size of a directory recursively
+/// 
+/// Symlinks are not followed, and a directory reachable twice (through a
+/// bind mount) is only counted once.
 fn calculate_dir_size(path: &Path) -> u64 {
     let mut total = 0u64;
-    
+    walk_files(path, &mut HashSet::new(), &mut |metadata| total += metadata.len());
+    total
+}
+
+/// Call `on_file` for every regular file below `path`
+/// 
+/// `DirEntry::metadata` does not follow symlinks, so symlinked directories are
+/// skipped. Directories whose device and inode are already in `visited` are
+/// skipped too, which breaks cycles made with bind mounts.
+fn walk_files(path: &Path, visited: &mut HashSet<(u64, u64)>, on_file: &mut dyn FnMut(&fs::Metadata)) {
     if let Ok(entries) = fs::read_dir(path) {
         for entry in entries.flatten() {
             if let Ok(metadata) = entry.metadata() {
                 if metadata.is_file() {
-                    total += metadata.len();
-                } else if metadata.is_dir() {
-                    total += calculate_dir_size(&entry.path());
+                    on_file(&metadata);
+                } else if metadata.is_dir() && visited.insert((metadata.dev(), metadata.ino())) {
+                    walk_files(&entry.path(), visited, on_file);
                 }
             }
         }
     }
-    
-    total
+}
+
+/// Size of a file, or of a directory and everything below it
+fn path_size(path: &Path) -> u64 {
+    match path.symlink_metadata() {
+        Ok(metadata) if metadata.is_dir() => calculate_dir_size(path),
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
 }
 
 /// Count files in a directory recursively
 fn count_files_in_dir(path: &Path) -> usize {
     let mut count = 0;
+    walk_files(path, &mut HashSet::new(), &mut |_| count += 1);
+    count
+}
+
+/// Whether `path` matches one of the exclusion patterns
+/// 
+/// A pattern containing `/` is matched against the whole path, after expanding
+/// a leading `~/`; any other pattern is matched against the file name alone.
+/// `*` matches any run of characters and `?` a single one, neither crossing a
+/// `/`. Everything below an excluded directory is excluded with it.
+fn is_excluded(path: &Path, exclusions: &[String]) -> bool {
+    let home = std::env::var("HOME").unwrap_or_default();
+    let path_str = path.to_string_lossy();
+    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
     
-    if let Ok(entries) = fs::read_dir(path) {
-        for entry in entries.flatten() {
-            if let Ok(metadata) = entry.metadata() {
-                if metadata.is_file() {
-                    count += 1;
-                } else if metadata.is_dir() {
-                    count += count_files_in_dir(&entry.path());
+    exclusions.iter().any(|pattern| {
+        let pattern = pattern.trim_end_matches('/');
+        if let Some(rest) = pattern.strip_prefix("~/") {
+            glob_match(&format!("{}/{}", home, rest), &path_str)
+        } else if pattern.contains('/') {
+            glob_match(pattern, &path_str)
+        } else {
+            !pattern.is_empty() && glob_match(pattern, &name)
+        }
+    })
+}
+
+/// Match `text` against a pattern with `*` and `?` wildcards that stop at `/`
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and how much text it has taken so far
+    let mut star: Option<(usize, usize)> = None;
+    
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == text[t] || (c == '?' && text[t] != '/') => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) if text[star_t] != '/' => {
+                    star = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
                 }
+                _ => return false,
+            },
+        }
+    }
+    
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// What cleaning a path removes and what its exclusions keep
+#[derive(Debug, Default)]
+struct Measured {
+    /// Bytes in regular files that would be removed
+    size: u64,
+    count: usize,
+    /// Bytes below excluded entries
+    excluded: u64,
+    excluded_entries: usize,
+}
+
+/// Measure a file or directory, leaving out excluded entries
+/// 
+/// Like `calculate_dir_size`, symlinks are not followed and directories
+/// reachable twice are only counted once.
+fn measure_excluding(path: &Path, exclusions: &[String]) -> Measured {
+    fn walk(dir: &Path, exclusions: &[String], visited: &mut HashSet<(u64, u64)>, measured: &mut Measured) {
+        for entry in list_entries(dir) {
+            let Ok(metadata) = entry.symlink_metadata() else {
+                continue;
+            };
+            if is_excluded(&entry, exclusions) {
+                measured.excluded += path_size(&entry);
+                measured.excluded_entries += 1;
+            } else if metadata.is_file() {
+                measured.size += metadata.len();
+                measured.count += 1;
+            } else if metadata.is_dir() && visited.insert((metadata.dev(), metadata.ino())) {
+                walk(&entry, exclusions, visited, measured);
             }
         }
     }
     
-    count
+    let mut measured = Measured::default();
+    match path.symlink_metadata() {
+        Ok(_) if is_excluded(path, exclusions) => {
+            measured.excluded = path_size(path);
+            measured.excluded_entries = 1;
+        }
+        Ok(metadata) if metadata.is_dir() => walk(path, exclusions, &mut HashSet::new(), &mut measured),
+        Ok(metadata) => {
+            measured.size = metadata.len();
+            measured.count = 1;
+        }
+        Err(_) => {}
+    }
+    measured
+}
+
+/// Delete `path` and everything below it, except excluded entries
+/// 
+/// Directories are emptied entry by entry and only removed once nothing in
+/// them was kept. Returns the bytes freed and whether anything was kept.
+fn remove_tree(path: &Path, exclusions: &[String]) -> Result<(u64, bool)> {
+    if is_excluded(path, exclusions) {
+        return Ok((0, true));
+    }
+    let Ok(metadata) = path.symlink_metadata() else {
+        return Ok((0, false));
+    };
+    
+    if !metadata.is_dir() {
+        fs::remove_file(path)?;
+        return Ok((metadata.len(), false));
+    }
+    
+    let (freed, kept) = remove_contents(path, exclusions)?;
+    if !kept {
+        fs::remove_dir(path)?;
+    }
+    Ok((freed, kept))
+}
+
+/// Delete everything below `dir` except excluded entries, keeping `dir` itself
+/// 
+/// Returns the bytes freed and whether anything was kept.
+fn remove_contents(dir: &Path, exclusions: &[String]) -> Result<(u64, bool)> {
+    if is_excluded(dir, exclusions) {
+        return Ok((0, true));
+    }
+    
+    let mut freed = 0u64;
+    let mut kept = false;
+    for entry in list_entries(dir) {
+        let (entry_freed, entry_kept) = remove_tree(&entry, exclusions)?;
+        freed += entry_freed;
+        kept |= entry_kept;
+    }
+    Ok((freed, kept))
 }
 
 /// Scan system for cleanable items
-pub fn scan_cleanable_items() -> Result<Vec<CleanupItem>> {
+/// 
+/// Each of `custom_paths` that exists is reported as a `CleanupCategory::Custom` item.
+pub fn scan_cleanable_items(custom_paths: &[PathBuf]) -> Result<Vec<CleanupItem>> {
+    scan_cleanable_items_with_progress(custom_paths, &[], |_| {})
+}
+
+/// Scan system for cleanable items, reporting each category as it completes
+/// 
+/// Walking large caches and /tmp can take a while, so callers with a UI should run
+/// this on a worker thread and forward the progress to the main loop. Entries
+/// matching `exclusions` (see `clean_category_with_exclusions`) are left out of
+/// the sizes and reported as `CleanupItem::excluded` instead.
+pub fn scan_cleanable_items_with_progress(
+    custom_paths: &[PathBuf],
+    exclusions: &[String],
+    mut on_progress: impl FnMut(&CleanupCategory),
+) -> Result<Vec<CleanupItem>> {
     let mut items = Vec::new();
 
     // Package cache
@@ -104,36 +318,55 @@ pub fn scan_cleanable_items() -> Result<Vec<CleanupItem>> {
             size,
             count,
             paths: vec![apt_cache],
+            excluded: 0,
         });
     }
 
+    on_progress(&CleanupCategory::PackageCache);
+
     // Thumbnails
     let home = std::env::var("HOME").unwrap_or_default();
     let thumbnails = PathBuf::from(format!("{}/.cache/thumbnails", home));
     if thumbnails.exists() {
-        let size = calculate_dir_size(&thumbnails);
-        let count = count_files_in_dir(&thumbnails);
+        let measured = measure_excluding(&thumbnails, exclusions);
         items.push(CleanupItem {
             category: CleanupCategory::Thumbnails,
-            size,
-            count,
+            size: measured.size,
+            count: measured.count,
             paths: vec![thumbnails],
+            excluded: measured.excluded,
         });
     }
 
-    // Trash
-    let trash_files = PathBuf::from(format!("{}/.local/share/Trash/files", home));
-    if trash_files.exists() {
-        let size = calculate_dir_size(&trash_files);
-        let count = count_files_in_dir(&trash_files);
+    on_progress(&CleanupCategory::Thumbnails);
+
+    // Trash (home trash plus per-volume trash directories)
+    let mut trash_size = 0u64;
+    let mut trash_count = 0usize;
+    let mut trash_excluded = 0u64;
+    let mut trash_paths = Vec::new();
+    for trash_dir in trash_dirs() {
+        let trash_files = trash_dir.join("files");
+        if trash_files.exists() {
+            let measured = measure_excluding(&trash_files, exclusions);
+            trash_size += measured.size;
+            trash_count += measured.count;
+            trash_excluded += measured.excluded;
+            trash_paths.push(trash_files);
+        }
+    }
+    if !trash_paths.is_empty() {
         items.push(CleanupItem {
             category: CleanupCategory::Trash,
-            size,
-            count,
-            paths: vec![trash_files],
+            size: trash_size,
+            count: trash_count,
+            paths: trash_paths,
+            excluded: trash_excluded,
         });
     }
 
+    on_progress(&CleanupCategory::Trash);
+
     // Old logs (journalctl)
     let journal_path = PathBuf::from("/var/log/journal");
     if journal_path.exists() {
@@ -144,139 +377,1025 @@ pub fn scan_cleanable_items() -> Result<Vec<CleanupItem>> {
             size,
             count,
             paths: vec![journal_path],
+            excluded: 0,
         });
     }
 
+    on_progress(&CleanupCategory::Logs);
+
+    // Rotated logs
+    let (rotated, rotated_kept): (Vec<PathBuf>, Vec<PathBuf>) = find_rotated_logs()
+        .into_iter()
+        .partition(|p| !is_excluded(p, exclusions));
+    if !rotated.is_empty() || !rotated_kept.is_empty() {
+        let file_size = |p: &PathBuf| p.symlink_metadata().map(|m| m.len()).unwrap_or(0);
+        items.push(CleanupItem {
+            category: CleanupCategory::RotatedLogs,
+            size: rotated.iter().map(file_size).sum(),
+            count: rotated.len(),
+            paths: rotated,
+            excluded: rotated_kept.iter().map(file_size).sum(),
+        });
+    }
+
+    on_progress(&CleanupCategory::RotatedLogs);
+
+    // Old kernels
+    if let Some(item) = scan_old_kernels() {
+        items.push(item);
+    }
+
+    on_progress(&CleanupCategory::OldKernels);
+
     // Browser caches
-    let firefox_cache = PathBuf::from(format!("{}/.cache/mozilla/firefox", home));
-    let chrome_cache = PathBuf::from(format!("{}/.cache/google-chrome", home));
     let mut browser_size = 0u64;
     let mut browser_count = 0usize;
+    let mut browser_excluded = 0u64;
     let mut browser_paths = Vec::new();
 
-    if firefox_cache.exists() {
-        browser_size += calculate_dir_size(&firefox_cache);
-        browser_count += count_files_in_dir(&firefox_cache);
-        browser_paths.push(firefox_cache);
-    }
-    if chrome_cache.exists() {
-        browser_size += calculate_dir_size(&chrome_cache);
-        browser_count += count_files_in_dir(&chrome_cache);
-        browser_paths.push(chrome_cache);
+    for cache in browser_cache_dirs() {
+        if cache.exists() {
+            let measured = measure_excluding(&cache, exclusions);
+            browser_size += measured.size;
+            browser_count += measured.count;
+            browser_excluded += measured.excluded;
+            browser_paths.push(cache);
+        }
     }
 
-    if browser_size > 0 {
+    if browser_size > 0 || browser_excluded > 0 {
         items.push(CleanupItem {
             category: CleanupCategory::BrowserCache,
             size: browser_size,
             count: browser_count,
             paths: browser_paths,
+            excluded: browser_excluded,
         });
     }
 
-    // Temp files
+    on_progress(&CleanupCategory::BrowserCache);
+
+    // Temp files (only what clean_temp_files would actually remove)
     let tmp = PathBuf::from("/tmp");
     if tmp.exists() {
-        let size = calculate_dir_size(&tmp);
-        let count = count_files_in_dir(&tmp);
-        if count > 0 {
+        let entries = select_temp_entries(&tmp, SystemTime::now(), &open_paths_under(&tmp));
+        let mut size = 0u64;
+        let mut count = 0usize;
+        let mut excluded = 0u64;
+        for entry in &entries {
+            let measured = measure_excluding(entry, exclusions);
+            size += measured.size;
+            count += measured.count;
+            excluded += measured.excluded;
+        }
+        if count > 0 || excluded > 0 {
             items.push(CleanupItem {
                 category: CleanupCategory::TempFiles,
                 size,
                 count,
-                paths: vec![tmp],
+                paths: entries,
+                excluded,
             });
         }
     }
 
+    on_progress(&CleanupCategory::TempFiles);
+
+    // Container images and build cache
+    if let Some(item) = scan_container_cache() {
+        items.push(item);
+    }
+
+    on_progress(&CleanupCategory::ContainerCache);
+
+    // User-chosen paths
+    for path in custom_paths {
+        let category = CleanupCategory::Custom(path.clone());
+        if path.exists() {
+            let measured = measure_excluding(path, exclusions);
+            items.push(CleanupItem {
+                category: category.clone(),
+                size: measured.size,
+                count: measured.count,
+                paths: vec![path.clone()],
+                excluded: measured.excluded,
+            });
+        }
+        on_progress(&category);
+    }
+
     Ok(items)
 }
 
-/// Clean package cache using apt-get clean
-pub fn clean_package_cache() -> Result<()> {
-    Command::new("pkexec")
-        .args(&["apt-get", "clean"])
-        .output()?;
-    Ok(())
+/// A top-level entry that cleaning the temp files category would touch
+#[derive(Debug, Clone)]
+pub struct TempEntry {
+    pub path: PathBuf,
+    /// False for entries owned by root or other users, which need elevated rights
+    pub owned_by_user: bool,
+}
+
+/// List the direct children of a directory
+fn list_entries(path: &Path) -> Vec<PathBuf> {
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default()
 }
 
-/// Clean thumbnails cache
-pub fn clean_thumbnails() -> Result<()> {
+/// Preview the paths that cleaning a category would remove
+/// 
+/// Computed the same way the cleaners operate, without deleting anything. For
+/// `OldKernels` the entries are package names, since apt removes whole packages.
+/// For `ContainerCache` they are the runtime storage directories, since the
+/// runtime itself decides what to prune.
+pub fn preview_clean(category: &CleanupCategory) -> Result<Vec<PathBuf>> {
     let home = std::env::var("HOME").unwrap_or_default();
-    let thumbnails = PathBuf::from(format!("{}/.cache/thumbnails", home));
-    if thumbnails.exists() {
-        fs::remove_dir_all(&thumbnails)?;
-        fs::create_dir_all(&thumbnails)?;
+    
+    let paths = match category {
+        CleanupCategory::PackageCache => {
+            // apt-get clean empties archives/ and archives/partial/ but keeps the lock
+            let archives = PathBuf::from("/var/cache/apt/archives");
+            let mut paths: Vec<PathBuf> = list_entries(&archives)
+                .into_iter()
+                .filter(|p| p.is_file() && p.file_name().is_some_and(|n| n != "lock"))
+                .collect();
+            paths.extend(list_entries(&archives.join("partial")));
+            paths
+        }
+        CleanupCategory::Thumbnails => {
+            list_entries(&PathBuf::from(format!("{}/.cache/thumbnails", home)))
+        }
+        CleanupCategory::Trash => {
+            let mut paths = Vec::new();
+            for trash_dir in trash_dirs() {
+                paths.extend(list_entries(&trash_dir.join("files")));
+                paths.extend(list_entries(&trash_dir.join("info")));
+            }
+            paths
+        }
+        CleanupCategory::Logs => {
+            // journalctl --vacuum-time=7d only drops archived journal files
+            let cutoff = SystemTime::now() - Duration::from_secs(7 * 24 * 60 * 60);
+            let mut paths = Vec::new();
+            for machine_dir in list_entries(Path::new("/var/log/journal")) {
+                for file in list_entries(&machine_dir) {
+                    let name = file.file_name().unwrap_or_default().to_string_lossy().to_string();
+                    let archived = (name.ends_with(".journal") && name.contains('@')) || name.ends_with(".journal~");
+                    let old = fs::metadata(&file)
+                        .and_then(|m| m.modified())
+                        .map(|t| t < cutoff)
+                        .unwrap_or(false);
+                    if archived && old {
+                        paths.push(file);
+                    }
+                }
+            }
+            paths
+        }
+        CleanupCategory::RotatedLogs => find_rotated_logs(),
+        CleanupCategory::OldKernels => {
+            find_old_kernels().into_iter().map(|(name, _)| PathBuf::from(name)).collect()
+        }
+        CleanupCategory::BrowserCache => {
+            let mut paths = list_entries(&PathBuf::from(format!("{}/.cache/mozilla/firefox", home)));
+            paths.extend(list_entries(&PathBuf::from(format!("{}/.cache/google-chrome", home))));
+            paths
+        }
+        CleanupCategory::TempFiles => {
+            preview_temp_files()?.into_iter().map(|e| e.path).collect()
+        }
+        CleanupCategory::ContainerCache => {
+            container_runtimes()
+                .into_iter()
+                .map(container_storage_dir)
+                .filter(|p| p.exists())
+                .collect()
+        }
+        CleanupCategory::Custom(path) => {
+            if path.is_dir() { list_entries(path) } else { vec![path.clone()] }
+        }
+    };
+    
+    Ok(paths)
+}
+
+/// Preview the entries in /tmp, noting which belong to the current user
+/// 
+/// Uses the same selection as `clean_temp_files`. Entries owned by root or other
+/// users can only be removed with elevated rights.
+pub fn preview_temp_files() -> Result<Vec<TempEntry>> {
+    let uid = getuid().as_raw();
+    let tmp = Path::new("/tmp");
+    
+    let entries = select_temp_entries(tmp, SystemTime::now(), &open_paths_under(tmp))
+        .into_iter()
+        .map(|path| {
+            let owned_by_user = path.symlink_metadata()
+                .map(|m| m.uid() == uid)
+                .unwrap_or(false);
+            TempEntry { path, owned_by_user }
+        })
+        .collect();
+    
+    Ok(entries)
+}
+
+/// Temp entries modified more recently than this are assumed to be in use
+const TEMP_MIN_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Temp entries of running sessions and services, never deleted whatever their age
+/// 
+/// systemd PrivateTmp and snap directories, the X11/ICE/font socket
+/// directories and tmux server sockets, for any user.
+const TEMP_KEEP_PREFIXES: [&str; 8] = [
+    "systemd-private-",
+    "snap-private-tmp",
+    ".X11-unix",
+    ".ICE-unix",
+    ".font-unix",
+    ".XIM-unix",
+    ".Test-unix",
+    "tmux-",
+];
+
+/// Select the top-level entries of a temp directory that are safe to delete
+/// 
+/// Skips anything modified within `TEMP_MIN_AGE` of `now` (looking inside
+/// directories), anything that is or contains a socket, the session and service
+/// directories in `TEMP_KEEP_PREFIXES`, and anything under one of `open_paths`.
+fn select_temp_entries(dir: &Path, now: SystemTime, open_paths: &HashSet<PathBuf>) -> Vec<PathBuf> {
+    let cutoff = now.checked_sub(TEMP_MIN_AGE).unwrap_or(SystemTime::UNIX_EPOCH);
+    
+    list_entries(dir)
+        .into_iter()
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if TEMP_KEEP_PREFIXES.iter().any(|prefix| name.starts_with(prefix)) {
+                return false;
+            }
+            if open_paths.iter().any(|open| open.starts_with(path)) {
+                return false;
+            }
+            !is_recent_or_socket(path, cutoff)
+        })
+        .collect()
+}
+
+/// Check whether a path, or anything below it, is a socket or newer than `cutoff`
+/// 
+/// Symlinks are not followed.
+fn is_recent_or_socket(path: &Path, cutoff: SystemTime) -> bool {
+    let Ok(metadata) = path.symlink_metadata() else {
+        return false;
+    };
+    
+    if metadata.file_type().is_socket() {
+        return true;
     }
-    Ok(())
+    if metadata.modified().map(|t| t > cutoff).unwrap_or(false) {
+        return true;
+    }
+    
+    if metadata.is_dir() {
+        return list_entries(path).iter().any(|child| is_recent_or_socket(child, cutoff));
+    }
+    
+    false
+}
+
+/// Collect paths under `dir` that running processes hold open or use as cwd
+/// 
+/// Only processes whose /proc entries we may read are visible, which for an
+/// unprivileged user means their own processes.
+fn open_paths_under(dir: &Path) -> HashSet<PathBuf> {
+    let mut open = HashSet::new();
+    
+    for proc_entry in list_entries(Path::new("/proc")) {
+        let is_pid = proc_entry.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.chars().all(|c| c.is_ascii_digit()));
+        if !is_pid {
+            continue;
+        }
+        
+        let mut links = list_entries(&proc_entry.join("fd"));
+        links.push(proc_entry.join("cwd"));
+        
+        for link in links {
+            if let Ok(target) = fs::read_link(&link) {
+                if target.starts_with(dir) {
+                    open.insert(target);
+                }
+            }
+        }
+    }
+    
+    open
+}
+
+/// Find installed kernel packages that are neither running nor the newest
+/// 
+/// Reports the installed size from dpkg. `paths` holds package names.
+fn scan_old_kernels() -> Option<CleanupItem> {
+    let old = find_old_kernels();
+    if old.is_empty() {
+        return None;
+    }
+    
+    Some(CleanupItem {
+        category: CleanupCategory::OldKernels,
+        size: old.iter().map(|(_, size)| size).sum(),
+        count: old.len(),
+        paths: old.into_iter().map(|(name, _)| PathBuf::from(name)).collect(),
+        excluded: 0,
+    })
+}
+
+/// Installed kernel packages that are neither running nor the newest, with their size
+/// 
+/// Scan, preview and clean all use this list, so exactly the packages shown
+/// are the ones purged. Empty when dpkg-query or uname can't be run.
+fn find_old_kernels() -> Vec<(String, u64)> {
+    let Ok(output) = Command::new("dpkg-query")
+        .args(["-W", "-f=${Package}\t${Installed-Size}\t${db:Status-Abbrev}\n", "linux-image-*", "linux-headers-*"])
+        .output()
+    else {
+        return Vec::new();
+    };
+    
+    let Ok(running) = Command::new("uname").arg("-r").output() else {
+        return Vec::new();
+    };
+    let running = String::from_utf8_lossy(&running.stdout).trim().to_string();
+    
+    select_old_kernels(&String::from_utf8_lossy(&output.stdout), &running)
+}
+
+/// The command that purges the given kernel packages
+fn purge_kernels_command(packages: Vec<String>) -> HelperCommand {
+    HelperCommand::Apt { action: AptAction::Purge, packages }
+}
+
+/// Pick the removable kernel packages out of dpkg-query output
+/// 
+/// Input lines are `package<TAB>installed-size-KiB<TAB>status`. Returns package
+/// names with their size in bytes, excluding the running and the newest kernel.
+fn select_old_kernels(dpkg_output: &str, running: &str) -> Vec<(String, u64)> {
+    let mut packages = Vec::new();
+    
+    for line in dpkg_output.lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 3 || !fields[2].starts_with("ii") {
+            continue;
+        }
+        
+        let version = fields[0]
+            .strip_prefix("linux-image-")
+            .or_else(|| fields[0].strip_prefix("linux-headers-"))
+            .unwrap_or("");
+        
+        // Skip meta packages such as linux-image-generic
+        let Some(key) = kernel_version_key(version) else {
+            continue;
+        };
+        
+        let size = fields[1].trim().parse::<u64>().unwrap_or(0) * 1024;
+        packages.push((fields[0].to_string(), key, size));
+    }
+    
+    let newest = packages.iter().map(|(_, key, _)| key.clone()).max();
+    let running_key = kernel_version_key(running);
+    
+    packages
+        .into_iter()
+        .filter(|(_, key, _)| Some(key) != newest.as_ref() && Some(key) != running_key.as_ref())
+        .map(|(name, _, size)| (name, size))
+        .collect()
+}
+
+/// Numeric version components of a kernel release like `6.5.0-14-generic`
+fn kernel_version_key(release: &str) -> Option<Vec<u64>> {
+    if !release.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    
+    let key: Vec<u64> = release
+        .split(['.', '-'])
+        .map_while(|part| part.parse().ok())
+        .collect();
+    
+    if key.is_empty() { None } else { Some(key) }
+}
+
+/// Container runtimes the cleaner knows how to prune
+const CONTAINER_RUNTIMES: [&str; 2] = ["docker", "podman"];
+
+/// Check whether an executable with the given name exists on $PATH
+fn binary_in_path(binary: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(binary).is_file()))
+        .unwrap_or(false)
+}
+
+/// Container runtimes installed on this system
+fn container_runtimes() -> Vec<&'static str> {
+    CONTAINER_RUNTIMES.into_iter().filter(|rt| binary_in_path(rt)).collect()
+}
+
+/// Where a runtime keeps its images: system-wide for Docker, per user for rootless Podman
+fn container_storage_dir(runtime: &str) -> PathBuf {
+    if runtime == "docker" {
+        PathBuf::from("/var/lib/docker")
+    } else {
+        let home = std::env::var("HOME").unwrap_or_default();
+        PathBuf::from(format!("{}/.local/share/containers", home))
+    }
+}
+
+/// Reclaimable bytes reported by `<runtime> system df`
+/// 
+/// Returns `None` when the runtime can't be queried, e.g. when the Docker
+/// daemon is not running or the user is not allowed to talk to it.
+fn container_reclaimable(runtime: &str) -> Option<u64> {
+    let output = Command::new(runtime)
+        .args(["system", "df", "--format", "{{.Reclaimable}}"])
+        .output()
+        .ok()?;
+    
+    if !output.status.success() {
+        return None;
+    }
+    
+    Some(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_reclaimable)
+        .sum())
 }
 
-/// Empty trash
-pub fn empty_trash() -> Result<()> {
+/// Parse a reclaimable column such as `1.2GB (45%)` or `512.3kB` into bytes
+/// 
+/// Docker and Podman both print decimal (SI) units.
+fn parse_reclaimable(value: &str) -> Option<u64> {
+    let size = value.split_whitespace().next()?;
+    let unit_start = size.find(|c: char| c.is_ascii_alphabetic())?;
+    let (number, unit) = size.split_at(unit_start);
+    let number: f64 = number.parse().ok()?;
+    
+    let multiplier = match unit.to_ascii_uppercase().as_str() {
+        "B" => 1.0,
+        "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        "PB" => 1e15,
+        _ => return None,
+    };
+    
+    Some((number * multiplier) as u64)
+}
+
+/// Scan the installed container runtimes for reclaimable space
+/// 
+/// Only reported when at least one runtime binary is present. `size` is the
+/// raw reclaimable byte count summed over the runtimes that answered.
+fn scan_container_cache() -> Option<CleanupItem> {
+    let runtimes = container_runtimes();
+    if runtimes.is_empty() {
+        return None;
+    }
+    
+    let size = runtimes.iter()
+        .filter_map(|rt| container_reclaimable(rt))
+        .sum();
+    let paths: Vec<PathBuf> = runtimes.into_iter()
+        .map(container_storage_dir)
+        .filter(|p| p.exists())
+        .collect();
+    
+    Some(CleanupItem {
+        category: CleanupCategory::ContainerCache,
+        size,
+        count: paths.len(),
+        paths,
+        excluded: 0,
+    })
+}
+
+/// Prune unused images, stopped containers and build cache
+/// 
+/// Docker talks to a root daemon, so it is pruned as root; rootless
+/// Podman is pruned as the current user. Returns the number of bytes freed.
+pub fn clean_container_cache() -> Result<u64> {
+    run_staged(stage_container_cache()?)
+}
+
+fn stage_container_cache() -> Result<StagedClean> {
+    let mut freed = 0u64;
+    let mut staged = None;
+    
+    for runtime in container_runtimes() {
+        if runtime == "docker" {
+            staged = Some((
+                vec![HelperCommand::PruneDocker],
+                || container_reclaimable("docker").unwrap_or(0),
+            ));
+            continue;
+        }
+        
+        let before = container_reclaimable(runtime).unwrap_or(0);
+        let output = Command::new(runtime)
+            .args(["system", "prune", "-f"])
+            .output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} system prune failed: {}",
+                runtime,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        
+        let after = container_reclaimable(runtime).unwrap_or(0);
+        freed += before.saturating_sub(after);
+    }
+    
+    Ok(match staged {
+        Some((commands, measure)) => StagedClean::privileged(freed, commands, measure),
+        None => StagedClean::unprivileged(freed),
+    })
+}
+
+/// Clean package cache using apt-get clean, returning the number of bytes freed
+pub fn clean_package_cache() -> Result<u64> {
+    run_staged(stage_package_cache())
+}
+
+fn stage_package_cache() -> StagedClean {
+    StagedClean::privileged(
+        0,
+        vec![HelperCommand::Apt { action: AptAction::Clean, packages: Vec::new() }],
+        || calculate_dir_size(Path::new("/var/cache/apt/archives")),
+    )
+}
+
+fn thumbnails_dir() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_default();
+    PathBuf::from(format!("{}/.cache/thumbnails", home))
+}
+
+/// Clean thumbnails cache, returning the number of bytes freed
+/// 
+/// Entries matching `exclusions` are kept.
+pub fn clean_thumbnails(exclusions: &[String]) -> Result<u64> {
+    Ok(remove_contents(&thumbnails_dir(), exclusions)?.0)
+}
+
+/// Find all trash directories belonging to the current user
+/// 
+/// Following the freedesktop trash spec this is the home trash plus, on every
+/// mounted volume, `$topdir/.Trash/$uid` and `$topdir/.Trash-$uid`. Volume trash
+/// directories are only returned when they are real directories owned by us.
+fn trash_dirs() -> Vec<PathBuf> {
     let home = std::env::var("HOME").unwrap_or_default();
-    let trash_files = PathBuf::from(format!("{}/.local/share/Trash/files", home));
-    let trash_info = PathBuf::from(format!("{}/.local/share/Trash/info", home));
+    let uid = getuid().as_raw();
+    let mut dirs = vec![PathBuf::from(format!("{}/.local/share/Trash", home))];
     
-    if trash_files.exists() {
-        fs::remove_dir_all(&trash_files)?;
-        fs::create_dir_all(&trash_files)?;
+    if let Ok(mounts) = disk_analyzer::get_mount_points() {
+        for mount in mounts {
+            let candidates = [
+                mount.mount_point.join(".Trash").join(uid.to_string()),
+                mount.mount_point.join(format!(".Trash-{}", uid)),
+            ];
+            
+            for candidate in candidates {
+                let owned_dir = candidate.symlink_metadata()
+                    .map(|m| m.is_dir() && m.uid() == uid)
+                    .unwrap_or(false);
+                if owned_dir && !dirs.contains(&candidate) {
+                    dirs.push(candidate);
+                }
+            }
+        }
     }
-    if trash_info.exists() {
-        fs::remove_dir_all(&trash_info)?;
-        fs::create_dir_all(&trash_info)?;
+    
+    dirs
+}
+
+/// Empty trash on all mounted volumes, returning the number of bytes freed
+/// 
+/// Entries matching `exclusions` are kept, along with the `.trashinfo` of any
+/// trashed item that is still there, so it can be restored.
+pub fn empty_trash(exclusions: &[String]) -> Result<u64> {
+    let mut freed = 0u64;
+    for trash_dir in trash_dirs() {
+        let trash_files = trash_dir.join("files");
+        freed += remove_contents(&trash_files, exclusions)?.0;
+        
+        for info in list_entries(&trash_dir.join("info")) {
+            let name = info.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let trashed = trash_files.join(name.strip_suffix(".trashinfo").unwrap_or(&name));
+            if trashed.symlink_metadata().is_err() {
+                freed += remove_tree(&info, &[])?.0;
+            }
+        }
     }
-    Ok(())
+    Ok(freed)
 }
 
 /// Clean old system logs using journalctl
-pub fn clean_old_logs() -> Result<()> {
-    Command::new("pkexec")
-        .args(&["journalctl", "--vacuum-time=7d"])
-        .output()?;
-    Ok(())
+pub fn clean_old_logs() -> Result<u64> {
+    clean_old_logs_with_age(LOG_MAX_AGE_DAYS)
+}
+
+/// Remove archived journal files older than the given number of days
+/// 
+/// Returns the number of bytes freed, measured on /var/log/journal.
+pub fn clean_old_logs_with_age(days: u32) -> Result<u64> {
+    vacuum_journal(JournalLimit::Days(days))
+}
+
+/// Remove archived journal files until the journal fits in the given size
+/// 
+/// Returns the number of bytes freed, measured on /var/log/journal.
+pub fn clean_old_logs_to_size(bytes: u64) -> Result<u64> {
+    vacuum_journal(JournalLimit::Bytes(bytes))
+}
+
+/// Run a journalctl vacuum and measure how much the journal shrank
+fn vacuum_journal(limit: JournalLimit) -> Result<u64> {
+    run_staged(stage_journal_vacuum(limit))
+}
+
+fn stage_journal_vacuum(limit: JournalLimit) -> StagedClean {
+    StagedClean::privileged(
+        0,
+        vec![HelperCommand::VacuumJournal(limit)],
+        || calculate_dir_size(Path::new("/var/log/journal")),
+    )
 }
 
-/// Clean browser caches
-pub fn clean_browser_cache() -> Result<()> {
+/// Find compressed rotated logs (`*.gz`) under /var/log
+/// 
+/// The journal directory is skipped; it is handled by `clean_old_logs`. So are
+/// logs the privileged helper won't delete, e.g. in /var/log/mysql, which is
+/// owned by the service user rather than root.
+fn find_rotated_logs() -> Vec<PathBuf> {
+    fn walk(dir: &Path, found: &mut Vec<PathBuf>) {
+        for path in list_entries(dir) {
+            let Ok(metadata) = path.symlink_metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                if path != Path::new("/var/log/journal") {
+                    walk(&path, found);
+                }
+            } else if metadata.is_file() && path.extension().is_some_and(|e| e == "gz") {
+                found.push(path);
+            }
+        }
+    }
+    
+    let mut found = Vec::new();
+    walk(Path::new("/var/log"), &mut found);
+    found.retain(|path| privileged::helper_may_delete(path));
+    found
+}
+
+/// Delete compressed rotated logs under /var/log (requires root)
+/// 
+/// Logs matching `exclusions` are kept. Returns the number of bytes freed.
+pub fn clean_rotated_logs(exclusions: &[String]) -> Result<u64> {
+    run_staged(stage_rotated_logs(exclusions))
+}
+
+fn stage_rotated_logs(exclusions: &[String]) -> StagedClean {
+    let rotated: Vec<PathBuf> = find_rotated_logs()
+        .into_iter()
+        .filter(|p| !is_excluded(p, exclusions))
+        .collect();
+    if rotated.is_empty() {
+        return StagedClean::unprivileged(0);
+    }
+    
+    let delete = HelperCommand::Delete(rotated.clone());
+    StagedClean::privileged(0, vec![delete], move || {
+        rotated.iter().map(|p| path_size(p)).sum()
+    })
+}
+
+/// A cleanup carried out as far as possible without root
+/// 
+/// `commands` finish the job as root. `measure` sizes what they are meant to
+/// remove; it is taken once when staging and again after the commands ran.
+struct StagedClean {
+    /// Bytes already freed as the current user
+    freed: u64,
+    commands: Vec<HelperCommand>,
+    measure: Box<dyn Fn() -> u64>,
+    before: u64,
+}
+
+impl StagedClean {
+    fn unprivileged(freed: u64) -> Self {
+        Self {
+            freed,
+            commands: Vec::new(),
+            measure: Box::new(|| 0),
+            before: 0,
+        }
+    }
+    
+    fn privileged(freed: u64, commands: Vec<HelperCommand>, measure: impl Fn() -> u64 + 'static) -> Self {
+        let before = measure();
+        Self {
+            freed,
+            commands,
+            measure: Box::new(measure),
+            before,
+        }
+    }
+    
+    /// Total bytes freed, once the commands have run
+    fn finish(&self) -> u64 {
+        self.freed + self.before.saturating_sub((self.measure)())
+    }
+}
+
+/// Run a staged cleanup's root commands and return the bytes freed
+fn run_staged(staged: StagedClean) -> Result<u64> {
+    privileged::run_commands(&staged.commands)?;
+    Ok(staged.finish())
+}
+
+/// Firefox and Chrome cache directories of the current user
+fn browser_cache_dirs() -> [PathBuf; 2] {
     let home = std::env::var("HOME").unwrap_or_default();
-    let firefox_cache = PathBuf::from(format!("{}/.cache/mozilla/firefox", home));
-    let chrome_cache = PathBuf::from(format!("{}/.cache/google-chrome", home));
+    [
+        PathBuf::from(format!("{}/.cache/mozilla/firefox", home)),
+        PathBuf::from(format!("{}/.cache/google-chrome", home)),
+    ]
+}
+
+/// Clean browser caches, returning the number of bytes freed
+/// 
+/// Entries matching `exclusions`, e.g. the cache of one Firefox profile, are kept.
+pub fn clean_browser_cache(exclusions: &[String]) -> Result<u64> {
+    let mut freed = 0u64;
+    for cache in browser_cache_dirs() {
+        freed += remove_contents(&cache, exclusions)?.0;
+    }
+    Ok(freed)
+}
+
+/// Clean temporary files in /tmp
+/// 
+/// Entries are enumerated in Rust (see `select_temp_entries`) and removed one by
+/// one. Our own entries are deleted directly; whatever is left is passed as
+/// explicit paths to a single privileged delete, so no shell glob is involved.
+/// Entries matching `exclusions` are kept, and so is anything we can't delete
+/// ourselves that has an excluded entry below it. Returns the number of bytes freed.
+pub fn clean_temp_files(exclusions: &[String]) -> Result<u64> {
+    run_staged(stage_temp_files(exclusions))
+}
+
+fn stage_temp_files(exclusions: &[String]) -> StagedClean {
+    let uid = getuid().as_raw();
+    let tmp = Path::new("/tmp");
+    let mut remaining = Vec::new();
+    let mut freed = 0u64;
     
-    if firefox_cache.exists() {
-        fs::remove_dir_all(&firefox_cache)?;
-        fs::create_dir_all(&firefox_cache)?;
+    for path in select_temp_entries(tmp, SystemTime::now(), &open_paths_under(tmp)) {
+        let owned = path.symlink_metadata()
+            .map(|m| m.uid() == uid)
+            .unwrap_or(false);
+        
+        let removed = if owned { remove_tree(&path, exclusions).ok() } else { None };
+        
+        match removed {
+            Some((size, _)) => freed += size,
+            None if measure_excluding(&path, exclusions).excluded_entries == 0 => remaining.push(path),
+            None => {}
+        }
     }
-    if chrome_cache.exists() {
-        fs::remove_dir_all(&chrome_cache)?;
-        fs::create_dir_all(&chrome_cache)?;
+    
+    if remaining.is_empty() {
+        return StagedClean::unprivileged(freed);
     }
-    Ok(())
+    
+    let delete = HelperCommand::Delete(remaining.clone());
+    StagedClean::privileged(freed, vec![delete], move || {
+        remaining.iter().map(|path| path_size(path)).sum()
+    })
 }
 
-/// Clean temporary files (requires root)
-pub fn clean_temp_files() -> Result<()> {
-    Command::new("pkexec")
-        .args(&["rm", "-rf", "/tmp/*"])
-        .output()?;
-    Ok(())
+/// Purge the old kernel packages by name, measured by their installed size
+/// 
+/// Only the packages `find_old_kernels` lists are purged, never whatever else
+/// apt considers auto-removable.
+fn stage_old_kernels() -> StagedClean {
+    let old = find_old_kernels();
+    if old.is_empty() {
+        return StagedClean::unprivileged(0);
+    }
+    
+    let names: Vec<String> = old.into_iter().map(|(name, _)| name).collect();
+    let purge = purge_kernels_command(names.clone());
+    StagedClean::privileged(0, vec![purge], move || {
+        find_old_kernels()
+            .into_iter()
+            .filter(|(name, _)| names.contains(name))
+            .map(|(_, size)| size)
+            .sum()
+    })
 }
 
-/// Clean a specific category
-pub fn clean_category(category: &CleanupCategory) -> Result<()> {
-    match category {
-        CleanupCategory::PackageCache => clean_package_cache(),
-        CleanupCategory::Thumbnails => clean_thumbnails(),
-        CleanupCategory::Trash => empty_trash(),
-        CleanupCategory::Logs => clean_old_logs(),
-        CleanupCategory::BrowserCache => clean_browser_cache(),
-        CleanupCategory::TempFiles => clean_temp_files(),
+/// Clean a specific category, returning the number of bytes freed
+/// 
+/// Sizes are measured in-process before and after cleaning, including for the
+/// categories that delete as root. With `dry_run` nothing is deleted;
+/// the planned deletions and root commands are returned instead.
+pub fn clean_category(category: &CleanupCategory, exclusions: &[String], dry_run: bool) -> Result<Outcome<u64>> {
+    if dry_run {
+        return Ok(Outcome::Simulated(plan_category(category, exclusions)?));
+    }
+    
+    Ok(Outcome::Done(clean_category_with_exclusions(category, exclusions)?))
+}
+
+/// Clean a specific category, keeping entries that match `exclusions`
+/// 
+/// Each exclusion is a path or a glob pattern: with a `/` it is matched
+/// against whole paths (`~/` is expanded), otherwise against file names, and
+/// `*` and `?` don't cross a `/`. For example `~/.cache/mozilla/firefox/*.default`
+/// keeps the cache of the default Firefox profile. Everything below an excluded
+/// directory is kept, and the directories leading to it are emptied but not removed.
+/// 
+/// Exclusions apply to the categories cleaned file by file. The package cache,
+/// journal, old kernels and container cache are cleaned by their own tools and
+/// ignore them. Returns the number of bytes freed.
+pub fn clean_category_with_exclusions(category: &CleanupCategory, exclusions: &[String]) -> Result<u64> {
+    run_staged(stage_category(category, exclusions)?)
+}
+
+/// Describe what cleaning a category would change, without changing anything
+fn plan_category(category: &CleanupCategory, exclusions: &[String]) -> Result<Vec<String>> {
+    let describe = |verb: &str, path: &PathBuf| {
+        let measured = measure_excluding(path, exclusions);
+        if measured.excluded_entries > 0 {
+            format!("{} {} ({}, keeping {} excluded)", verb, path.display(), format_size(measured.size), format_size(measured.excluded))
+        } else {
+            format!("{} {} ({})", verb, path.display(), format_size(measured.size))
+        }
+    };
+    let delete = |path: &PathBuf| describe("Delete", path);
+    let empty = |path: &PathBuf| describe("Empty", path);
+    let run_as_root = |args: &[&str]| format!("Run as root: {}", args.join(" "));
+    
+    let plan = match category {
+        CleanupCategory::PackageCache => vec![run_as_root(&["apt-get", "clean"])],
+        CleanupCategory::Thumbnails => [thumbnails_dir()].iter()
+            .filter(|dir| dir.exists())
+            .map(empty)
+            .collect(),
+        CleanupCategory::Trash => trash_dirs().into_iter()
+            .flat_map(|dir| [dir.join("files"), dir.join("info")])
+            .filter(|dir| dir.exists())
+            .map(|dir| empty(&dir))
+            .collect(),
+        CleanupCategory::Logs => vec![
+            run_as_root(&["journalctl", &format!("--vacuum-time={}d", LOG_MAX_AGE_DAYS)])
+        ],
+        CleanupCategory::RotatedLogs => find_rotated_logs().iter()
+            .filter(|p| !is_excluded(p, exclusions))
+            .map(delete)
+            .collect(),
+        CleanupCategory::BrowserCache => browser_cache_dirs().iter()
+            .filter(|dir| dir.exists())
+            .map(empty)
+            .collect(),
+        CleanupCategory::TempFiles => {
+            let tmp = Path::new("/tmp");
+            select_temp_entries(tmp, SystemTime::now(), &open_paths_under(tmp))
+                .iter()
+                .filter(|p| !is_excluded(p, exclusions))
+                .map(delete)
+                .collect()
+        }
         CleanupCategory::OldKernels => {
-            Command::new("pkexec")
-                .args(&["apt-get", "autoremove", "--purge", "-y"])
-                .output()?;
-            Ok(())
+            let names: Vec<String> = find_old_kernels().into_iter().map(|(name, _)| name).collect();
+            if names.is_empty() {
+                Vec::new()
+            } else {
+                let argv = purge_kernels_command(names).argv();
+                vec![run_as_root(&argv.iter().map(String::as_str).collect::<Vec<_>>())]
+            }
+        }
+        CleanupCategory::ContainerCache => container_runtimes().into_iter()
+            .map(|runtime| match runtime {
+                "docker" => run_as_root(&["docker", "system", "prune", "-f"]),
+                _ => format!("Run: {} system prune -f", runtime),
+            })
+            .collect(),
+        CleanupCategory::Custom(path) => {
+            ensure_cleanable(path)?;
+            match path.symlink_metadata() {
+                Ok(metadata) if metadata.is_dir() => list_entries(path).iter()
+                    .filter(|p| !is_excluded(p, exclusions))
+                    .map(delete)
+                    .collect(),
+                Ok(_) => vec![delete(path)],
+                Err(_) => Vec::new(),
+            }
         }
+    };
+    
+    Ok(plan)
+}
+
+/// Clean several categories, asking for authentication at most once
+/// 
+/// Each category's unprivileged work is done first, then the root commands of
+/// all of them go to the privileged helper together. Returns the bytes freed per
+/// category, in the order given. If the batch fails, every category that
+/// needed root reports that failure. `exclusions` work as in
+/// `clean_category_with_exclusions`.
+pub fn clean_categories(categories: &[CleanupCategory], exclusions: &[String]) -> Vec<Result<u64>> {
+    let staged: Vec<Result<StagedClean>> = categories.iter()
+        .map(|category| stage_category(category, exclusions))
+        .collect();
+    
+    let commands: Vec<HelperCommand> = staged.iter()
+        .flatten()
+        .flat_map(|staged| staged.commands.iter().cloned())
+        .collect();
+    let batch = privileged::run_commands(&commands);
+    
+    staged.into_iter()
+        .map(|staged| {
+            let staged = staged?;
+            match &batch {
+                Err(e) if !staged.commands.is_empty() => Err(batch_error(e)),
+                _ => Ok(staged.finish()),
+            }
+        })
+        .collect()
+}
+
+/// Copy a batch failure for each category it affects, keeping dismissals recognisable
+fn batch_error(e: &anyhow::Error) -> anyhow::Error {
+    if e.is::<AuthenticationDismissed>() {
+        AuthenticationDismissed.into()
+    } else {
+        anyhow::anyhow!("{:#}", e)
+    }
+}
+
+fn stage_category(category: &CleanupCategory, exclusions: &[String]) -> Result<StagedClean> {
+    Ok(match category {
+        CleanupCategory::PackageCache => stage_package_cache(),
+        CleanupCategory::Thumbnails => StagedClean::unprivileged(clean_thumbnails(exclusions)?),
+        CleanupCategory::Trash => StagedClean::unprivileged(empty_trash(exclusions)?),
+        CleanupCategory::Logs => stage_journal_vacuum(JournalLimit::Days(LOG_MAX_AGE_DAYS)),
+        CleanupCategory::RotatedLogs => stage_rotated_logs(exclusions),
+        CleanupCategory::BrowserCache => StagedClean::unprivileged(clean_browser_cache(exclusions)?),
+        CleanupCategory::TempFiles => stage_temp_files(exclusions),
+        CleanupCategory::OldKernels => stage_old_kernels(),
+        CleanupCategory::ContainerCache => stage_container_cache()?,
+        CleanupCategory::Custom(path) => StagedClean::unprivileged(clean_paths(std::slice::from_ref(path), exclusions)?),
+    })
+}
+
+/// Clean user-chosen paths, returning the number of bytes freed
+/// 
+/// Directories are emptied but kept, files are deleted. Entries matching
+/// `exclusions` are kept. Relative paths, `/` and the home directory itself
+/// are refused.
+pub fn clean_paths(paths: &[PathBuf], exclusions: &[String]) -> Result<u64> {
+    let mut freed = 0u64;
+    
+    for path in paths {
+        ensure_cleanable(path)?;
+        
+        let Ok(metadata) = path.symlink_metadata() else {
+            continue;
+        };
+        
+        freed += if metadata.is_dir() {
+            remove_contents(path, exclusions)?.0
+        } else {
+            remove_tree(path, exclusions)?.0
+        };
+    }
+    
+    Ok(freed)
+}
+
+/// Refuse relative paths, `/` and the home directory itself
+fn ensure_cleanable(path: &Path) -> Result<()> {
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+    if !path.is_absolute() || path == Path::new("/") || path == home {
+        anyhow::bail!("Refusing to clean {}", path.display());
     }
+    Ok(())
 }
 
 /// Format bytes into human-readable size string
@@ -295,3 +1414,103 @@ pub fn format_size(bytes: u64) -> String {
         format!("{} bytes", bytes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const DPKG_KERNELS: &str = "\
+linux-headers-6.5.0-14-generic\t12000\tii 
+linux-headers-6.5.0-21-generic\t12100\tii 
+linux-headers-6.8.0-31-generic\t12500\tii 
+linux-headers-generic\t20\tii 
+linux-image-6.5.0-14-generic\t14000\tii 
+linux-image-6.5.0-21-generic\t14100\tii 
+linux-image-6.8.0-31-generic\t14500\tii 
+linux-image-6.2.0-39-generic\t13800\trc 
+linux-image-generic\t20\tii 
+";
+    
+    #[test]
+    fn kernel_version_key_orders_releases_numerically() {
+        assert_eq!(kernel_version_key("6.5.0-14-generic"), Some(vec![6, 5, 0, 14]));
+        assert!(kernel_version_key("6.10.0-1-generic") > kernel_version_key("6.9.0-40-generic"));
+        assert!(kernel_version_key("6.5.0-21-generic") > kernel_version_key("6.5.0-14-generic"));
+        assert_eq!(kernel_version_key("generic"), None);
+        assert_eq!(kernel_version_key("generic-hwe-22.04"), None);
+        assert_eq!(kernel_version_key(""), None);
+    }
+    
+    #[test]
+    fn select_old_kernels_keeps_running_and_newest() {
+        let old = select_old_kernels(DPKG_KERNELS, "6.5.0-21-generic");
+        let names: Vec<&str> = old.iter().map(|(name, _)| name.as_str()).collect();
+        
+        // Meta packages, removed-but-configured kernels, the running 6.5.0-21
+        // and the newest 6.8.0-31 all stay
+        assert_eq!(names, ["linux-headers-6.5.0-14-generic", "linux-image-6.5.0-14-generic"]);
+        assert_eq!(old[0].1, 12000 * 1024);
+    }
+    
+    #[test]
+    fn select_old_kernels_running_newest_removes_all_others() {
+        let old = select_old_kernels(DPKG_KERNELS, "6.8.0-31-generic");
+        let names: Vec<&str> = old.iter().map(|(name, _)| name.as_str()).collect();
+        
+        assert_eq!(names, [
+            "linux-headers-6.5.0-14-generic",
+            "linux-headers-6.5.0-21-generic",
+            "linux-image-6.5.0-14-generic",
+            "linux-image-6.5.0-21-generic",
+        ]);
+    }
+    
+    /// A fresh directory for one test, removed again by the caller
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sysmate-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+    
+    fn set_mtime(path: &Path, time: SystemTime) {
+        fs::File::open(path).unwrap().set_modified(time).unwrap();
+    }
+    
+    #[test]
+    fn select_temp_entries_targets_only_old_unused_entries() {
+        let dir = scratch_dir("temp-select");
+        let now = SystemTime::now();
+        let day_ago = now - Duration::from_secs(24 * 60 * 60);
+        
+        fs::write(dir.join("foo"), "old").unwrap();
+        set_mtime(&dir.join("foo"), day_ago);
+        fs::write(dir.join("fresh"), "new").unwrap();
+        
+        // An old directory with a fresh file inside is still in use
+        fs::create_dir(dir.join("busy")).unwrap();
+        fs::write(dir.join("busy/file"), "new").unwrap();
+        set_mtime(&dir.join("busy"), day_ago);
+        
+        // Session directories are kept however old
+        for name in ["systemd-private-abc-cups.service-x", ".X11-unix", "tmux-1000", "snap-private-tmp"] {
+            fs::create_dir(dir.join(name)).unwrap();
+            set_mtime(&dir.join(name), day_ago);
+        }
+        
+        fs::write(dir.join("open"), "old").unwrap();
+        set_mtime(&dir.join("open"), day_ago);
+        let open_paths = HashSet::from([dir.join("open")]);
+        
+        let selected = select_temp_entries(&dir, now, &open_paths);
+        fs::remove_dir_all(&dir).unwrap();
+        
+        assert_eq!(selected, [dir.join("foo")]);
+    }
+    
+    #[test]
+    fn old_kernels_are_purged_by_name() {
+        let command = purge_kernels_command(vec!["linux-image-6.5.0-14-generic".to_string()]);
+        assert_eq!(command.argv(), ["apt-get", "-y", "purge", "linux-image-6.5.0-14-generic"]);
+    }
+}